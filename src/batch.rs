@@ -0,0 +1,256 @@
+use std::fs;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use anyhow::{bail, ensure, Context, Result};
+use itertools::Itertools;
+use jagua_rs::Instant;
+use log::{error, info, warn, Level};
+use rand::SeedableRng;
+use rand_xoshiro::Xoshiro256PlusPlus;
+use serde::Serialize;
+use sparrow::config::SparrowConfig;
+use sparrow::optimizer::optimize;
+use sparrow::util::ctrlc_terminator::CtrlCTerminator;
+use sparrow::util::io;
+use sparrow::util::jsonl_listener::JsonlProgressListener;
+use sparrow::util::io::{BatchCli, ExtContainer, ExtSPOutput, MainCli};
+use sparrow::util::lower_bound::LowerBound;
+use sparrow::util::run_recorder::RunRecorder;
+use sparrow::util::terminator::StopCriteria;
+use sparrow::EPOCH;
+
+/// Outcome of a single instance in batch mode
+#[derive(Serialize, Clone, Debug)]
+pub struct BatchEntry {
+    pub input: String,
+    pub name: Option<String>,
+    pub success: bool,
+    pub rng_seed: Option<u64>,
+    pub strip_width: Option<f32>,
+    pub strip_height: Option<f32>,
+    pub density: Option<f32>,
+    pub lower_bound: Option<f32>,
+    /// Gap between the objective and the lower bound, in percent
+    pub gap: Option<f32>,
+    /// Wall time of the run in seconds
+    pub time: f64,
+    pub error: Option<String>,
+}
+
+impl BatchEntry {
+    fn failed(input: &Path, error: String, time: f64) -> Self {
+        BatchEntry {
+            input: input.display().to_string(),
+            name: None,
+            success: false,
+            rng_seed: None,
+            strip_width: None,
+            strip_height: None,
+            density: None,
+            lower_bound: None,
+            gap: None,
+            time,
+            error: Some(error),
+        }
+    }
+}
+
+/// Optimizes all instances matched by the batch arguments. Results of every instance are written to
+/// `{output_dir}/{stem}/`, where `stem` is the file stem of the input (suffixed with its index if several inputs share it),
+/// a summary of all runs to `{output_dir}/batch_summary.{csv,json}`.
+/// A failing instance is recorded in the summary and does not stop the batch.
+pub fn run(args: &MainCli, batch_args: &BatchCli, mut config: SparrowConfig, stop_criteria: &StopCriteria, output_dir: &Path, terminator: CtrlCTerminator) -> Result<()> {
+    if config.bin_packing.is_some() || config.knapsack.is_some() {
+        bail!("batch mode only supports the default (single container) mode");
+    }
+    ensure!(batch_args.jobs > 0, "the number of concurrent jobs must be positive");
+
+    let inputs = resolve_inputs(&batch_args.inputs)?;
+    let instance_dirs = instance_dirs(&inputs).into_iter().map(|d| output_dir.join(d)).collect_vec();
+    let n_jobs = batch_args.jobs.min(inputs.len());
+
+    // Divide the workers over the concurrent runs
+    for sep_config in [&mut config.expl_cfg.separator_config, &mut config.cmpr_cfg.separator_config] {
+        sep_config.n_workers = usize::max(1, sep_config.n_workers / n_jobs);
+    }
+    info!("[BATCH] optimizing {} instances, {} at a time ({} exploration workers each)", inputs.len(), n_jobs, config.expl_cfg.separator_config.n_workers);
+
+    // All runs write to the same progress stream, their events are labelled with the input
+    let progress_jsonl = crate::progress_jsonl(args, &config)?;
+
+    let next_input = AtomicUsize::new(0);
+    let entries = Mutex::new(vec![None; inputs.len()]);
+    thread::scope(|s| {
+        for job in 0..n_jobs {
+            let (inputs, instance_dirs, next_input, entries, config, terminator, progress_jsonl) = (&inputs, &instance_dirs, &next_input, &entries, &config, &terminator, &progress_jsonl);
+            thread::Builder::new()
+                .name(format!("batch-{job}"))
+                .spawn_scoped(s, move || loop {
+                    if terminator.aborted() {
+                        break;
+                    }
+                    let i = next_input.fetch_add(1, Ordering::SeqCst);
+                    let Some(input) = inputs.get(i) else { break };
+                    let jsonl_listener = progress_jsonl.as_ref().map(|p| p.for_input(&input.display().to_string()));
+                    let entry = run_guarded(args, input, config, stop_criteria, &instance_dirs[i], terminator.clone(), jsonl_listener);
+                    entries.lock().unwrap()[i] = Some(entry);
+                })
+                .expect("failed to spawn batch thread");
+        }
+    });
+    // Inputs can remain unprocessed if the batch was stopped by Ctrl-C
+    let entries = entries.into_inner().unwrap().into_iter()
+        .zip(inputs.iter())
+        .map(|(entry, input)| entry.unwrap_or_else(|| BatchEntry::failed(input, "skipped".to_string(), 0.0)))
+        .collect_vec();
+
+    let n_failed = entries.iter().filter(|e| !e.success).count();
+    match n_failed {
+        0 => info!("[BATCH] all {} instances finished successfully", entries.len()),
+        _ => warn!("[BATCH] {}/{} instances failed", n_failed, entries.len()),
+    }
+
+    io::write_json(&entries, &output_dir.join("batch_summary.json"), Level::Info)?;
+    write_summary_csv(&entries, &output_dir.join("batch_summary.csv"))?;
+    Ok(())
+}
+
+/// All instance files in a directory, or matching a glob pattern
+fn resolve_inputs(pattern: &str) -> Result<Vec<PathBuf>> {
+    let path = Path::new(pattern);
+    let mut inputs = match path.is_dir() {
+        true => fs::read_dir(path)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json" || ext == "svg"))
+            .collect_vec(),
+        false => glob::glob(pattern)
+            .context("invalid glob pattern")?
+            .filter_map(|p| p.ok())
+            .collect_vec(),
+    };
+    inputs.sort();
+    ensure!(!inputs.is_empty(), "no instances found for {pattern}");
+    Ok(inputs)
+}
+
+/// Name of the output directory of every input: its file stem, suffixed with its index if several inputs share the stem
+fn instance_dirs(inputs: &[PathBuf]) -> Vec<String> {
+    let stems = inputs.iter()
+        .map(|input| input.file_stem().map_or("instance".to_string(), |s| s.to_string_lossy().to_string()))
+        .collect_vec();
+    let counts = stems.iter().counts();
+    stems.iter().enumerate()
+        .map(|(i, stem)| match counts[stem] {
+            1 => stem.clone(),
+            _ => format!("{stem}_{i}"),
+        })
+        .collect()
+}
+
+/// Runs a single instance, turning errors and panics into a failed entry
+fn run_guarded(args: &MainCli, input: &Path, config: &SparrowConfig, stop_criteria: &StopCriteria, instance_dir: &Path, terminator: CtrlCTerminator, jsonl_listener: Option<JsonlProgressListener>) -> BatchEntry {
+    let start = Instant::now();
+    let failed = |error: String| {
+        error!("[BATCH] {} failed: {}", input.display(), error);
+        BatchEntry::failed(input, error, start.elapsed().as_secs_f64())
+    };
+
+    match catch_unwind(AssertUnwindSafe(|| run_instance(args, input, config, stop_criteria, instance_dir, terminator, jsonl_listener))) {
+        Ok(Ok(entry)) => entry,
+        Ok(Err(e)) => failed(format!("{e:#}")),
+        Err(panic) => {
+            let msg = panic.downcast_ref::<&str>().map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            failed(format!("panicked: {msg}"))
+        }
+    }
+}
+
+fn run_instance(args: &MainCli, input: &Path, config: &SparrowConfig, stop_criteria: &StopCriteria, instance_dir: &Path, terminator: CtrlCTerminator, jsonl_listener: Option<JsonlProgressListener>) -> Result<BatchEntry> {
+    let start = Instant::now();
+    let (ext_instance, ext_solution) = io::read_spp_input_with(input, config)?;
+
+    let instance = io::import_instance(&ext_instance, config)?;
+    let initial_solution = ext_solution.map(|e| jagua_rs::probs::spp::io::import_solution(&instance, &e));
+
+    let lower_bound = LowerBound::new(&ext_instance, &instance, config.container_mode)?;
+
+    let rng_seed = config.rng_seed.map_or_else(rand::random, |seed| seed as u64);
+    info!("[BATCH] starting {} (seed: {}, lower bound: {:.3})", ext_instance.name, rng_seed, lower_bound.bound);
+
+    let svg_path = instance_dir.join(format!("final_{}.svg", ext_instance.name));
+    let json_path = instance_dir.join(format!("final_{}.json", ext_instance.name));
+    let sols_dir = instance_dir.join(format!("sols_{}", ext_instance.name));
+
+    let svg_exporter = crate::svg_exporter(args, Some(svg_path.display().to_string()), sols_dir.display().to_string());
+    let (mut terminator, progress) = stop_criteria.build(terminator);
+    let mut listener = ((svg_exporter, jsonl_listener), (RunRecorder::new().with_lower_bound(Some(lower_bound)), progress));
+
+    let solution = optimize(
+        instance.clone(),
+        Xoshiro256PlusPlus::seed_from_u64(rng_seed),
+        &mut listener,
+        &mut terminator,
+        &config.expl_cfg,
+        &config.cmpr_cfg,
+        &config.tuning,
+        config.container_mode,
+        initial_solution.as_ref(),
+    )?;
+
+    let entry = BatchEntry {
+        input: input.display().to_string(),
+        name: Some(ext_instance.name.clone()),
+        success: true,
+        rng_seed: Some(rng_seed),
+        strip_width: Some(solution.strip_width()),
+        strip_height: Some(solution.strip.fixed_height),
+        density: Some(solution.density(&instance)),
+        lower_bound: Some(lower_bound.bound),
+        gap: Some(lower_bound.gap(&solution)),
+        time: start.elapsed().as_secs_f64(),
+        error: None,
+    };
+
+    let json_output = ExtSPOutput {
+        instance: ext_instance,
+        solution: jagua_rs::probs::spp::io::export(&instance, &solution, *EPOCH),
+        container: Some(ExtContainer::new(&solution, config.container_mode)),
+        metadata: Some(listener.1.0.metadata(rng_seed, *config)),
+    };
+    io::write_json(&json_output, &json_path, Level::Info)?;
+
+    info!("[BATCH] finished {} in {:.1}s, width: {:.3}, density: {:.3}%, gap: {:.3}%", input.display(), entry.time, solution.strip_width(), solution.density(&instance) * 100.0, lower_bound.gap(&solution));
+    Ok(entry)
+}
+
+fn write_summary_csv(entries: &[BatchEntry], path: &Path) -> Result<()> {
+    let fmt_opt = |v: Option<String>| v.unwrap_or_default();
+    let quote = |s: &str| format!("\"{}\"", s.replace('"', "\"\""));
+
+    let mut csv = String::from("input,name,success,rng_seed,strip_width,strip_height,density,lower_bound,gap,time,error\n");
+    for e in entries {
+        csv.push_str(&[
+            quote(&e.input),
+            quote(e.name.as_deref().unwrap_or_default()),
+            e.success.to_string(),
+            fmt_opt(e.rng_seed.map(|s| s.to_string())),
+            fmt_opt(e.strip_width.map(|w| w.to_string())),
+            fmt_opt(e.strip_height.map(|h| h.to_string())),
+            fmt_opt(e.density.map(|d| d.to_string())),
+            fmt_opt(e.lower_bound.map(|lb| lb.to_string())),
+            fmt_opt(e.gap.map(|g| g.to_string())),
+            format!("{:.3}", e.time),
+            quote(e.error.as_deref().unwrap_or_default()),
+        ].join(","));
+        csv.push('\n');
+    }
+    fs::write(path, csv).context("could not write batch summary csv")?;
+    info!("[BATCH] summary exported to file://{}", fs::canonicalize(path)?.display());
+    Ok(())
+}
@@ -14,13 +14,13 @@ use std::time::Duration;
 use jagua_rs::Instant;
 
 use anyhow::Result;
-use jagua_rs::io::import::Importer;
 use jagua_rs::io::svg::s_layout_to_svg;
 use rand_xoshiro::Xoshiro256PlusPlus;
-use sparrow::consts::{DEFAULT_COMPRESS_TIME_RATIO, DEFAULT_EXPLORE_TIME_RATIO, DRAW_OPTIONS, LBF_SAMPLE_CONFIG};
+use sparrow::consts::{DEFAULT_COMPRESS_TIME_RATIO, DEFAULT_EXPLORE_TIME_RATIO, DRAW_OPTIONS};
 use sparrow::optimizer::compress::compression_phase;
 use sparrow::optimizer::explore::exploration_phase;
 use sparrow::util::listener::DummySolListener;
+use sparrow::util::lower_bound::LowerBound;
 use sparrow::util::terminator::BasicTerminator;
 
 pub const OUTPUT_DIR: &str = "output";
@@ -66,8 +66,9 @@ fn main() -> Result<()> {
         ext_instance.name, n_batches, n_runs_per_iter, num_cpus::get_physical(), time_limit
     );
 
-    let importer = Importer::new(config.cde_config, config.poly_simpl_tolerance, config.min_item_separation, config.narrow_concavity_cutoff_ratio);
-    let instance = jagua_rs::probs::spp::io::import_instance(&importer, &ext_instance)?;
+    let instance = io::import_instance(&ext_instance, &config)?;
+    let lower_bound = LowerBound::new(&ext_instance, &instance, config.container_mode)?;
+    println!("[BENCH] lower bound: {:.3} (item area: {:.3}, item width: {:.3})", lower_bound.bound, lower_bound.item_area, lower_bound.item_width);
 
     let mut final_solutions = vec![];
 
@@ -85,25 +86,29 @@ fn main() -> Result<()> {
 
                 s.spawn(move |_| {
                     let mut next_rng = || Xoshiro256PlusPlus::seed_from_u64(rng.next_u64());
-                    let builder = LBFBuilder::new(instance.clone(), next_rng(), LBF_SAMPLE_CONFIG).construct();
-                    let mut expl_separator = Separator::new(builder.instance, builder.prob, next_rng(), config.expl_cfg.separator_config);
+                    let builder = LBFBuilder::new(instance.clone(), next_rng(), config.tuning).construct()
+                        .unwrap_or_else(|e| panic!("[BENCH] [id:{bench_idx:>3}] construction failed: {e}"));
+                    let mut expl_separator = Separator::new(builder.instance, builder.prob, next_rng(), config.expl_cfg.separator_config, config.tuning, config.container_mode);
 
                     terminator.new_timeout(config.expl_cfg.time_limit);
-                    let solutions = exploration_phase(&instance, &mut expl_separator, &mut DummySolListener, &terminator, &config.expl_cfg);
+                    let solutions = exploration_phase(&instance, &mut expl_separator, &mut DummySolListener, &terminator, &config.expl_cfg)
+                        .unwrap_or_else(|e| panic!("[BENCH] [id:{bench_idx:>3}] exploration failed: {e}"));
                     let final_explore_sol = solutions.last().expect("no solutions found during exploration");
 
                     let start_comp = Instant::now();
 
                     terminator.new_timeout(config.cmpr_cfg.time_limit);
-                    let mut cmpr_separator = Separator::new(expl_separator.instance, expl_separator.prob, next_rng(), config.cmpr_cfg.separator_config);
-                    let cmpr_sol = compression_phase(&instance, &mut cmpr_separator, final_explore_sol, &mut DummySolListener, &terminator, &config.cmpr_cfg);
+                    let mut cmpr_separator = Separator::new(expl_separator.instance, expl_separator.prob, next_rng(), config.cmpr_cfg.separator_config, config.tuning, config.container_mode);
+                    let cmpr_sol = compression_phase(&instance, &mut cmpr_separator, final_explore_sol, &mut DummySolListener, &terminator, &config.cmpr_cfg)
+                        .unwrap_or_else(|e| panic!("[BENCH] [id:{bench_idx:>3}] compression failed: {e}"));
 
-                    println!("[BENCH] [id:{:>3}] finished, expl: {:.3}% ({}s), cmpr: {:.3}% (+{:.3}%) ({}s)",
+                    println!("[BENCH] [id:{:>3}] finished, expl: {:.3}% ({}s), cmpr: {:.3}% (+{:.3}%) ({}s), gap: {:.3}%",
                              bench_idx,
                              final_explore_sol.density(&instance) * 100.0, time_limit.mul_f32(DEFAULT_EXPLORE_TIME_RATIO).as_secs(),
                              cmpr_sol.density(&instance) * 100.0,
                              cmpr_sol.density(&instance) * 100.0 - final_explore_sol.density(&instance) * 100.0,
-                             start_comp.elapsed().as_secs()
+                             start_comp.elapsed().as_secs(),
+                             lower_bound.gap(&cmpr_sol)
                     );
 
                     io::write_svg(
@@ -128,6 +133,7 @@ fn main() -> Result<()> {
             (width, usage * 100.0)
         })
         .unzip();
+    let final_gaps = final_solutions.iter().map(|s| lower_bound.gap(s)).collect::<Vec<f32>>();
 
     let best_final_solution = final_solutions.iter().max_by_key(|s| OrderedFloat(s.density(&instance))).unwrap();
 
@@ -141,6 +147,7 @@ fn main() -> Result<()> {
 
     println!("widths:\n{:?}", &final_widths);
     println!("usages:\n{:?}", &final_usages);
+    println!("gaps:\n{:?}", &final_gaps);
 
     println!("---- WIDTH STATS ----");
     println!("worst:  {:.3}", final_widths.iter().max_by_key(|&x| OrderedFloat(*x)).unwrap());
@@ -158,6 +165,14 @@ fn main() -> Result<()> {
     println!("best:   {:.3}", final_usages.iter().max_by_key(|&x| OrderedFloat(*x)).unwrap());
     println!("avg:    {:.3}", calculate_average(&final_usages));
     println!("stddev: {:.3}", calculate_stddev(&final_usages));
+    println!("---- GAP STATS (lower bound: {:.3}) ----", lower_bound.bound);
+    println!("worst:  {:.3}", final_gaps.iter().max_by_key(|&x| OrderedFloat(*x)).unwrap());
+    println!("25%:    {:.3}", calculate_percentile(&final_gaps, 0.75));
+    println!("med:    {:.3}", calculate_median(&final_gaps));
+    println!("75%:    {:.3}", calculate_percentile(&final_gaps, 0.25));
+    println!("best:   {:.3}", final_gaps.iter().min_by_key(|&x| OrderedFloat(*x)).unwrap());
+    println!("avg:    {:.3}", calculate_average(&final_gaps));
+    println!("stddev: {:.3}", calculate_stddev(&final_gaps));
     println!("======================");
     println!("[BENCH] system time: {}", jiff::Timestamp::now());
 
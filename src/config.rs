@@ -1,12 +1,25 @@
+use crate::consts::{CD_STEP_FAIL, CD_STEP_SUCCESS, GLS_WEIGHT_DECAY, GLS_WEIGHT_MAX_INC_RATIO, GLS_WEIGHT_MIN_INC_RATIO, LBF_SAMPLE_CONFIG, OVERLAP_PROXY_EPSILON_DIAM_RATIO, PRE_REFINE_CD_R_STEPS, PRE_REFINE_CD_TL_RATIOS, SND_REFINE_CD_R_STEPS, SND_REFINE_CD_TL_RATIOS, UNIQUE_SAMPLE_THRESHOLD};
 use crate::optimizer::separator::SeparatorConfig;
 use crate::sample::search::SampleConfig;
+use anyhow::{ensure, Result};
 use jagua_rs::collision_detection::CDEConfig;
 use jagua_rs::geometry::fail_fast::SPSurrogateConfig;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SparrowConfig {
     pub rng_seed: Option<usize>,
+    /// Geometry of the container that is being minimized.
+    /// See [`ContainerMode`] for more details.
+    pub container_mode: ContainerMode,
+    /// Enables the multi-bin (bin packing) mode if set.
+    /// See [`BinPackingConfig`] for more details.
+    pub bin_packing: Option<BinPackingConfig>,
+    /// Enables the knapsack (placement maximization) mode if set.
+    /// See [`KnapsackConfig`] for more details.
+    pub knapsack: Option<KnapsackConfig>,
     pub expl_cfg: ExplorationConfig,
     pub cmpr_cfg: CompressionConfig,
     /// Configuration for the collision detection engine.
@@ -24,11 +37,24 @@ pub struct SparrowConfig {
     /// Disabled if `None`.
     /// See [`jagua_rs::io::parser::Parser::new`] for more details.
     pub narrow_concavity_cutoff_ratio: Option<f32>,
+    /// Low-level tuning parameters of the heuristic.
+    /// See [`TuningConfig`] for more details.
+    pub tuning: TuningConfig,
+    /// Import of item shapes from DXF files.
+    /// See [`DxfImportConfig`] for more details.
+    #[serde(default)]
+    pub dxf_import: DxfImportConfig,
+    /// Import of instances from SVG files.
+    /// See [`SvgImportConfig`] for more details.
+    #[serde(default)]
+    pub svg_import: SvgImportConfig,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ExplorationConfig {
     pub shrink_step: f32,
+    #[serde(with = "duration_secs")]
     pub time_limit: Duration,
     pub max_conseq_failed_attempts: Option<usize>,
     pub solution_pool_distribution_stddev: f32,
@@ -36,15 +62,156 @@ pub struct ExplorationConfig {
     pub large_item_ch_area_cutoff_percentile: f32
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct CompressionConfig {
     pub shrink_range: (f32, f32),
+    #[serde(with = "duration_secs")]
     pub time_limit: Duration,
     pub shrink_decay: ShrinkDecayStrategy,
     pub separator_config: SeparatorConfig,
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Defines which dimensions of the container are variable and how they relate to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerMode {
+    /// Classic strip packing: the height is fixed by the instance and only the width is minimized
+    Strip,
+    /// Width and height are kept equal and minimized together
+    Square,
+    /// The width/height ratio of the container is kept fixed, both are minimized together
+    FixedAspectRatio(f32),
+    /// Width and height are shrunk independently, minimizing the area of the container
+    FreeRectangle,
+}
+
+impl ContainerMode {
+    /// Returns the height of the container when its width is changed to `width`, given its current `height`.
+    pub fn height_for_width(&self, width: f32, height: f32) -> f32 {
+        match self {
+            ContainerMode::Strip | ContainerMode::FreeRectangle => height,
+            ContainerMode::Square => width,
+            ContainerMode::FixedAspectRatio(ratio) => width / ratio,
+        }
+    }
+
+    /// Returns the smallest container dimensions allowed by the mode that still enclose a `width` x `height` container.
+    pub fn enclosing_dims(&self, width: f32, height: f32) -> (f32, f32) {
+        match self {
+            ContainerMode::Strip | ContainerMode::FreeRectangle => (width, height),
+            ContainerMode::Square => {
+                let side = f32::max(width, height);
+                (side, side)
+            }
+            ContainerMode::FixedAspectRatio(ratio) => {
+                let width = f32::max(width, height * ratio);
+                (width, width / ratio)
+            }
+        }
+    }
+
+    /// The objective being minimized for a container of the given dimensions.
+    pub fn objective(&self, width: f32, height: f32) -> f32 {
+        match self {
+            ContainerMode::FreeRectangle => width * height,
+            _ => width,
+        }
+    }
+}
+
+/// Configuration of the multi-bin (bin packing) mode.
+/// Items are spread over bins of a fixed size, the number of bins is minimized first, then the fill of the last bin.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BinPackingConfig {
+    /// Width of a bin, its height is the `strip_height` of the instance
+    pub bin_width: f32,
+    /// Number of times colliding items are migrated to other bins before an attempt to eliminate a bin is abandoned
+    pub max_migration_rounds: usize,
+}
+
+/// Configuration of the knapsack (placement maximization) mode.
+/// The container is fixed and items are optional: the total value of the placed items is maximized.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KnapsackConfig {
+    /// Width of the container, its height is the `strip_height` of the instance
+    pub container_width: f32,
+    /// Standard deviation of the (half) normal distribution used to select the next item to insert, relative to the number of candidates
+    pub insertion_distribution_stddev: f32,
+}
+
+/// Configuration of the import of item shapes from DXF files (items with a `dxf` field instead of a `shape`)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DxfImportConfig {
+    /// Maximum deviation between an arc (or spline) and its polygonal approximation, in the units of the DXF file
+    pub flatten_tolerance: f32,
+    /// Maximum distance between the endpoints of two entities to chain them into the same contour, in the units of the DXF file
+    pub join_tolerance: f32,
+    /// Units to convert the coordinates to, based on the `$INSUNITS` header of every file.
+    /// Coordinates are left untouched if `None`, or if a file does not specify its units.
+    pub units: Option<DxfUnits>,
+}
+
+impl Default for DxfImportConfig {
+    fn default() -> Self {
+        DEFAULT_DXF_IMPORT_CONFIG
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DxfUnits {
+    Inches,
+    Feet,
+    Millimeters,
+    Centimeters,
+    Meters,
+}
+
+impl DxfUnits {
+    /// Length of one unit in millimeters
+    pub fn in_mm(self) -> f64 {
+        match self {
+            DxfUnits::Inches => 25.4,
+            DxfUnits::Feet => 304.8,
+            DxfUnits::Millimeters => 1.0,
+            DxfUnits::Centimeters => 10.0,
+            DxfUnits::Meters => 1000.0,
+        }
+    }
+
+    /// Units of a `$INSUNITS` header value, if supported
+    pub fn from_insunits(code: i32) -> Option<Self> {
+        match code {
+            1 => Some(DxfUnits::Inches),
+            2 => Some(DxfUnits::Feet),
+            4 => Some(DxfUnits::Millimeters),
+            5 => Some(DxfUnits::Centimeters),
+            6 => Some(DxfUnits::Meters),
+            _ => None,
+        }
+    }
+}
+
+/// Configuration of the import of instances from SVG files (one element per item)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SvgImportConfig {
+    /// Maximum deviation between a curve (Bézier or arc) and its polygonal approximation, in the user units of the SVG file
+    pub flatten_tolerance: f32,
+}
+
+impl Default for SvgImportConfig {
+    fn default() -> Self {
+        DEFAULT_SVG_IMPORT_CONFIG
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ShrinkDecayStrategy {
     /// The shrink ratio decays linearly with time
     TimeBased,
@@ -52,8 +219,152 @@ pub enum ShrinkDecayStrategy {
     FailureBased(f32),
 }
 
+/// Low-level tuning parameters, defaults are defined in [`crate::consts`].
+/// Passed to the optimizer alongside the phase configurations, see [`crate::optimizer::optimize`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TuningConfig {
+    /// Maximum multiplier of a GLS weight for the most severe collision
+    pub gls_weight_max_inc_ratio: f32,
+    /// Minimum multiplier of a GLS weight for a colliding pair
+    pub gls_weight_min_inc_ratio: f32,
+    /// Multiplier of a GLS weight for a pair which is no longer colliding
+    pub gls_weight_decay: f32,
+    /// Epsilon of the overlap proxy, as a ratio of the largest diameter of the two shapes
+    pub overlap_proxy_epsilon_diam_ratio: f32,
+    /// Coordinate descent step multiplier on success
+    pub cd_step_success: f32,
+    /// Coordinate descent step multiplier on failure
+    pub cd_step_fail: f32,
+    /// Initial and limit translation step of the first refinement, as a ratio of the item's min dimension
+    pub pre_refine_cd_tl_ratios: (f32, f32),
+    /// Initial and limit rotation step (in radians) of the first refinement
+    pub pre_refine_cd_r_steps: (f32, f32),
+    /// Initial and limit translation step of the second (final) refinement, as a ratio of the item's min dimension
+    pub snd_refine_cd_tl_ratios: (f32, f32),
+    /// Initial and limit rotation step (in radians) of the second (final) refinement
+    pub snd_refine_cd_r_steps: (f32, f32),
+    /// Samples closer than this ratio of the item's min dimension are considered duplicates
+    pub unique_sample_threshold: f32,
+    /// Sample configuration of the LBF constructor and item insertions
+    pub lbf_sample_config: SampleConfig,
+}
+
+pub const DEFAULT_TUNING_CONFIG: TuningConfig = TuningConfig {
+    gls_weight_max_inc_ratio: GLS_WEIGHT_MAX_INC_RATIO,
+    gls_weight_min_inc_ratio: GLS_WEIGHT_MIN_INC_RATIO,
+    gls_weight_decay: GLS_WEIGHT_DECAY,
+    overlap_proxy_epsilon_diam_ratio: OVERLAP_PROXY_EPSILON_DIAM_RATIO,
+    cd_step_success: CD_STEP_SUCCESS,
+    cd_step_fail: CD_STEP_FAIL,
+    pre_refine_cd_tl_ratios: PRE_REFINE_CD_TL_RATIOS,
+    pre_refine_cd_r_steps: PRE_REFINE_CD_R_STEPS,
+    snd_refine_cd_tl_ratios: SND_REFINE_CD_TL_RATIOS,
+    snd_refine_cd_r_steps: SND_REFINE_CD_R_STEPS,
+    unique_sample_threshold: UNIQUE_SAMPLE_THRESHOLD,
+    lbf_sample_config: LBF_SAMPLE_CONFIG,
+};
+
+impl SparrowConfig {
+    /// Largest dimensions of the container during optimization, `None` if a dimension can grow without bound.
+    /// The height of the strip is fixed, unless the container mode ties it to the width.
+    pub fn max_container_dims(&self, strip_height: f32) -> (Option<f32>, Option<f32>) {
+        let width = self.bin_packing.map(|b| b.bin_width)
+            .or(self.knapsack.map(|k| k.container_width));
+        let height = match (width, self.container_mode) {
+            (Some(_), _) | (None, ContainerMode::Strip | ContainerMode::FreeRectangle) => Some(strip_height),
+            (None, ContainerMode::Square | ContainerMode::FixedAspectRatio(_)) => None,
+        };
+        (width, height)
+    }
+
+    /// Checks whether all parameters are within their valid ranges.
+    pub fn validate(&self) -> Result<()> {
+        if let ContainerMode::FixedAspectRatio(ratio) = self.container_mode {
+            ensure!(ratio > 0.0, "container_mode: aspect ratio must be positive, got {ratio}");
+        }
+        if let Some(bin_cfg) = &self.bin_packing {
+            ensure!(bin_cfg.bin_width > 0.0, "bin_packing.bin_width must be positive, got {}", bin_cfg.bin_width);
+            ensure!(self.knapsack.is_none(), "bin_packing and knapsack are mutually exclusive");
+        }
+        if let Some(knapsack_cfg) = &self.knapsack {
+            ensure!(knapsack_cfg.container_width > 0.0, "knapsack.container_width must be positive, got {}", knapsack_cfg.container_width);
+            ensure!(knapsack_cfg.insertion_distribution_stddev > 0.0, "knapsack.insertion_distribution_stddev must be positive");
+        }
+
+        let expl = &self.expl_cfg;
+        ensure!(expl.shrink_step > 0.0 && expl.shrink_step < 1.0, "expl_cfg.shrink_step must be in ]0, 1[, got {}", expl.shrink_step);
+        ensure!(expl.solution_pool_distribution_stddev > 0.0, "expl_cfg.solution_pool_distribution_stddev must be positive");
+        ensure!((0.0..=1.0).contains(&expl.large_item_ch_area_cutoff_percentile), "expl_cfg.large_item_ch_area_cutoff_percentile must be in [0, 1]");
+        expl.separator_config.validate().map_err(|e| e.context("expl_cfg.separator_config"))?;
+
+        let cmpr = &self.cmpr_cfg;
+        let (shrink_max, shrink_min) = cmpr.shrink_range;
+        ensure!(0.0 < shrink_min && shrink_min <= shrink_max && shrink_max < 1.0, "cmpr_cfg.shrink_range must satisfy 0 < min <= max < 1, got {:?}", cmpr.shrink_range);
+        if let ShrinkDecayStrategy::FailureBased(ratio) = cmpr.shrink_decay {
+            ensure!(ratio > 0.0 && ratio < 1.0, "cmpr_cfg.shrink_decay: failure based decay ratio must be in ]0, 1[, got {ratio}");
+        }
+        cmpr.separator_config.validate().map_err(|e| e.context("cmpr_cfg.separator_config"))?;
+
+        ensure!(self.dxf_import.flatten_tolerance > 0.0, "dxf_import.flatten_tolerance must be positive");
+        ensure!(self.dxf_import.join_tolerance >= 0.0, "dxf_import.join_tolerance must be non-negative");
+        ensure!(self.svg_import.flatten_tolerance > 0.0, "svg_import.flatten_tolerance must be positive");
+
+        if let Some(tol) = self.poly_simpl_tolerance {
+            ensure!(tol >= 0.0, "poly_simpl_tolerance must be non-negative, got {tol}");
+        }
+        if let Some(sep) = self.min_item_separation {
+            ensure!(sep >= 0.0, "min_item_separation must be non-negative, got {sep}");
+        }
+        if let Some(ratio) = self.narrow_concavity_cutoff_ratio {
+            ensure!(ratio >= 0.0, "narrow_concavity_cutoff_ratio must be non-negative, got {ratio}");
+        }
+        self.tuning.validate().map_err(|e| e.context("tuning"))
+    }
+}
+
+impl TuningConfig {
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.gls_weight_min_inc_ratio >= 1.0, "gls_weight_min_inc_ratio must be at least 1");
+        ensure!(self.gls_weight_max_inc_ratio >= self.gls_weight_min_inc_ratio, "gls_weight_max_inc_ratio must be at least gls_weight_min_inc_ratio");
+        ensure!(self.gls_weight_decay > 0.0 && self.gls_weight_decay <= 1.0, "gls_weight_decay must be in ]0, 1]");
+        ensure!(self.overlap_proxy_epsilon_diam_ratio > 0.0, "overlap_proxy_epsilon_diam_ratio must be positive");
+        ensure!(self.cd_step_success >= 1.0, "cd_step_success must be at least 1");
+        ensure!(self.cd_step_fail > 0.0 && self.cd_step_fail < 1.0, "cd_step_fail must be in ]0, 1[");
+        for (name, (init, limit)) in [
+            ("pre_refine_cd_tl_ratios", self.pre_refine_cd_tl_ratios),
+            ("pre_refine_cd_r_steps", self.pre_refine_cd_r_steps),
+            ("snd_refine_cd_tl_ratios", self.snd_refine_cd_tl_ratios),
+            ("snd_refine_cd_r_steps", self.snd_refine_cd_r_steps),
+        ] {
+            ensure!(0.0 < limit && limit <= init, "{name} must satisfy 0 < limit <= init, got ({init}, {limit})");
+        }
+        ensure!(self.unique_sample_threshold >= 0.0, "unique_sample_threshold must be non-negative");
+        ensure!(self.lbf_sample_config.n_container_samples > 0, "lbf_sample_config.n_container_samples must be positive");
+        Ok(())
+    }
+}
+
+/// (De)serializes a [`Duration`] as a (fractional) number of seconds
+mod duration_secs {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(duration.as_secs_f64())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let secs = f64::deserialize(deserializer)?;
+        Duration::try_from_secs_f64(secs).map_err(serde::de::Error::custom)
+    }
+}
+
 pub const DEFAULT_SPARROW_CONFIG: SparrowConfig = SparrowConfig {
     rng_seed: None,
+    container_mode: ContainerMode::Square,
+    bin_packing: None,
+    knapsack: None,
     expl_cfg: ExplorationConfig {
         shrink_step: 0.0015,
         time_limit: Duration::from_secs(9 * 60),
@@ -100,4 +411,17 @@ pub const DEFAULT_SPARROW_CONFIG: SparrowConfig = SparrowConfig {
     poly_simpl_tolerance: Some(0.0001),
     narrow_concavity_cutoff_ratio: Some(0.01),
     min_item_separation: None,
+    tuning: DEFAULT_TUNING_CONFIG,
+    dxf_import: DEFAULT_DXF_IMPORT_CONFIG,
+    svg_import: DEFAULT_SVG_IMPORT_CONFIG,
+};
+
+pub const DEFAULT_DXF_IMPORT_CONFIG: DxfImportConfig = DxfImportConfig {
+    flatten_tolerance: 0.01,
+    join_tolerance: 0.001,
+    units: None,
+};
+
+pub const DEFAULT_SVG_IMPORT_CONFIG: SvgImportConfig = SvgImportConfig {
+    flatten_tolerance: 0.01,
 };
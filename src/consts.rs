@@ -1,5 +1,6 @@
 use jagua_rs::io::svg::{SvgDrawOptions, SvgLayoutTheme};
 use crate::sample::search::SampleConfig;
+use std::time::Duration;
 
 pub const GLS_WEIGHT_MAX_INC_RATIO: f32 = 2.0;
 pub const GLS_WEIGHT_MIN_INC_RATIO: f32 = 1.2;
@@ -35,6 +36,13 @@ pub const DEFAULT_MAX_CONSEQ_FAILS_EXPL: usize = 10;
 
 pub const DEFAULT_FAIL_DECAY_RATIO_CMPR: f32 = 0.9;
 
+pub const DEFAULT_MAX_MIGRATION_ROUNDS: usize = 5;
+
+pub const DEFAULT_KNAPSACK_INSERTION_STDDEV: f32 = 0.1;
+
+/// Fraction of the compression time limit spent shrinking the last bin in the exploration phase (multi-bin mode)
+pub const LAST_BIN_EXPLORE_TIME_RATIO: f32 = 0.5;
+
 pub const LOG_LEVEL_FILTER_RELEASE: log::LevelFilter = log::LevelFilter::Info;
 
 pub const LOG_LEVEL_FILTER_DEBUG: log::LevelFilter = log::LevelFilter::Debug;
@@ -52,4 +60,28 @@ pub const LBF_SAMPLE_CONFIG: SampleConfig = SampleConfig {
     n_container_samples: 1000,
     n_focussed_samples: 0,
     n_coord_descents: 3,
-};
\ No newline at end of file
+};
+
+/// Interval between checkpoints (in seconds) when resuming without --checkpoint-interval
+pub const DEFAULT_CHECKPOINT_INTERVAL: u64 = 60;
+
+/// Minimum time between two checks for the existence of a stop file
+pub const STOP_FILE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Maximum time to wait for a client of the live viewer to accept an event, before dropping it
+pub const LIVE_SERVER_WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Maximum time to wait for the request of a client of the live viewer, before closing the connection
+pub const LIVE_SERVER_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Minimum time between two intermediate (infeasible or improving) solutions streamed to the live viewer
+pub const LIVE_SERVER_MIN_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Height of the item labels in exported DXF files, relative to the smallest dimension of the container
+pub const DXF_LABEL_HEIGHT_RATIO: f64 = 0.01;
+
+/// Default tolerance of the `verify` binary, relative to the height of the container
+pub const DEFAULT_VERIFY_TOLERANCE_RATIO: f64 = 1e-5;
+
+/// Minimum time between two intermediate (infeasible or improving) solutions written to the JSONL progress stream
+pub const JSONL_PROGRESS_MIN_INTERVAL: Duration = Duration::from_millis(500);
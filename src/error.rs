@@ -0,0 +1,39 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::util::listener::Phase;
+
+/// Failures which end an optimization run. They are returned instead of panicking,
+/// so library users can recover (e.g. by retrying with a larger container) and the CLI can report the cause.
+#[derive(Debug)]
+pub enum SparrowError {
+    /// The strip kept expanding during construction without finding a placement for the item,
+    /// it does not fit the container at any of its allowed rotations (or within its allowed area)
+    ItemDoesNotFit { item_id: usize, strip_width: f32 },
+    /// No placement of the item could be sampled within the container
+    NoPlacementFound { item_id: usize },
+    /// Two movable items to swap could not be chosen to disrupt the layout
+    DisruptionFailed { n_movable_items: usize },
+    /// A phase ended without any feasible solution
+    NoFeasibleSolution { phase: Phase },
+    /// A solution listener failed to process a report, e.g. because an output file could not be written
+    Listener(anyhow::Error),
+}
+
+impl Display for SparrowError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SparrowError::ItemDoesNotFit { item_id, strip_width } =>
+                write!(f, "item {item_id} does not fit the container (strip width expanded to {strip_width:.3}), check its size, allowed rotations and allowed area"),
+            SparrowError::NoPlacementFound { item_id } =>
+                write!(f, "no placement could be sampled for item {item_id} within the container"),
+            SparrowError::DisruptionFailed { n_movable_items } =>
+                write!(f, "failed to choose two items to disrupt the layout ({n_movable_items} movable items)"),
+            SparrowError::NoFeasibleSolution { phase } =>
+                write!(f, "the {phase:?} phase ended without a feasible solution"),
+            SparrowError::Listener(e) =>
+                write!(f, "solution listener failed: {e:#}"),
+        }
+    }
+}
+
+impl Error for SparrowError {}
@@ -160,9 +160,9 @@ impl<'a> SpecializedHazardCollector<'a> {
                 let other_shape = &self.layout.placed_items[*other_pk].shape;
 
                 #[cfg(not(feature = "simd"))]
-                let loss = quantify_collision_poly_poly(other_shape, shape);
+                let loss = quantify_collision_poly_poly(other_shape, shape, self.ct.tuning.overlap_proxy_epsilon_diam_ratio);
                 #[cfg(feature = "simd")]
-                let loss = quantify_collision_poly_poly_simd(other_shape, shape, &self.poles_soa);
+                let loss = quantify_collision_poly_poly_simd(other_shape, shape, &self.poles_soa, self.ct.tuning.overlap_proxy_epsilon_diam_ratio);
 
                 let weight = self.ct.get_pair_weight(self.current_pk, *other_pk);
                 loss * weight
@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use anyhow::Result;
+use itertools::Itertools;
+use jagua_rs::entities::Instance;
+use jagua_rs::geometry::geo_enums::RotationRange;
+use log::{info, warn};
+use sparrow::config::SparrowConfig;
+use sparrow::util::inspect::inspect_instance;
+use sparrow::util::io;
+use sparrow::util::io::InspectCli;
+
+/// Logs the shape complexity, constraints and lower bound of an instance, and warns about constraints which cannot be satisfied
+pub fn run(inspect_args: &InspectCli, config: &SparrowConfig) -> Result<()> {
+    let (ext_instance, _) = io::read_spp_input_with(Path::new(&inspect_args.instance), config)?;
+    // fixed placements which cannot be satisfied are reported instead of rejected
+    let instance = io::import_instance_unchecked(&ext_instance, config)?;
+    let report = inspect_instance(&ext_instance, &instance, config)?;
+
+    let fmt_dim = |d: Option<f32>| d.map_or("unbounded".to_string(), |d| format!("{d:.3}"));
+    info!("[INSPECT] instance {}: {} items, total demand {}", report.name, report.items.len(), report.total_demand);
+    info!("[INSPECT] container mode: {:?}, max container: {} x {}", config.container_mode, fmt_dim(report.max_container_dims.0), fmt_dim(report.max_container_dims.1));
+    info!("[INSPECT] poly_simpl_tolerance: {:?}", config.poly_simpl_tolerance);
+
+    for item in &report.items {
+        let rotations = match &item.allowed_rotation {
+            RotationRange::None => "none".to_string(),
+            RotationRange::Continuous => "continuous".to_string(),
+            RotationRange::Discrete(rotations) => rotations.iter().map(|r| format!("{:.1}°", r.to_degrees())).join(", "),
+        };
+        let fixed = item.fixed_placement.map_or("-".to_string(), |d_transf| format!("[{d_transf}]"));
+        info!(
+            "[INSPECT] item {:>3}: demand {:>3}, vertices {:>4} -> {:>4}, convex hull ratio {:.3}, poles {:>3}, rotations: {}, fixed: {}",
+            item.id, item.demand, item.n_vertices, item.n_vertices_simplified, item.convex_hull_ratio, item.n_poles, rotations, fixed
+        );
+    }
+    let n_vertices = report.items.iter().map(|i| i.n_vertices * i.demand).sum::<usize>();
+    let n_vertices_simplified = report.items.iter().map(|i| i.n_vertices_simplified * i.demand).sum::<usize>();
+    info!("[INSPECT] total vertices (including demand): {} -> {} after simplification", n_vertices, n_vertices_simplified);
+    info!("[INSPECT] fixed items: {}", instance.items().filter(|i| i.fixed_placement.is_some()).count());
+
+    let lb = &report.lower_bound;
+    info!("[INSPECT] item area: {:.3}, lower bound: {:.3} (item width: {:.3})", lb.item_area, lb.bound, lb.item_width);
+
+    for warning in &report.warnings {
+        warn!("[INSPECT] {warning}");
+    }
+    if report.warnings.is_empty() {
+        info!("[INSPECT] no problems found");
+    }
+    Ok(())
+}
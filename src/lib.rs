@@ -13,6 +13,7 @@ pub mod util;
 pub mod config;
 pub mod eval;
 pub mod consts;
+pub mod error;
 
 pub static EPOCH: LazyLock<Instant> = LazyLock::new(Instant::now);
 
@@ -23,6 +24,7 @@ static FMT: fn() -> Formatter = || -> Formatter {
 };
 
 
+// The SVG features only determine the default of the runtime `--svg` option, see [`util::io::SvgMode`]
 #[cfg(feature = "live_svg")]
 pub const EXPORT_LIVE_SVG: bool = true;
 
@@ -34,6 +36,3 @@ pub const EXPORT_ONLY_FINAL_SVG: bool = true;
 
 #[cfg(not(feature = "only_final_svg"))]
 pub const EXPORT_ONLY_FINAL_SVG: bool = false;
-
-#[cfg(all(feature = "live_svg", feature = "only_final_svg"))]
-compile_error!("The features `live_svg` and `only_final_svg` are mutually exclusive.");
\ No newline at end of file
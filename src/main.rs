@@ -1,90 +1,121 @@
 extern crate core;
 
+mod batch;
+mod inspect;
+
 use clap::Parser as Clap;
-use log::{info, warn, Level};
-use rand::SeedableRng;
+use log::{error, info, warn, Level};
+use rand::{RngCore, SeedableRng};
 use sparrow::config::*;
-use sparrow::optimizer::optimize;
-use sparrow::util::io;
-use sparrow::util::io::{MainCli, ExtSPOutput};
+use sparrow::optimizer::{optimize, resume};
+use sparrow::optimizer::lbf::LBFBuilder;
+use sparrow::optimizer::multi_bin::optimize_bins;
+use sparrow::optimizer::knapsack::{optimize_knapsack, ItemValue};
+use sparrow::util::{dxf, io, placement_table};
+use sparrow::util::io::{MainCli, ExtContainer, ExtSPOutput, ExtMultiBinOutput, ContainerModeArg, MainCommand, SvgMode};
+use jagua_rs::io::svg::s_layout_to_svg;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
-use jagua_rs::io::import::Importer;
 use jagua_rs::entities::Instance;
+use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use jagua_rs::probs::spp::io::ext_repr::ExtSPInstance;
 use sparrow::EPOCH;
+use sparrow::error::SparrowError;
 
-use anyhow::{bail, Result};
+use anyhow::{bail, ensure, Result};
 use rand_xoshiro::Xoshiro256PlusPlus;
-use sparrow::consts::{DEFAULT_COMPRESS_TIME_RATIO, DEFAULT_EXPLORE_TIME_RATIO, DEFAULT_FAIL_DECAY_RATIO_CMPR, DEFAULT_MAX_CONSEQ_FAILS_EXPL, LOG_LEVEL_FILTER_DEBUG, LOG_LEVEL_FILTER_RELEASE};
+use sparrow::consts::{DEFAULT_CHECKPOINT_INTERVAL, DEFAULT_COMPRESS_TIME_RATIO, DEFAULT_EXPLORE_TIME_RATIO, DEFAULT_FAIL_DECAY_RATIO_CMPR, DEFAULT_MAX_CONSEQ_FAILS_EXPL, DEFAULT_KNAPSACK_INSERTION_STDDEV, DEFAULT_MAX_MIGRATION_ROUNDS, DRAW_OPTIONS, LOG_LEVEL_FILTER_DEBUG, LOG_LEVEL_FILTER_RELEASE};
 use sparrow::util::svg_exporter::SvgExporter;
+use sparrow::util::lower_bound::LowerBound;
+use sparrow::util::run_recorder::RunRecorder;
 use sparrow::util::ctrlc_terminator::CtrlCTerminator;
-
-pub const OUTPUT_DIR: &str = "output";
+use sparrow::util::terminator::StopCriteria;
+use sparrow::util::jsonl_listener::JsonlProgressListener;
+use sparrow::util::live_server::LiveServer;
+use sparrow::util::checkpoint::{read_checkpoint, Checkpointer};
 
 pub const LIVE_DIR: &str = "data/live";
 
 fn main() -> Result<()>{
-    let mut config = DEFAULT_SPARROW_CONFIG;
+    let args = MainCli::parse();
+
+    if args.dump_config {
+        let config = configure(&args)?;
+        println!("{}", serde_json::to_string_pretty(&config)?);
+        return Ok(());
+    }
 
-    fs::create_dir_all(OUTPUT_DIR)?;
-    let log_file_path = format!("{}/log.txt", OUTPUT_DIR);
+    let output_dir = args.output_dir.as_str();
+    fs::create_dir_all(output_dir)?;
+    let log_file_path = args.log_file.clone().unwrap_or(format!("{output_dir}/log.txt"));
+    // The progress stream on stdout must not be mixed with the log
+    let log_to_stderr = args.progress_jsonl.as_deref() == Some("-");
     match cfg!(debug_assertions) {
-        true => io::init_logger(LOG_LEVEL_FILTER_DEBUG, Path::new(&log_file_path))?,
-        false => io::init_logger(LOG_LEVEL_FILTER_RELEASE, Path::new(&log_file_path))?,
+        true => io::init_logger(LOG_LEVEL_FILTER_DEBUG, Path::new(&log_file_path), log_to_stderr)?,
+        false => io::init_logger(LOG_LEVEL_FILTER_RELEASE, Path::new(&log_file_path), log_to_stderr)?,
     }
 
-    let args = MainCli::parse();
-    let input_file_path = &args.input;
-    let (explore_dur, compress_dur) = match (args.global_time, args.exploration, args.compression) {
-        (Some(gt), None, None) => {
-            (Duration::from_secs(gt).mul_f32(DEFAULT_EXPLORE_TIME_RATIO), Duration::from_secs(gt).mul_f32(DEFAULT_COMPRESS_TIME_RATIO))
-        },
-        (None, Some(et), Some(ct)) => {
-            (Duration::from_secs(et), Duration::from_secs(ct))
-        },
-        (None, None, None) => {
-            warn!("[MAIN] no time limit specified");
-            (Duration::from_secs(600).mul_f32(DEFAULT_EXPLORE_TIME_RATIO), Duration::from_secs(600).mul_f32(DEFAULT_COMPRESS_TIME_RATIO))
-        },
-        _ => bail!("invalid cli pattern (clap should have caught this)"),
-    };
-    config.expl_cfg.time_limit = explore_dur;
-    config.cmpr_cfg.time_limit = compress_dur;
-    if args.early_termination {
-        config.expl_cfg.max_conseq_failed_attempts = Some(DEFAULT_MAX_CONSEQ_FAILS_EXPL);
-        config.cmpr_cfg.shrink_decay = ShrinkDecayStrategy::FailureBased(DEFAULT_FAIL_DECAY_RATIO_CMPR);
-        warn!("[MAIN] early termination enabled!");
+    if let Some(checkpoint_path) = &args.resume {
+        return resume_run(&args, Path::new(checkpoint_path), output_dir);
     }
-    if let Some(arg_rng_seed) = args.rng_seed {
-        config.rng_seed = Some(arg_rng_seed as usize);
+
+    let config = configure(&args)?;
+
+    if let Some(MainCommand::Batch(batch_args)) = &args.command {
+        return batch::run(&args, batch_args, config, &stop_criteria(&args)?, Path::new(output_dir), CtrlCTerminator::new());
+    }
+    if let Some(MainCommand::Inspect(inspect_args)) = &args.command {
+        return inspect::run(inspect_args, &config);
     }
 
-    info!("[MAIN] configured to explore for {}s and compress for {}s", explore_dur.as_secs(), compress_dur.as_secs());
+    let input_file_path = args.input.as_deref().expect("clap requires an input unless --dump-config or a subcommand is set");
 
-    let rng = match config.rng_seed {
+    info!("[MAIN] configured to explore for {}s and compress for {}s", config.expl_cfg.time_limit.as_secs(), config.cmpr_cfg.time_limit.as_secs());
+    info!("[MAIN] container mode: {:?}", config.container_mode);
+
+    let rng_seed = match config.rng_seed {
         Some(seed) => {
             info!("[MAIN] using seed: {}", seed);
-            Xoshiro256PlusPlus::seed_from_u64(seed as u64)
+            seed as u64
         },
         None => {
             let seed = rand::random();
             warn!("[MAIN] no seed provided, using: {}", seed);
-            Xoshiro256PlusPlus::seed_from_u64(seed)
+            seed
         }
     };
+    let mut rng = Xoshiro256PlusPlus::seed_from_u64(rng_seed);
 
     info!("[MAIN] system time: {}", jiff::Timestamp::now());
 
-    let (ext_instance, ext_solution) = io::read_spp_input(Path::new(&input_file_path))?;
+    let (ext_instance, ext_solution) = match &args.append {
+        None => io::read_spp_input_with(Path::new(&input_file_path), &config)?,
+        Some(append_path) => {
+            let (prev_instance, prev_solution) = io::read_spp_input_with(Path::new(&input_file_path), &config)?;
+            let Some(prev_solution) = prev_solution else {
+                bail!("--append requires the input to be a solution JSON file (e.g. final_{{name}}.json)");
+            };
+            let (new_instance, _) = io::read_spp_input_with(Path::new(append_path), &config)?;
+            info!("[MAIN] appending {} items of {} to the solution of {}", new_instance.items.len(), new_instance.name, prev_instance.name);
+            (io::merge_instances(&prev_instance, &new_instance), Some(prev_solution))
+        }
+    };
 
-    let importer = Importer::new(config.cde_config, config.poly_simpl_tolerance, config.min_item_separation, config.narrow_concavity_cutoff_ratio);
-    let instance = jagua_rs::probs::spp::io::import_instance(&importer, &ext_instance)?;
+    let instance = io::import_instance(&ext_instance, &config)?;
 
-    let initial_solution = ext_solution.map(|e|
+    let mut initial_solution = ext_solution.map(|e|
         jagua_rs::probs::spp::io::import_solution(&instance, &e)
     );
 
+    if args.append.is_some() {
+        // Pin the placements of the previous solution, the newly arrived items are placed around them during warm start
+        let prev_solution = initial_solution.take().unwrap();
+        let builder = LBFBuilder::new(instance.clone(), Xoshiro256PlusPlus::seed_from_u64(rng.next_u64()), config.tuning)
+            .restore_placements(&prev_solution, !args.soft_pin);
+        initial_solution = Some(builder.prob.save());
+    }
+
     info!("[MAIN] loaded instance {} with #{} items", ext_instance.name, instance.total_item_qty());
     
     let mut fixed_count = 0;
@@ -111,45 +142,340 @@ fn main() -> Result<()>{
     } else {
         warn!("[MAIN]   -> No fixed items detected. Solving from scratch.");
     }    
+    let lower_bound = lower_bound(&ext_instance, &instance, &config)?;
+    
+    let json_path = args.output_json.clone().unwrap_or(format!("{output_dir}/final_{}.json", ext_instance.name));
+    let svg_path = args.output_svg.clone().unwrap_or(format!("{output_dir}/final_{}.svg", ext_instance.name));
+
+    // In multi-bin mode, the final SVGs are written per bin
+    let final_svg_path = match config.bin_packing {
+        None => Some(svg_path.clone()),
+        Some(_) => None,
+    };
+    let svg_exporter = svg_exporter(&args, final_svg_path, format!("{output_dir}/sols_{}", ext_instance.name));
     
-    let mut svg_exporter = {
-        let final_svg_path = Some(format!("{OUTPUT_DIR}/final_{}.svg", ext_instance.name));
+    let ctrlc_terminator = CtrlCTerminator::new();
+    let (mut terminator, progress) = stop_criteria(&args)?.build(ctrlc_terminator.clone());
+    let checkpointer = match args.checkpoint_interval {
+        Some(_) if config.bin_packing.is_some() || config.knapsack.is_some() => {
+            warn!("[MAIN] checkpoints are only supported in the default (single container) mode, ignoring --checkpoint-interval");
+            None
+        }
+        Some(interval) => Some(Checkpointer::new(
+            Path::new(output_dir).join(format!("checkpoint_{}.json", ext_instance.name)),
+            Duration::from_secs(interval),
+            ext_instance.clone(),
+            config,
+            rng_seed,
+            Duration::ZERO,
+        )),
+        None => None,
+    };
+    let jsonl_listener = progress_jsonl(&args, &config)?;
+    let live_server = args.serve.map(LiveServer::start).transpose()?;
+    let mut listener = ((svg_exporter, (jsonl_listener, live_server)), (RunRecorder::new().with_lower_bound(lower_bound), (checkpointer, progress)));
 
-        let intermediate_svg_dir = match cfg!(feature = "only_final_svg") {
-            true => None,
-            false => Some(format!("{OUTPUT_DIR}/sols_{}", ext_instance.name))
+    if let Some(bin_cfg) = config.bin_packing {
+        if initial_solution.is_some() {
+            warn!("[MAIN] initial solutions are not supported in multi-bin mode, ignoring it");
+        }
+        let bin_solutions = optimize_bins(
+            instance.clone(),
+            rng,
+            &mut listener,
+            &mut terminator,
+            &config.expl_cfg,
+            &config.cmpr_cfg,
+            &config.tuning,
+            &bin_cfg,
+        ).map_err(log_failure)?;
+        log_abort(&ctrlc_terminator);
+
+        if SvgMode::enabled(svg_modes(&args), SvgMode::Final) {
+            let svg_stem = svg_path.strip_suffix(".svg").unwrap_or(&svg_path);
+            for (i, bin_sol) in bin_solutions.iter().enumerate() {
+                let bin_svg_path = format!("{svg_stem}_bin_{i}.svg");
+                let svg = s_layout_to_svg(&bin_sol.layout_snapshot, &instance, DRAW_OPTIONS, &format!("bin_{i}"));
+                io::write_svg(&svg, Path::new(&bin_svg_path), Level::Info)?;
+            }
+        }
+        for (i, bin_sol) in bin_solutions.iter().enumerate() {
+            export_layout(&args, output_dir, &ext_instance, &instance, bin_sol, &config, Some(i))?;
+        }
+
+        let json_output = ExtMultiBinOutput {
+            instance: ext_instance,
+            bins: bin_solutions.iter()
+                .map(|bin_sol| jagua_rs::probs::spp::io::export(&instance, bin_sol, *EPOCH))
+                .collect(),
+            metadata: Some(listener.1.0.metadata(rng_seed, config)),
         };
+        io::write_json(&json_output, Path::new(json_path.as_str()), Level::Info)?;
+        return Ok(());
+    }
+
+    if let Some(knapsack_cfg) = config.knapsack {
+        if initial_solution.is_some() {
+            warn!("[MAIN] initial solutions are not supported in knapsack mode, ignoring it");
+        }
+        let ext_item_values = io::read_item_values(Path::new(&input_file_path), &config)?;
+        let item_values = ItemValue::resolve(&instance, &ext_item_values);
+        let solution = optimize_knapsack(
+            instance.clone(),
+            rng,
+            &mut listener,
+            &mut terminator,
+            &config.expl_cfg,
+            &config.cmpr_cfg,
+            &config.tuning,
+            &knapsack_cfg,
+            &item_values,
+        ).map_err(log_failure)?;
+        log_abort(&ctrlc_terminator);
+        let total_value = solution.layout_snapshot.placed_items.values()
+            .map(|pi| item_values[pi.item_id].value)
+            .sum::<f32>();
+        info!("[MAIN] placed {}/{} items with a total value of {:.3}", solution.layout_snapshot.placed_items.len(), instance.total_item_qty(), total_value);
+        export_layout(&args, output_dir, &ext_instance, &instance, &solution, &config, None)?;
 
-        let live_svg_path = match cfg!(feature = "live_svg") {
-            true => Some(format!("{LIVE_DIR}/.live_solution.svg")),
-            false => None
+        let json_output = ExtSPOutput {
+            instance: ext_instance,
+            solution: jagua_rs::probs::spp::io::export(&instance, &solution, *EPOCH),
+            // The knapsack container is fixed, its width is the one of the knapsack config
+            container: Some(ExtContainer::new(&solution, ContainerMode::Strip)),
+            metadata: Some(listener.1.0.metadata(rng_seed, config)),
         };
-        
-        SvgExporter::new(
-            final_svg_path,
-            intermediate_svg_dir,
-            live_svg_path
-        )
-    };
-    
-    let mut ctrlc_terminator = CtrlCTerminator::new();
+        io::write_json(&json_output, Path::new(json_path.as_str()), Level::Info)?;
+        return Ok(());
+    }
 
     let solution = optimize(
         instance.clone(),
         rng,
-        &mut svg_exporter,
-        &mut ctrlc_terminator,
+        &mut listener,
+        &mut terminator,
         &config.expl_cfg,
         &config.cmpr_cfg,
+        &config.tuning,
+        config.container_mode,
         initial_solution.as_ref()
+    ).map_err(log_failure)?;
+    log_abort(&ctrlc_terminator);
+    log_gap(lower_bound.as_ref(), &solution);
+    export_layout(&args, output_dir, &ext_instance, &instance, &solution, &config, None)?;
+
+    let json_output = ExtSPOutput {
+        instance: ext_instance,
+        solution: jagua_rs::probs::spp::io::export(&instance, &solution, *EPOCH),
+        container: Some(ExtContainer::new(&solution, config.container_mode)),
+        metadata: Some(listener.1.0.metadata(rng_seed, config)),
+    };
+    io::write_json(&json_output, Path::new(json_path.as_str()), Level::Info)?;
+
+    Ok(())
+}
+
+/// Resumes an interrupted run from a checkpoint, using the configuration stored in the checkpoint
+fn resume_run(args: &MainCli, checkpoint_path: &Path, output_dir: &str) -> Result<()> {
+    let checkpoint = read_checkpoint(checkpoint_path)?;
+    let config = checkpoint.config;
+    info!("[MAIN] resuming {} from checkpoint {} ({:.1}s elapsed, seed: {})", checkpoint.instance.name, checkpoint_path.display(), checkpoint.elapsed, checkpoint.rng_seed);
+
+    let instance = io::import_instance(&checkpoint.instance, &config)?;
+    let state = checkpoint.resume_state(&instance);
+    let ext_instance = checkpoint.instance;
+    let lower_bound = lower_bound(&ext_instance, &instance, &config)?;
+
+    let json_path = args.output_json.clone().unwrap_or(format!("{output_dir}/final_{}.json", ext_instance.name));
+    let svg_path = args.output_svg.clone().unwrap_or(format!("{output_dir}/final_{}.svg", ext_instance.name));
+
+    // Keep checkpointing to the same file, unless a different interval was requested
+    let checkpointer = Checkpointer::new(
+        checkpoint_path.to_path_buf(),
+        Duration::from_secs(args.checkpoint_interval.unwrap_or(DEFAULT_CHECKPOINT_INTERVAL)),
+        ext_instance.clone(),
+        config,
+        checkpoint.rng_seed,
+        Duration::from_secs_f64(checkpoint.elapsed),
     );
+    let svg_exporter = svg_exporter(args, Some(svg_path), format!("{output_dir}/sols_{}", ext_instance.name));
+    let ctrlc_terminator = CtrlCTerminator::new();
+    let (mut terminator, progress) = stop_criteria(args)?.build(ctrlc_terminator.clone());
+    let jsonl_listener = progress_jsonl(args, &config)?;
+    let live_server = args.serve.map(LiveServer::start).transpose()?;
+    let mut listener = ((svg_exporter, (jsonl_listener, live_server)), (RunRecorder::new().with_lower_bound(lower_bound), (Some(checkpointer), progress)));
+
+    let solution = resume(
+        instance.clone(),
+        state,
+        &mut listener,
+        &mut terminator,
+        &config.expl_cfg,
+        &config.cmpr_cfg,
+        &config.tuning,
+        config.container_mode,
+    ).map_err(log_failure)?;
+    log_abort(&ctrlc_terminator);
+    log_gap(lower_bound.as_ref(), &solution);
+    export_layout(args, output_dir, &ext_instance, &instance, &solution, &config, None)?;
 
-    let json_path = format!("{OUTPUT_DIR}/final_{}.json", ext_instance.name);
     let json_output = ExtSPOutput {
         instance: ext_instance,
-        solution: jagua_rs::probs::spp::io::export(&instance, &solution, *EPOCH)
+        solution: jagua_rs::probs::spp::io::export(&instance, &solution, *EPOCH),
+        container: Some(ExtContainer::new(&solution, config.container_mode)),
+        metadata: Some(listener.1.0.metadata(checkpoint.rng_seed, config)),
     };
     io::write_json(&json_output, Path::new(json_path.as_str()), Level::Info)?;
+    Ok(())
+}
+
+/// Lower bound on the objective of the container mode, none in multi-bin and knapsack mode (which have a different objective)
+fn lower_bound(ext_instance: &ExtSPInstance, instance: &SPInstance, config: &SparrowConfig) -> Result<Option<LowerBound>> {
+    if config.bin_packing.is_some() || config.knapsack.is_some() {
+        return Ok(None);
+    }
+    let lb = LowerBound::new(ext_instance, instance, config.container_mode)?;
+    info!("[MAIN] lower bound: {:.3} (item area: {:.3}, item width: {:.3})", lb.bound, lb.item_area, lb.item_width);
+    Ok(Some(lb))
+}
 
+fn log_gap(lower_bound: Option<&LowerBound>, solution: &SPSolution) {
+    if let Some(lb) = lower_bound {
+        info!("[MAIN] gap to lower bound: {:.3}%", lb.gap(solution));
+    }
+}
+
+/// Exports the final layout as DXF and/or CSV placement table, if requested.
+/// The layout of every bin in multi-bin mode is exported to a separate file.
+fn export_layout(args: &MainCli, output_dir: &str, ext_instance: &ExtSPInstance, instance: &SPInstance, solution: &SPSolution, config: &SparrowConfig, bin: Option<usize>) -> Result<()> {
+    let path = |output_path: &Option<String>, enabled: bool, ext: &str| {
+        let path = output_path.clone().or_else(|| enabled.then(|| format!("{output_dir}/final_{}.{ext}", ext_instance.name)))?;
+        Some(match bin {
+            Some(i) => format!("{}_bin_{i}.{ext}", path.strip_suffix(&format!(".{ext}")).unwrap_or(&path)),
+            None => path,
+        })
+    };
+    if let Some(dxf_path) = path(&args.output_dxf, args.dxf, "dxf") {
+        let dxf = dxf::layout_to_dxf(ext_instance, instance, solution, config.dxf_import.units)?;
+        io::write_dxf(&dxf, Path::new(&dxf_path), Level::Info)?;
+    }
+    if let Some(csv_path) = path(&args.output_csv, args.csv, "csv") {
+        let csv = placement_table::placements_to_csv(ext_instance, instance, solution)?;
+        io::write_csv(&csv, Path::new(&csv_path), Level::Info)?;
+    }
     Ok(())
 }
+
+/// Collects the optional stop criteria from the CLI arguments
+fn stop_criteria(args: &MainCli) -> Result<StopCriteria> {
+    if let Some(target_density) = args.target_density {
+        ensure!(target_density > 0.0 && target_density <= 1.0, "--target-density must be in (0, 1]");
+    }
+    Ok(StopCriteria {
+        target_width: args.target_width,
+        target_density: args.target_density,
+        max_stall: args.stall.map(Duration::from_secs),
+        stop_file: args.stop_file.as_ref().map(PathBuf::from),
+        max_wall_time: args.max_wall_time.map(Duration::from_secs),
+    })
+}
+
+fn svg_modes(args: &MainCli) -> &[SvgMode] {
+    args.svg.as_deref().unwrap_or(SvgMode::DEFAULT)
+}
+
+/// Exports the SVG files requested by `--svg` and its related options
+fn svg_exporter(args: &MainCli, final_svg_path: Option<String>, intermediate_svg_dir: String) -> SvgExporter {
+    let enabled = |mode| SvgMode::enabled(svg_modes(args), mode);
+    let mut svg_exporter = SvgExporter::new(
+        final_svg_path.filter(|_| enabled(SvgMode::Final)),
+        enabled(SvgMode::Intermediate).then_some(intermediate_svg_dir),
+        enabled(SvgMode::Live).then(|| format!("{LIVE_DIR}/.live_solution.svg")),
+    );
+    if let Some(svg_reports) = &args.svg_reports {
+        svg_exporter.intermediate_filter = svg_reports.clone();
+    }
+    svg_exporter.max_intermediate_files = args.svg_max_files;
+    svg_exporter
+}
+
+/// Opens the progress stream requested by `--progress-jsonl`
+fn progress_jsonl(args: &MainCli, config: &SparrowConfig) -> Result<Option<JsonlProgressListener>> {
+    args.progress_jsonl.as_deref()
+        .map(|path| JsonlProgressListener::open(path, args.progress_placements).map(|l| l.with_tuning(config.tuning)))
+        .transpose()
+}
+
+fn log_abort(terminator: &CtrlCTerminator) {
+    if terminator.aborted() {
+        warn!("[MAIN] optimization stopped by Ctrl-C, saving the best feasible solution found");
+    }
+}
+
+fn log_failure(e: SparrowError) -> SparrowError {
+    error!("[MAIN] optimization failed: {e}");
+    e
+}
+
+/// Builds the effective configuration: the defaults (or the config file, if provided), overridden by the CLI arguments
+fn configure(args: &MainCli) -> Result<SparrowConfig> {
+    let mut config = match &args.config {
+        Some(config_path) => {
+            let config = io::read_config(Path::new(config_path))?;
+            info!("[MAIN] loaded config from {}", config_path);
+            config
+        }
+        None => DEFAULT_SPARROW_CONFIG,
+    };
+
+    let (explore_dur, compress_dur) = match (args.global_time, args.exploration, args.compression) {
+        (Some(gt), None, None) => {
+            (Duration::from_secs(gt).mul_f32(DEFAULT_EXPLORE_TIME_RATIO), Duration::from_secs(gt).mul_f32(DEFAULT_COMPRESS_TIME_RATIO))
+        },
+        (None, Some(et), Some(ct)) => {
+            (Duration::from_secs(et), Duration::from_secs(ct))
+        },
+        (None, None, None) if args.config.is_some() => {
+            (config.expl_cfg.time_limit, config.cmpr_cfg.time_limit)
+        },
+        (None, None, None) => {
+            warn!("[MAIN] no time limit specified");
+            (Duration::from_secs(600).mul_f32(DEFAULT_EXPLORE_TIME_RATIO), Duration::from_secs(600).mul_f32(DEFAULT_COMPRESS_TIME_RATIO))
+        },
+        _ => bail!("invalid cli pattern (clap should have caught this)"),
+    };
+    config.expl_cfg.time_limit = explore_dur;
+    config.cmpr_cfg.time_limit = compress_dur;
+    if args.early_termination {
+        config.expl_cfg.max_conseq_failed_attempts = Some(DEFAULT_MAX_CONSEQ_FAILS_EXPL);
+        config.cmpr_cfg.shrink_decay = ShrinkDecayStrategy::FailureBased(DEFAULT_FAIL_DECAY_RATIO_CMPR);
+        warn!("[MAIN] early termination enabled!");
+    }
+    if let Some(mode_arg) = args.container_mode {
+        config.container_mode = match (mode_arg, args.aspect_ratio) {
+            (ContainerModeArg::Strip, _) => ContainerMode::Strip,
+            (ContainerModeArg::Square, _) => ContainerMode::Square,
+            (ContainerModeArg::AspectRatio, Some(ratio)) if ratio > 0.0 => ContainerMode::FixedAspectRatio(ratio),
+            (ContainerModeArg::AspectRatio, _) => bail!("aspect-ratio mode requires a positive --aspect-ratio"),
+            (ContainerModeArg::Free, _) => ContainerMode::FreeRectangle,
+        };
+    }
+    if let Some(bin_width) = args.bin_width {
+        config.bin_packing = Some(BinPackingConfig {
+            bin_width,
+            max_migration_rounds: DEFAULT_MAX_MIGRATION_ROUNDS,
+        });
+    }
+    if let Some(container_width) = args.knapsack_width {
+        config.knapsack = Some(KnapsackConfig {
+            container_width,
+            insertion_distribution_stddev: DEFAULT_KNAPSACK_INSERTION_STDDEV,
+        });
+    }
+    if let Some(arg_rng_seed) = args.rng_seed {
+        config.rng_seed = Some(arg_rng_seed as usize);
+    }
+
+    config.validate()?;
+    Ok(config)
+}
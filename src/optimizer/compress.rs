@@ -2,10 +2,11 @@ use jagua_rs::Instant;
 use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
 use log::info;
 use rand::Rng;
-use crate::config::{CompressionConfig, ShrinkDecayStrategy};
+use crate::config::{CompressionConfig, ContainerMode, ShrinkDecayStrategy};
+use crate::error::SparrowError;
 use crate::optimizer::separator::Separator;
-use crate::util::listener::{ReportType, SolutionListener};
-use crate::util::terminator::Terminator;
+use crate::util::listener::{Phase, PhaseState, ReportType, SolutionListener};
+use crate::util::terminator::{time_left, Terminator};
 
 /// Algorithm 13 from https://doi.org/10.48550/arXiv.2509.13329
 pub fn compression_phase(
@@ -15,10 +16,23 @@ pub fn compression_phase(
     sol_listener: &mut impl SolutionListener,
     term: &impl Terminator,
     config: &CompressionConfig
-) -> SPSolution {
+) -> Result<SPSolution, SparrowError> {
+    resume_compression_phase(instance, sep, init_sol, sol_listener, term, config, 0)
+}
+
+/// Continues an interrupted compression phase from its best solution `init_sol`,
+/// with the number of failed attempts before the interruption
+pub fn resume_compression_phase(
+    instance: &SPInstance,
+    sep: &mut Separator,
+    init_sol: &SPSolution,
+    sol_listener: &mut impl SolutionListener,
+    term: &impl Terminator,
+    config: &CompressionConfig,
+    mut n_failed_attempts: i32,
+) -> Result<SPSolution, SparrowError> {
     let mut best_sol = init_sol.clone();
     let start = Instant::now();
-    let mut n_failed_attempts = 0;
 
     // Create the function to calculate the shrink step size.
     let shrink_step_size = |n_failed_attempts: i32| -> f32 {
@@ -37,10 +51,25 @@ pub fn compression_phase(
 
     // As long as the shrink step size is above the minimum, keep attempting to compress
     while !term.kill() && let step = shrink_step_size(n_failed_attempts) && step >= config.shrink_range.1 {
-        match attempt_to_compress(sep, &best_sol, step, term, sol_listener) {
+        if sol_listener.checkpoint_due() {
+            sol_listener.checkpoint(&PhaseState {
+                phase: Phase::Compression,
+                instance,
+                best: &best_sol,
+                best_height: best_sol.strip.fixed_height,
+                current: &best_sol,
+                current_height: best_sol.strip.fixed_height,
+                infeas_sol_pool: &[],
+                rng: &sep.rng,
+                worker_rngs: sep.worker_rngs(),
+                n_failed_attempts,
+                time_left: time_left(term),
+            });
+        }
+        match attempt_to_compress(sep, &best_sol, step, term, sol_listener)? {
             Some(compacted_sol) => {
-                info!("[CMPR] success at {:.3}% ({:.3} | {:.3}%)", step * 100.0, compacted_sol.strip_width(), compacted_sol.density(instance) * 100.0);
-                sol_listener.report(ReportType::CmprFeas, &compacted_sol, instance);
+                info!("[CMPR] success at {:.3}% ({:.3} x {:.3} | {:.3}%)", step * 100.0, compacted_sol.strip_width(), compacted_sol.strip.fixed_height, compacted_sol.density(instance) * 100.0);
+                sol_listener.report(ReportType::CmprFeas, &compacted_sol, instance)?;
                 best_sol = compacted_sol;
             }
             None => {
@@ -50,35 +79,40 @@ pub fn compression_phase(
         }
     }
     info!("[CMPR] finished, compressed from {:.3}% to {:.3}% (+{:.3}%)", init_sol.density(instance) * 100.0, best_sol.density(instance) * 100.0, (best_sol.density(instance) - init_sol.density(instance)) * 100.0);
-    best_sol
+    Ok(best_sol)
 }
 
 
-fn attempt_to_compress(sep: &mut Separator, init: &SPSolution, r_shrink: f32, term: &impl Terminator, sol_listener: &mut impl SolutionListener) -> Option<SPSolution> {
-    //restore to the initial solution and width
-    
-    // === CHANGE START ===
-    // Ensure we restore the square shape of the solution we are rolling back to
-    sep.prob.instance.base_strip.fixed_height = init.strip_width();
-    // === CHANGE END ===
-    
-    sep.change_strip_width(init.strip_width(), None);
+fn attempt_to_compress(sep: &mut Separator, init: &SPSolution, r_shrink: f32, term: &impl Terminator, sol_listener: &mut impl SolutionListener) -> Result<Option<SPSolution>, SparrowError> {
+    //restore to the initial solution and container
+    let (init_width, init_height) = (init.strip_width(), init.strip.fixed_height);
+    sep.change_container(init_width, init_height, None);
     sep.rollback(init, None);
 
     // Shrink the container by the provided amount at a random position
-    let new_width = init.strip_width() * (1.0 - r_shrink);
-    let split_pos = sep.rng.random_range(0.0..sep.prob.strip_width());
-    
-    // === CHANGE START ===
-    // Force the fixed height to match the new target width
-    sep.prob.instance.base_strip.fixed_height = new_width;
-    // === CHANGE END ===
-    
-    sep.change_strip_width(new_width, Some(split_pos));
+    let (new_width, new_height) = match sep.container_mode {
+        ContainerMode::FreeRectangle if sep.rng.random_bool(0.5) => (init_width, init_height * (1.0 - r_shrink)),
+        mode => {
+            let new_width = init_width * (1.0 - r_shrink);
+            (new_width, mode.height_for_width(new_width, init_height))
+        }
+    };
+    if !sep.container_fits_locked_items(new_width, new_height) {
+        return Ok(None);
+    }
+    // Locked items stay in place, so only split right of (above) them
+    let (split_x_min, split_y_min) = sep.locked_items_bbox()
+        .map_or((0.0, 0.0), |bbox| (bbox.x_max, bbox.y_max));
+    let split_pos = (
+        sep.rng.random_range(f32::min(split_x_min, init_width)..=init_width),
+        sep.rng.random_range(f32::min(split_y_min, init_height)..=init_height),
+    );
+    sep.change_container(new_width, new_height, Some(split_pos));
+
     //try to separate layout, if all collisions are eliminated, return the solution
-    let (compacted_sol, ot) = sep.separate(term, sol_listener);
+    let (compacted_sol, ot) = sep.separate(term, sol_listener)?;
     match ot.get_total_loss() == 0.0 {
-        true => Some(compacted_sol),
-        false => None,
+        true => Ok(Some(compacted_sol)),
+        false => Ok(None),
     }
 }
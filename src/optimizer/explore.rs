@@ -9,72 +9,122 @@ use jagua_rs::geometry::DTransformation;
 use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
 use log::{debug, info, warn};
 use ordered_float::OrderedFloat;
+use rand::Rng;
 use rand::prelude::{Distribution, IteratorRandom};
 use rand_distr::Normal;
 use slotmap::SecondaryMap;
-use crate::config::ExplorationConfig;
+use crate::config::{ContainerMode, ExplorationConfig};
+use crate::error::SparrowError;
 use crate::FMT;
 use crate::optimizer::separator::{Separator, SeparatorConfig};
 use crate::sample::uniform_sampler::convert_sample_to_closest_feasible;
-use crate::util::listener::{ReportType, SolutionListener};
-use crate::util::terminator::Terminator;
+use crate::util::listener::{Phase, PhaseState, ReportType, SolutionListener};
+use crate::util::terminator::{time_left, Terminator};
 
-// Notes on is_locked flag: The changes in explore.rs protect the global disruption phase, but the local search (separation) must also respect the lock.
-
-// === CHANGE START ===
-// Flag to toggle the adaptive square resizing logic
-const ENABLE_ADAPTIVE_SQUARE_RECOVERY: bool = true;
-// === CHANGE END ===
+/// Instead of terminating after too many failed attempts, grow the container slightly and continue (not used in [`ContainerMode::Strip`])
+const ENABLE_ADAPTIVE_RECOVERY: bool = true;
 
 /// Algorithm 12 from https://doi.org/10.48550/arXiv.2509.13329
-pub fn exploration_phase(instance: &SPInstance, sep: &mut Separator, sol_listener: &mut impl SolutionListener,  term: &impl Terminator, config: &ExplorationConfig) -> Vec<SPSolution> {
-    //let mut current_width = sep.prob.strip_width();
-   
-    // 1. Get the large height from your input (e.g., 5000.0)
-    let start_size = sep.prob.instance.base_strip.fixed_height;
-    
-    // 2. Force the strip width to match this height immediately
-    //    This creates a 5000x5000 square (because of Step 1)
-    sep.change_strip_width(start_size, None);
-    
-    let mut current_width = start_size;
-    let mut best_width = current_width;
+pub fn exploration_phase(instance: &SPInstance, sep: &mut Separator, sol_listener: &mut impl SolutionListener,  term: &impl Terminator, config: &ExplorationConfig) -> Result<Vec<SPSolution>, SparrowError> {
+    let mode = sep.container_mode;
+
+    // Bring the container in line with the geometry mode (e.g. square it), while still enclosing the initial layout
+    let (start_width, start_height) = mode.enclosing_dims(sep.prob.strip_width(), sep.strip_height());
+    sep.change_container(start_width, start_height, None);
+
+    let init_sol = sep.prob.save();
+    sol_listener.report(ReportType::ExplFeas, &init_sol, instance)?;
+    info!("[EXPL] starting optimization ({:?}) with initial container: {:.3} x {:.3} ({:.3}%)", mode, start_width, start_height, sep.prob.density() * 100.0);
 
-    let mut feasible_sols = vec![sep.prob.save()];
+    explore(instance, sep, sol_listener, term, config, init_sol, start_height, vec![])
+}
+
+/// Continues an interrupted exploration phase, from the layout and container currently loaded in the separator.
+/// `best_sol` (with a container of height `best_height`) is the best feasible solution found before the interruption,
+/// `infeas_sol_pool` the pool of infeasible solutions in the current container.
+pub fn resume_exploration_phase(
+    instance: &SPInstance,
+    sep: &mut Separator,
+    sol_listener: &mut impl SolutionListener,
+    term: &impl Terminator,
+    config: &ExplorationConfig,
+    best_sol: SPSolution,
+    best_height: f32,
+    infeas_sol_pool: Vec<(SPSolution, f32)>,
+) -> Result<Vec<SPSolution>, SparrowError> {
+    info!("[EXPL] resuming optimization ({:?}) with container: {:.3} x {:.3}, best feasible: {:.3} x {:.3}", sep.container_mode, sep.prob.strip_width(), sep.strip_height(), best_sol.strip_width(), best_height);
+    explore(instance, sep, sol_listener, term, config, best_sol, best_height, infeas_sol_pool)
+}
 
-    sol_listener.report(ReportType::ExplFeas, &feasible_sols[0], instance);
-    info!("[EXPL] starting optimization with initial width: {:.3} ({:.3}%)",current_width,sep.prob.density() * 100.0);
+fn explore(
+    instance: &SPInstance,
+    sep: &mut Separator,
+    sol_listener: &mut impl SolutionListener,
+    term: &impl Terminator,
+    config: &ExplorationConfig,
+    best_sol: SPSolution,
+    best_height: f32,
+    mut infeas_sol_pool: Vec<(SPSolution, f32)>,
+) -> Result<Vec<SPSolution>, SparrowError> {
+    let mode = sep.container_mode;
 
-    let mut infeas_sol_pool: Vec<(SPSolution, f32)> = vec![];
+    let mut current_width = sep.prob.strip_width();
+    let mut best_obj = mode.objective(best_sol.strip_width(), best_height);
+    let mut best_height = best_height;
+
+    let mut feasible_sols = vec![best_sol];
 
     while !term.kill() {
+        if sol_listener.checkpoint_due() {
+            sol_listener.checkpoint(&PhaseState {
+                phase: Phase::Exploration,
+                instance,
+                best: feasible_sols.last().unwrap(),
+                best_height,
+                current: &sep.prob.save(),
+                current_height: sep.strip_height(),
+                infeas_sol_pool: &infeas_sol_pool,
+                rng: &sep.rng,
+                worker_rngs: sep.worker_rngs(),
+                n_failed_attempts: 0,
+                time_left: time_left(term),
+            });
+        }
+
         // Attempt to separate the current layout
-        let local_best = sep.separate(term, sol_listener);
+        let local_best = sep.separate(term, sol_listener)?;
         let total_loss = local_best.1.get_total_loss();
 
         if total_loss == 0.0 {
             // If successfully separated
-            if current_width < best_width {
-                info!("[EXPL] feasible solution found! (width: {:.3}, dens: {:.3}%)",current_width,sep.prob.density() * 100.0);
-                best_width = current_width;
+            let current_obj = mode.objective(current_width, sep.strip_height());
+            if current_obj < best_obj {
+                info!("[EXPL] feasible solution found! (container: {:.3} x {:.3}, dens: {:.3}%)", current_width, sep.strip_height(), sep.prob.density() * 100.0);
+                best_obj = current_obj;
+                best_height = sep.strip_height();
                 feasible_sols.push(local_best.0.clone());
-                sol_listener.report(ReportType::ExplFeas, &local_best.0, instance);
+                sol_listener.report(ReportType::ExplFeas, &local_best.0, instance)?;
             }
-            // Shrink the strip width and clear the infeasible solution pool
-            let next_width = current_width * (1.0 - config.shrink_step);
-            info!("[EXPL] shrinking strip by {}%: {:.3} -> {:.3}", config.shrink_step * 100.0, current_width, next_width);
-            sep.change_strip_width(next_width, None);
-            
-            // Force the fixed height to match the new width (Square constraint)
-            sep.prob.instance.base_strip.fixed_height = next_width;
-	    // Apply the shrink to the variable dimension
-	    sep.change_strip_width(next_width, None);
-
+            // Shrink the container and clear the infeasible solution pool
+            let current_height = sep.strip_height();
+            let (next_width, next_height) = match mode {
+                ContainerMode::FreeRectangle if sep.rng.random_bool(0.5) => (current_width, current_height * (1.0 - config.shrink_step)),
+                _ => {
+                    let next_width = current_width * (1.0 - config.shrink_step);
+                    (next_width, mode.height_for_width(next_width, current_height))
+                }
+            };
+            if !sep.container_fits_locked_items(next_width, next_height) {
+                info!("[EXPL] container cannot shrink any further without excluding locked items, terminating");
+                break;
+            }
+            info!("[EXPL] shrinking container by {}%: {:.3} x {:.3} -> {:.3} x {:.3}", config.shrink_step * 100.0, current_width, current_height, next_width, next_height);
+            sep.change_container(next_width, next_height, None);
             current_width = next_width;
             infeas_sol_pool.clear();
         } else {
-            info!("[EXPL] unable to reach feasibility (width: {:.3}, dens: {:.3}%, min loss: {:.3})", current_width, sep.prob.density() * 100.0, FMT().fmt2(total_loss));
-            sol_listener.report(ReportType::ExplInfeas, &local_best.0, instance);
+            info!("[EXPL] unable to reach feasibility (container: {:.3} x {:.3}, dens: {:.3}%, min loss: {:.3})", current_width, sep.strip_height(), sep.prob.density() * 100.0, FMT().fmt2(total_loss));
+            sol_listener.report(ReportType::ExplInfeas, &local_best.0, instance)?;
 
             // Separation was not successful add it to the pool of infeasible solutions
             match infeas_sol_pool.binary_search_by(|(_, o)| o.partial_cmp(&total_loss).unwrap()) {
@@ -82,30 +132,22 @@ pub fn exploration_phase(instance: &SPInstance, sep: &mut Separator, sol_listene
             }
 
             if infeas_sol_pool.len() >= config.max_conseq_failed_attempts.unwrap_or(usize::MAX) {
-	    	// === CHANGE START ===
-                // Logic to recover from over-shrinking by increasing square size slightly
-                if ENABLE_ADAPTIVE_SQUARE_RECOVERY {
-                    // Back off by half the shrink step (e.g., if we shrank by 10%, grow by 5%)
+                if ENABLE_ADAPTIVE_RECOVERY && mode != ContainerMode::Strip {
+                    // Recover from over-shrinking by growing the container by half a shrink step
                     let backoff_ratio = config.shrink_step * 0.5;
                     let next_width = current_width * (1.0 + backoff_ratio);
-                    
-                    info!("[EXPL] max consecutive failed attempts ({}) reached. ADAPTIVE: Backing off square size {:.3} -> {:.3}", infeas_sol_pool.len(), current_width, next_width);
+                    info!("[EXPL] max consecutive failed attempts ({}), backing off width {:.3} -> {:.3}", infeas_sol_pool.len(), current_width, next_width);
 
-                    // Update square dimensions
-                    sep.prob.instance.base_strip.fixed_height = next_width;
                     sep.change_strip_width(next_width, None);
                     current_width = next_width;
 
-                    // Reset the pool to restart attempts at this new, slightly easier size
+                    // Restart the attempts at this new, slightly easier size
                     infeas_sol_pool.clear();
-                    
-                    // Skip the disruption logic below and immediately try to separate at the new size
-                    continue; 
+                    continue;
                 } else {
                     info!("[EXPL] max consecutive failed attempts ({}), terminating", infeas_sol_pool.len());
                     break;
                 }
-                // === CHANGE END ===            
             }
 
             // Restore to a random solution from the pool, with better solutions having more chance to be selected
@@ -123,27 +165,28 @@ pub fn exploration_phase(instance: &SPInstance, sep: &mut Separator, sol_listene
 
             // Rollback to this solution and disrupt it.
             sep.rollback(selected_sol, None);
-            disrupt_solution(sep, config);
+            disrupt_solution(sep, config)?;
         }
     }
 
-    info!("[EXPL] finished, best feasible solution: width: {:.3} ({:.3}%)",best_width,feasible_sols.last().unwrap().density(instance) * 100.0);
+    let best_sol = feasible_sols.last().unwrap();
+    info!("[EXPL] finished, best feasible solution: {:.3} x {:.3} ({:.3}%)", best_sol.strip_width(), best_sol.strip.fixed_height, best_sol.density(instance) * 100.0);
 
-    feasible_sols
+    Ok(feasible_sols)
 }
 
 
-fn disrupt_solution(sep: &mut Separator, config: &ExplorationConfig) {
+fn disrupt_solution(sep: &mut Separator, config: &ExplorationConfig) -> Result<(), SparrowError> {
 
     let movable_items_count = sep.prob.layout.placed_items.iter().filter(|(_, pi)| !pi.is_locked).count();
     
     if movable_items_count < 2 {
         warn!("[DSRP] cannot disrupt solution with less than 2 movable items");
-        return;
+        return Ok(());
     }
     if sep.prob.layout.placed_items.len() < 2 {
         warn!("[DSRP] cannot disrupt solution with less than 2 items");
-        return;
+        return Ok(());
     }
 
     // The general idea is to disrupt a solution by swapping two 'large' items in the layout.
@@ -191,11 +234,18 @@ fn disrupt_solution(sep: &mut Separator, config: &ExplorationConfig) {
     // Step 2: Select two 'large' items and 'swap' them.
 
     let large_items = sep.prob.layout.placed_items.iter()
-        .filter(|(_, pi)| !pi.is_locked) // <--- CRITICAL CHANGE
+        .filter(|(_, pi)| !pi.is_locked)
         .filter(|(_, pi)| pi.shape.surrogate().convex_hull_area >= ch_area_cutoff);
 
-    //Choose a first item with a large enough convex hull
-    let (pk1, pi1) = large_items.clone().choose(&mut sep.rng).expect("[DSRP] failed to choose first item");
+    //Choose a first item with a large enough convex hull.
+    //If all large items are locked, choose a random movable one.
+    let (pk1, pi1) = large_items.clone().choose(&mut sep.rng)
+        .or_else(|| {
+            sep.prob.layout.placed_items.iter()
+                .filter(|(_, pi)| !pi.is_locked)
+                .choose(&mut sep.rng)
+        })
+        .ok_or(SparrowError::DisruptionFailed { n_movable_items: movable_items_count })?;
 
     //Choose a second item with a large enough convex hull and different enough from the first.
     //If no such item is found, choose a random one.
@@ -208,10 +258,10 @@ fn disrupt_solution(sep: &mut Separator, config: &ExplorationConfig) {
         .choose(&mut sep.rng)
         .or_else(|| {
             sep.prob.layout.placed_items.iter()
-                .filter(|(pk, _)| *pk != pk1) // Ensure the second item is not the same as the first
+                .filter(|(pk, pi)| *pk != pk1 && !pi.is_locked) // Ensure the second item is not the same as the first and is movable
                 .choose(&mut sep.rng)
         }) // As a fallback, choose any item
-        .expect("[EXPL] failed to choose second item for disruption");
+        .ok_or(SparrowError::DisruptionFailed { n_movable_items: movable_items_count })?;
 
     // Step 3: Swap the two items' positions in the layout.
 
@@ -282,6 +332,7 @@ let converting_transformation = dt1_new.compose().inverse()
             sep.move_item(c2_pk, new_feasible_dt);
         }
     }
+    Ok(())
 }
 
 /// Collects all items which point of inaccessibility (POI) is contained by pk_c's shape.
@@ -0,0 +1,90 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::thread::JoinHandle;
+use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use rand_xoshiro::Xoshiro256PlusPlus;
+use crate::config::SparrowConfig;
+use crate::optimizer::optimize;
+use crate::error::SparrowError;
+use crate::util::listener::{ReportType, SolutionListener};
+use crate::util::terminator::{AnyOf, CancellationTerminator, Terminator};
+
+/// Handle to an [`optimize`] run in a background thread.
+/// The run can be cancelled and its best feasible solution inspected from any thread.
+pub struct SparrowHandle {
+    cancellation: CancellationTerminator,
+    best: Arc<Mutex<Option<SPSolution>>>,
+    thread: JoinHandle<Result<SPSolution, SparrowError>>,
+}
+
+impl SparrowHandle {
+    /// Starts optimizing `instance` in a background thread. All solutions are also reported to `listener`.
+    /// The run stops when `terminator` kills it (e.g. built from [`crate::util::terminator::StopCriteria`]) or when cancelled.
+    pub fn spawn(
+        instance: SPInstance,
+        rng: Xoshiro256PlusPlus,
+        config: SparrowConfig,
+        initial_solution: Option<SPSolution>,
+        listener: impl SolutionListener + Send + 'static,
+        terminator: impl Terminator + Send + 'static,
+    ) -> Self {
+        let cancellation = CancellationTerminator::new();
+        let best = Arc::new(Mutex::new(None));
+
+        let thread = {
+            let mut terminator = AnyOf(vec![Box::new(cancellation.clone()), Box::new(terminator)]);
+            let mut listener = (BestSoFar(best.clone()), listener);
+            thread::Builder::new()
+                .name("sparrow".to_string())
+                .spawn(move || optimize(
+                    instance,
+                    rng,
+                    &mut listener,
+                    &mut terminator,
+                    &config.expl_cfg,
+                    &config.cmpr_cfg,
+                    &config.tuning,
+                    config.container_mode,
+                    initial_solution.as_ref(),
+                ))
+                .expect("failed to spawn optimization thread")
+        };
+
+        Self { cancellation, best, thread }
+    }
+
+    /// Stops the optimization as soon as possible, the best feasible solution is still returned by [`SparrowHandle::join`]
+    pub fn cancel(&self) {
+        self.cancellation.cancel();
+    }
+
+    /// The best feasible solution found so far, if any
+    pub fn best_so_far(&self) -> Option<SPSolution> {
+        self.best.lock().unwrap().clone()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// Waits for the optimization to finish and returns the final solution or the error which ended it
+    /// (or the panic of the optimization thread)
+    pub fn join(self) -> thread::Result<Result<SPSolution, SparrowError>> {
+        self.thread.join()
+    }
+}
+
+/// Keeps the last reported feasible solution, which is always the best one so far
+struct BestSoFar(Arc<Mutex<Option<SPSolution>>>);
+
+impl SolutionListener for BestSoFar {
+    fn report(&mut self, report: ReportType, solution: &SPSolution, _instance: &SPInstance) -> Result<(), SparrowError> {
+        match report {
+            ReportType::ExplFeas | ReportType::CmprFeas | ReportType::Final => {
+                *self.0.lock().unwrap() = Some(solution.clone());
+            }
+            ReportType::ExplInfeas | ReportType::ExplImproving => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+use std::cmp::Reverse;
+use itertools::Itertools;
+use jagua_rs::entities::Instance;
+use jagua_rs::probs::spp::entities::{SPInstance, SPProblem, SPSolution};
+use log::{debug, info, warn};
+use ordered_float::OrderedFloat;
+use rand::prelude::Distribution;
+use rand::{RngCore, SeedableRng};
+use rand_distr::Normal;
+use rand_xoshiro::Xoshiro256PlusPlus;
+use std::iter;
+use crate::config::{CompressionConfig, ContainerMode, ExplorationConfig, KnapsackConfig, TuningConfig};
+use crate::error::SparrowError;
+use crate::FMT;
+use crate::optimizer::lbf::LBFBuilder;
+use crate::optimizer::separator::Separator;
+use crate::sample::uniform_sampler::UniformBBoxSampler;
+use crate::util::io::ExtItemValue;
+use crate::util::listener::{Phase, ReportType, SolutionListener};
+use crate::util::terminator::Terminator;
+
+/// Value and priority of an item in the knapsack mode.
+/// Items with a higher priority are inserted first and evicted last, regardless of their value.
+#[derive(Debug, Clone, Copy)]
+pub struct ItemValue {
+    pub value: f32,
+    pub priority: i32,
+}
+
+impl ItemValue {
+    /// Resolves the values of all items of the instance (indexed by item id), matching `ext_values` on their `id`.
+    /// Items without a specified value are valued by their area, items without an entry also get the default priority of 0.
+    pub fn resolve(instance: &SPInstance, ext_values: &[ExtItemValue]) -> Vec<ItemValue> {
+        instance.items().map(|item| {
+            let area = item.shape_cd.area;
+            match ext_values.iter().find(|v| v.id == item.id) {
+                Some(ext_value) => ItemValue { value: ext_value.value.unwrap_or(area), priority: ext_value.priority },
+                None => ItemValue { value: area, priority: 0 },
+            }
+        }).collect()
+    }
+}
+
+/// Knapsack (placement maximization) variant of [`crate::optimizer::optimize`].
+/// The container has a fixed size and items are optional, the total value of the placed items is maximized.
+/// Instead of shrinking the container, the exploration and compression phases repeatedly insert an unplaced item
+/// and separate the layout, evicting the lowest ranked colliding items until it is feasible again.
+pub fn optimize_knapsack(
+    instance: SPInstance,
+    mut rng: Xoshiro256PlusPlus,
+    sol_listener: &mut impl SolutionListener,
+    terminator: &mut impl Terminator,
+    expl_config: &ExplorationConfig,
+    cmpr_config: &CompressionConfig,
+    tuning: &TuningConfig,
+    knapsack_config: &KnapsackConfig,
+    item_values: &[ItemValue],
+) -> Result<SPSolution, SparrowError> {
+    let mut next_rng = || Xoshiro256PlusPlus::seed_from_u64(rng.next_u64());
+
+    // Greedily fill the container in order of rank, skipping items that do not fit
+    sol_listener.enter_phase(Phase::Construction);
+    let mut builder = LBFBuilder::new(instance.clone(), next_rng(), *tuning);
+    builder.prob.change_strip_width(knapsack_config.container_width);
+    builder.enforce_fixed_items();
+
+    // Items which do not fit the empty container at any allowed rotation are never inserted
+    let container_bbox = builder.prob.layout.container.outer_cd.bbox;
+    let insertable = instance.items()
+        .map(|item| UniformBBoxSampler::new(container_bbox, item, container_bbox).is_some())
+        .collect_vec();
+    let too_large = insertable.iter().positions(|fits| !fits).collect_vec();
+    if !too_large.is_empty() {
+        warn!("[KNAP] items {:?} do not fit the container at any allowed rotation, they are never placed", too_large);
+    }
+
+    let insertion_order = (0..instance.items.len())
+        .filter(|id| insertable[*id])
+        .sorted_by_cached_key(|id| Reverse(item_rank(&instance, item_values, *id)))
+        .flat_map(|id| iter::repeat_n(id, builder.prob.item_demand_qtys[id]))
+        .collect_vec();
+    for item_id in insertion_order {
+        builder.try_place_item(item_id);
+    }
+    info!("[KNAP] initial construction placed {} items, value: {}", builder.prob.layout.placed_items.len(), FMT().fmt2(placed_value(&builder.prob, item_values)));
+
+    sol_listener.enter_phase(Phase::Exploration);
+    terminator.new_timeout(expl_config.time_limit);
+    let mut expl_separator = Separator::new(instance.clone(), builder.prob, next_rng(), expl_config.separator_config, *tuning, ContainerMode::Strip);
+    let expl_sol = knapsack_phase(&instance, &mut expl_separator, item_values, &insertable, sol_listener, terminator, knapsack_config, expl_config.max_conseq_failed_attempts, ReportType::ExplFeas)?;
+
+    sol_listener.enter_phase(Phase::Compression);
+    terminator.new_timeout(cmpr_config.time_limit);
+    let mut cmpr_separator = Separator::new(expl_separator.instance, expl_separator.prob, next_rng(), cmpr_config.separator_config, *tuning, ContainerMode::Strip);
+    cmpr_separator.rollback(&expl_sol, None);
+    let cmpr_sol = knapsack_phase(&instance, &mut cmpr_separator, item_values, &insertable, sol_listener, terminator, knapsack_config, None, ReportType::CmprFeas)?;
+
+    sol_listener.report(ReportType::Final, &cmpr_sol, &instance)?;
+    sol_listener.enter_phase(Phase::Finished);
+
+    Ok(cmpr_sol)
+}
+
+/// Repeatedly inserts an unplaced item and separates the layout, evicting colliding items when needed.
+/// Changes are only accepted if they increase the total value of the placed items.
+/// Only items marked in `insertable` (indexed by item id) are inserted.
+fn knapsack_phase(
+    instance: &SPInstance,
+    sep: &mut Separator,
+    item_values: &[ItemValue],
+    insertable: &[bool],
+    sol_listener: &mut impl SolutionListener,
+    term: &impl Terminator,
+    config: &KnapsackConfig,
+    max_conseq_failed_attempts: Option<usize>,
+    report_type: ReportType,
+) -> Result<SPSolution, SparrowError> {
+    let mut best_sol = sep.prob.save();
+    let mut best_value = placed_value(&sep.prob, item_values);
+    let mut n_failed_attempts = 0;
+
+    while !term.kill() && n_failed_attempts < max_conseq_failed_attempts.unwrap_or(usize::MAX) {
+        let Some(item_id) = select_item_to_insert(instance, sep, item_values, insertable, config) else {
+            info!("[KNAP] all insertable items are placed");
+            break;
+        };
+        debug!("[KNAP] inserting item {}", item_id);
+        sep.insert_item(item_id)?;
+
+        // Separate the layout, evicting the lowest ranked colliding item every time separation fails
+        let feasible = loop {
+            let (_, ct) = sep.separate(term, sol_listener)?;
+            if ct.get_total_loss() == 0.0 {
+                break true;
+            }
+            if term.kill() {
+                break false;
+            }
+            let evict_pk = sep.prob.layout.placed_items.iter()
+                .filter(|(pk, pi)| !pi.is_locked && sep.ct.get_loss(*pk) > 0.0)
+                .min_by_key(|(_, pi)| item_rank(instance, item_values, pi.item_id))
+                .map(|(pk, _)| pk);
+            match evict_pk {
+                Some(pk) => {
+                    let evicted = sep.remove_item(pk);
+                    debug!("[KNAP] evicting item {}", evicted.item_id);
+                }
+                None => break false,
+            }
+        };
+
+        let value = placed_value(&sep.prob, item_values);
+        if feasible && value > best_value {
+            info!("[KNAP] placed value improved: {} -> {} ({} items, {:.3}%)", FMT().fmt2(best_value), FMT().fmt2(value), sep.prob.layout.placed_items.len(), sep.prob.density() * 100.0);
+            best_sol = sep.prob.save();
+            best_value = value;
+            n_failed_attempts = 0;
+            sol_listener.report(report_type.clone(), &best_sol, instance)?;
+        } else {
+            sep.rollback(&best_sol, None);
+            n_failed_attempts += 1;
+        }
+    }
+    info!("[KNAP] finished, placed value: {} ({} items)", FMT().fmt2(best_value), best_sol.layout_snapshot.placed_items.len());
+    Ok(best_sol)
+}
+
+/// Selects an insertable item with unmet demand to insert, better ranked items are more likely to be selected.
+fn select_item_to_insert(instance: &SPInstance, sep: &mut Separator, item_values: &[ItemValue], insertable: &[bool], config: &KnapsackConfig) -> Option<usize> {
+    let candidates = (0..instance.items.len())
+        .filter(|id| insertable[*id] && sep.prob.item_demand_qtys[*id] > 0)
+        .sorted_by_cached_key(|id| Reverse(item_rank(instance, item_values, *id)))
+        .collect_vec();
+
+    if candidates.is_empty() {
+        return None;
+    }
+
+    // Sample a value in range [0.0, 1.0[ from a normal distribution and map it to a candidate
+    let distribution = Normal::new(0.0, config.insertion_distribution_stddev).unwrap();
+    let sample = distribution.sample(&mut sep.rng).abs().min(0.999);
+    Some(candidates[(sample * candidates.len() as f32) as usize])
+}
+
+/// Ranks items by priority first, and by value per unit of area second.
+fn item_rank(instance: &SPInstance, item_values: &[ItemValue], item_id: usize) -> (i32, OrderedFloat<f32>) {
+    let ItemValue { value, priority } = item_values[item_id];
+    (priority, OrderedFloat(value / instance.item(item_id).shape_cd.area))
+}
+
+pub fn placed_value(prob: &SPProblem, item_values: &[ItemValue]) -> f32 {
+    prob.layout.placed_items.values()
+        .map(|pi| item_values[pi.item_id].value)
+        .sum()
+}
@@ -1,6 +1,8 @@
+use crate::error::SparrowError;
 use crate::eval::lbf_evaluator::LBFEvaluator;
 use crate::eval::sample_eval::SampleEval;
-use crate::sample::search::{search_placement, SampleConfig};
+use crate::config::TuningConfig;
+use crate::sample::search::search_placement;
 use itertools::Itertools;
 use log::debug;
 use log::warn;
@@ -10,22 +12,24 @@ use std::cmp::Reverse;
 use std::iter;
 use jagua_rs::Instant;
 use jagua_rs::entities::Instance;
-use jagua_rs::probs::spp::entities::{SPInstance, SPPlacement, SPProblem};
+use jagua_rs::probs::spp::entities::{SPInstance, SPPlacement, SPProblem, SPSolution};
 use rand_xoshiro::Xoshiro256PlusPlus;
-use crate::util::assertions;
+use crate::consts::DEFAULT_VERIFY_TOLERANCE_RATIO;
+use crate::util::{assertions, verify};
 
 pub struct LBFBuilder {
     pub instance: SPInstance,
     pub prob: SPProblem,
     pub rng: Xoshiro256PlusPlus,
-    pub sample_config: SampleConfig,
+    /// Items are placed with [`TuningConfig::lbf_sample_config`]
+    pub tuning: TuningConfig,
 }
 
 impl LBFBuilder {
     pub fn new(
         instance: SPInstance,
         rng: Xoshiro256PlusPlus,
-        sample_config: SampleConfig,
+        tuning: TuningConfig,
     ) -> Self {
         let prob = SPProblem::new(instance.clone());
 
@@ -33,17 +37,45 @@ impl LBFBuilder {
             instance,
             prob,
             rng,
-            sample_config,
+            tuning,
         }
     }
 
-    pub fn construct(mut self) -> Self {
+    /// Starts the construction from the placements of an existing (partial) solution.
+    /// If `lock` is set, these placements are locked and will not be moved during optimization.
+    pub fn restore_placements(mut self, sol: &SPSolution, lock: bool) -> Self {
+        self.prob.restore(sol);
+        if lock {
+            self.prob.layout.placed_items.values_mut().for_each(|pi| pi.is_locked = true);
+        }
+        info!("[CONSTR] restored {} placements (locked: {})", self.prob.layout.placed_items.len(), lock);
+        self
+    }
+
+    /// Places all missing demand, expanding the strip as needed.
+    /// Fails with [`SparrowError::ItemDoesNotFit`] if the strip keeps expanding without an item fitting.
+    pub fn construct(mut self) -> Result<Self, SparrowError> {
         let start = Instant::now();
         // [CHANGE] Enforce fixed items (Unmelted Crystal) before greedy construction
         self.enforce_fixed_items();
-        
+
+        let sorted_item_indices = self.placement_order();
+
+        info!("[CONSTR] placing items in order: {:?}",sorted_item_indices);
+
+        for item_id in sorted_item_indices {
+            self.place_item(item_id)?;
+        }
+
+        self.prob.fit_strip();
+        info!("[CONSTR] placed all items in width: {:.3} (in {:?})",self.prob.strip_width(), start.elapsed());
+        Ok(self)
+    }
+
+    /// Returns the item ids of all missing demand, in the order they should be placed (largest first).
+    pub fn placement_order(&self) -> Vec<usize> {
         let n_items = self.instance.items.len();
-        let sorted_item_indices = (0..n_items)
+        (0..n_items)
             .sorted_by_cached_key(|id| {
                 let item_shape = self.instance.item(*id).shape_cd.as_ref();
                 let convex_hull_area = item_shape.surrogate().convex_hull_area;
@@ -54,30 +86,35 @@ impl LBFBuilder {
                 let missing_qty = self.prob.item_demand_qtys[id];
                 iter::repeat_n(id, missing_qty)
             })
-            .collect_vec();
-
-        info!("[CONSTR] placing items in order: {:?}",sorted_item_indices);
+            .collect_vec()
+    }
 
-        for item_id in sorted_item_indices {
-            self.place_item(item_id);
+    /// Attempts to place an item without expanding the strip, returns whether it succeeded.
+    pub fn try_place_item(&mut self, item_id: usize) -> bool {
+        match self.find_placement(item_id) {
+            Some(p_opt) => {
+                self.prob.place_item(p_opt);
+                debug!("[CONSTR] placing item with id {} at [{}]", p_opt.item_id, p_opt.d_transf);
+                true
+            }
+            None => false,
         }
-
-        self.prob.fit_strip();
-        info!("[CONSTR] placed all items in width: {:.3} (in {:?})",self.prob.strip_width(), start.elapsed());
-        self
     }
 
-    fn place_item(&mut self, item_id: usize) {
+    fn place_item(&mut self, item_id: usize) -> Result<(), SparrowError> {
         match self.find_placement(item_id) {
             Some(p_opt) => {
                 self.prob.place_item(p_opt);
                 info!("[CONSTR] placing item {}/{} with id {} at [{}]",self.prob.layout.placed_items.len(),self.instance.total_item_qty(),p_opt.item_id,p_opt.d_transf);
+                Ok(())
             }
             None => {
                 info!("[CONSTR] failed to place item with id {}, expanding strip width",item_id);
                 self.prob.change_strip_width(self.prob.strip_width() * 1.2);
-                assert!(assertions::strip_width_is_in_check(&self.prob), "strip-width is running away (>{:.3}), item {item_id} does not seem to fit into the strip", self.prob.strip_width());          
-                self.place_item(item_id);
+                if !assertions::strip_width_is_in_check(&self.prob) {
+                    return Err(SparrowError::ItemDoesNotFit { item_id, strip_width: self.prob.strip_width() });
+                }
+                self.place_item(item_id)
             }
         }
     }
@@ -90,7 +127,7 @@ impl LBFBuilder {
 	let evaluator_factory = || LBFEvaluator::new(layout, item);
 
 	// Pass the factory to search_placement
-	let (best_sample, _) = search_placement(layout, item, None, evaluator_factory, self.sample_config, &mut self.rng);
+	let (best_sample, _) = search_placement(layout, item, None, evaluator_factory, self.tuning.lbf_sample_config, &self.tuning, &mut self.rng);
 
 	match best_sample {
 	   Some((d_transf, SampleEval::Clear { .. })) => {
@@ -100,38 +137,50 @@ impl LBFBuilder {
 	}
     }
     
-    // [CHANGE] Logic moved from explore.rs
-    fn enforce_fixed_items(&mut self) {
+    /// Places and locks all items with a fixed placement.
+    /// Fixed items which are already placed at their fixed placement (e.g. restored from an initial solution) are only locked,
+    /// restored copies placed elsewhere are moved to it.
+    pub fn enforce_fixed_items(&mut self) {
         // Collect items to fix first to avoid borrow conflicts
         let items_to_fix = self.instance.items()
             .filter_map(|item| item.fixed_placement.map(|fp| (item.id, fp)))
             .collect::<Vec<_>>();
+        let tolerance = self.instance.base_strip.fixed_height * DEFAULT_VERIFY_TOLERANCE_RATIO as f32;
 
         let mut fixed_count = 0;
 
         for (item_id, d_transf) in items_to_fix {
-            // Only place if there is demand (safeguard)
-            if self.prob.item_demand_qtys[item_id] > 0 {
-                let placement = SPPlacement { item_id, d_transf };
-                
-                // Place the item (this handles demand decrement and CDE registration)
-                // Note: Standard placement is unlocked (false).
-                self.prob.place_item(placement);
-                info!("[CONSTR] placing item {}/{} with id {} at [{}]",self.prob.layout.placed_items.len(),self.instance.total_item_qty(),item_id,d_transf);
-
-                // Immediately find and lock the item we just placed
-                // We assume it's one of the items with this ID that isn't locked yet.
-                if let Some((_, pi)) = self.prob.layout.placed_items.iter_mut()
-                    .find(|(_, pi)| pi.item_id == item_id && !pi.is_locked) 
-                {
-                    pi.is_locked = true;
-                    fixed_count += 1;
-                } else {
-                     warn!("[CONSTR] Failed to lock fixed item {}", item_id);
+            let restored = self.prob.layout.placed_items.values_mut()
+                .find(|pi| pi.item_id == item_id && verify::same_placement(&pi.d_transf, &d_transf, tolerance));
+            if let Some(pi) = restored {
+                pi.is_locked = true;
+                fixed_count += 1;
+                debug!("[CONSTR] fixed item {} is already in place", item_id);
+                continue;
+            }
+            if self.prob.item_demand_qtys[item_id] == 0 {
+                // All copies are already placed elsewhere (e.g. restored from an initial solution), move a free one
+                let misplaced = self.prob.layout.placed_items.iter()
+                    .find(|(_, pi)| pi.item_id == item_id && !pi.is_locked)
+                    .map(|(pk, _)| pk);
+                match misplaced {
+                    Some(pk) => {
+                        self.prob.remove_item(pk);
+                        debug!("[CONSTR] moving restored item {} to its fixed placement", item_id);
+                    }
+                    None => {
+                        warn!("[CONSTR] Item {} has fixed placement but no demand left.", item_id);
+                        continue;
+                    }
                 }
-            } else {
-                 warn!("[CONSTR] Item {} has fixed placement but no demand left.", item_id);
             }
+            let placement = SPPlacement { item_id, d_transf };
+
+            // Place the item (this handles demand decrement and CDE registration) and lock it
+            let pk = self.prob.place_item(placement);
+            self.prob.layout.placed_items[pk].is_locked = true;
+            fixed_count += 1;
+            info!("[CONSTR] placing item {}/{} with id {} at [{}]",self.prob.layout.placed_items.len(),self.instance.total_item_qty(),item_id,d_transf);
         }
 
         if fixed_count > 0 {
@@ -1,15 +1,17 @@
 use crate::config::*;
+use crate::error::SparrowError;
 use crate::optimizer::lbf::LBFBuilder;
 use crate::optimizer::separator::Separator;
-use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use jagua_rs::probs::spp::entities::{SPInstance, SPProblem, SPSolution};
 use rand::{RngCore, SeedableRng};
 use std::time::Duration;
+use itertools::Itertools;
 use log::info;
 use rand_xoshiro::Xoshiro256PlusPlus;
-use crate::consts::LBF_SAMPLE_CONFIG;
-use crate::optimizer::compress::compression_phase;
-use crate::optimizer::explore::exploration_phase;
-use crate::util::listener::{ReportType, SolutionListener};
+use crate::optimizer::compress::{compression_phase, resume_compression_phase};
+use crate::optimizer::explore::{exploration_phase, resume_exploration_phase};
+use crate::util::assertions;
+use crate::util::listener::{Phase, ReportType, SolutionListener, WarmStartReport};
 use crate::util::terminator::Terminator;
 
 pub mod lbf;
@@ -17,6 +19,9 @@ pub mod separator;
 mod worker;
 pub mod explore;
 pub mod compress;
+pub mod multi_bin;
+pub mod knapsack;
+pub mod handle;
 
 ///Algorithm 11 from https://doi.org/10.48550/arXiv.2509.13329
 pub fn optimize(
@@ -26,39 +31,63 @@ pub fn optimize(
     terminator: &mut impl Terminator,
     expl_config: &ExplorationConfig,
     cmpr_config: &CompressionConfig,
+    tuning: &TuningConfig,
+    container_mode: ContainerMode,
     initial_solution: Option<&SPSolution>
-) -> SPSolution {
+) -> Result<SPSolution, SparrowError> {
     let mut next_rng = || Xoshiro256PlusPlus::seed_from_u64(rng.next_u64());
     
     // First build an initial solution if none is provided
+    sol_listener.enter_phase(Phase::Construction);
     let start_prob = match initial_solution {
         None => {
-            let builder = LBFBuilder::new(instance.clone(), next_rng(), LBF_SAMPLE_CONFIG).construct();
+            let builder = LBFBuilder::new(instance.clone(), next_rng(), *tuning).construct()?;
             builder.prob
         }
         Some(init_sol) => {
+            // Keep the provided placements and greedily place any remaining demand
             info!("[OPT] warm starting from provided initial solution");
-            let mut prob = jagua_rs::probs::spp::entities::SPProblem::new(instance.clone());
-            prob.restore(init_sol);
-            prob
+            let builder = LBFBuilder::new(instance.clone(), next_rng(), *tuning)
+                .restore_placements(init_sol, false);
+            let restored = builder.prob.layout.placed_items.keys().collect_vec();
+            let builder = builder.construct()?;
+
+            // Every placement made during construction is newly placed, including those of fixed items
+            let placed_items = &builder.prob.layout.placed_items;
+            let report = WarmStartReport {
+                carried_over: placed_items.iter().filter(|(pk, _)| restored.contains(pk)).map(|(_, pi)| pi.item_id).sorted().collect(),
+                newly_placed: placed_items.iter().filter(|(pk, _)| !restored.contains(pk)).map(|(_, pi)| pi.item_id).sorted().collect(),
+            };
+
+            info!("[OPT] warm start: {} placements carried over, {} newly placed (item ids: {:?})", report.carried_over.len(), report.newly_placed.len(), report.newly_placed.iter().dedup().collect_vec());
+            sol_listener.warm_start(&report);
+            builder.prob
         }
     };
 
+    let start_sol = start_prob.save();
+
     // Begin by executing the exploration phase
+    sol_listener.enter_phase(Phase::Exploration);
     terminator.new_timeout(expl_config.time_limit);
-    let mut expl_separator = Separator::new(instance.clone(), start_prob, next_rng(), expl_config.separator_config);
+    let mut expl_separator = Separator::new(instance.clone(), start_prob, next_rng(), expl_config.separator_config, *tuning, container_mode);
     let solutions = exploration_phase(
         &instance,
         &mut expl_separator,
         sol_listener,
         terminator,
         expl_config,
-    );
-    let final_explore_sol = solutions.last().unwrap().clone();
+    )?;
+    let final_explore_sol = solutions.last()
+        .ok_or(SparrowError::NoFeasibleSolution { phase: Phase::Exploration })?
+        .clone();
 
-    // Start the compression phase from the final solution from the exploration phase
+    // Start the compression phase from the final solution from the exploration phase.
+    // Its rng follows from the one of the exploration phase, so a resumed exploration phase continues exactly like the original run.
+    sol_listener.enter_phase(Phase::Compression);
     terminator.new_timeout(cmpr_config.time_limit);
-    let mut cmpr_separator = Separator::new(expl_separator.instance, expl_separator.prob, next_rng(), cmpr_config.separator_config);
+    let cmpr_rng = Xoshiro256PlusPlus::seed_from_u64(expl_separator.rng.next_u64());
+    let mut cmpr_separator = Separator::new(expl_separator.instance, expl_separator.prob, cmpr_rng, cmpr_config.separator_config, *tuning, container_mode);
     let cmpr_sol = compression_phase(
         &instance,
         &mut cmpr_separator,
@@ -66,10 +95,97 @@ pub fn optimize(
         sol_listener,
         terminator,
         cmpr_config,
-    );
+    )?;
+
+    debug_assert!(assertions::locked_items_unchanged(&start_sol.layout_snapshot, &cmpr_sol.layout_snapshot), "locked items were moved during optimization");
 
-    sol_listener.report(ReportType::Final, &cmpr_sol, &instance);
+    sol_listener.report(ReportType::Final, &cmpr_sol, &instance)?;
+    sol_listener.enter_phase(Phase::Finished);
 
     // Return the final compressed solution
-    cmpr_sol
-}
\ No newline at end of file
+    Ok(cmpr_sol)
+}
+
+/// State from which an interrupted run of [`optimize`] is resumed.
+/// See [`crate::util::checkpoint`] for more details.
+pub struct ResumeState {
+    /// Phase that was interrupted
+    pub phase: Phase,
+    pub best_sol: SPSolution,
+    pub best_height: f32,
+    pub current_sol: SPSolution,
+    pub current_height: f32,
+    pub infeas_sol_pool: Vec<(SPSolution, f32)>,
+    /// Random number generator of the separator of the interrupted phase
+    pub rng: Xoshiro256PlusPlus,
+    /// Random number generators of the workers of the separator
+    pub worker_rngs: Vec<Xoshiro256PlusPlus>,
+    /// Number of failed compression attempts
+    pub n_failed_attempts: i32,
+    pub expl_time_left: Duration,
+    pub cmpr_time_left: Duration,
+}
+
+/// Resumes an interrupted run of [`optimize`], with the time that was left in each phase.
+pub fn resume(
+    mut instance: SPInstance,
+    state: ResumeState,
+    sol_listener: &mut impl SolutionListener,
+    terminator: &mut impl Terminator,
+    expl_config: &ExplorationConfig,
+    cmpr_config: &CompressionConfig,
+    tuning: &TuningConfig,
+    container_mode: ContainerMode,
+) -> Result<SPSolution, SparrowError> {
+    let ResumeState { phase, best_sol, best_height, current_sol, current_height, infeas_sol_pool, rng, worker_rngs, n_failed_attempts, expl_time_left, cmpr_time_left } = state;
+    info!("[OPT] resuming from {:?} phase ({:.1}s exploration, {:.1}s compression left)", phase, expl_time_left.as_secs_f32(), cmpr_time_left.as_secs_f32());
+
+    let (final_explore_sol, prob, cmpr_rng, cmpr_worker_rngs, n_failed_attempts) = match phase {
+        Phase::Construction | Phase::Exploration => {
+            // Restore the current layout and container
+            instance.base_strip.fixed_height = current_height;
+            let mut prob = SPProblem::new(instance.clone());
+            prob.restore(&current_sol);
+
+            sol_listener.enter_phase(Phase::Exploration);
+            terminator.new_timeout(expl_time_left);
+            let mut expl_separator = Separator::new(instance.clone(), prob, rng.clone(), expl_config.separator_config, *tuning, container_mode);
+            expl_separator.restore_rngs(rng, worker_rngs);
+            let solutions = resume_exploration_phase(&instance, &mut expl_separator, sol_listener, terminator, expl_config, best_sol, best_height, infeas_sol_pool)?;
+            let final_explore_sol = solutions.last()
+                .ok_or(SparrowError::NoFeasibleSolution { phase: Phase::Exploration })?
+                .clone();
+            let cmpr_rng = Xoshiro256PlusPlus::seed_from_u64(expl_separator.rng.next_u64());
+            (final_explore_sol, expl_separator.prob, cmpr_rng, vec![], 0)
+        }
+        Phase::Compression | Phase::Finished => {
+            instance.base_strip.fixed_height = best_height;
+            let mut prob = SPProblem::new(instance.clone());
+            prob.restore(&best_sol);
+            (best_sol, prob, rng, worker_rngs, n_failed_attempts)
+        }
+    };
+
+    // The compression schedule is stretched over the time that is left
+    let cmpr_config = CompressionConfig { time_limit: cmpr_time_left, ..*cmpr_config };
+    sol_listener.enter_phase(Phase::Compression);
+    terminator.new_timeout(cmpr_time_left);
+    let mut cmpr_separator = Separator::new(instance.clone(), prob, cmpr_rng.clone(), cmpr_config.separator_config, *tuning, container_mode);
+    if !cmpr_worker_rngs.is_empty() {
+        cmpr_separator.restore_rngs(cmpr_rng, cmpr_worker_rngs);
+    }
+    let cmpr_sol = resume_compression_phase(
+        &instance,
+        &mut cmpr_separator,
+        &final_explore_sol,
+        sol_listener,
+        terminator,
+        &cmpr_config,
+        n_failed_attempts,
+    )?;
+
+    sol_listener.report(ReportType::Final, &cmpr_sol, &instance)?;
+    sol_listener.enter_phase(Phase::Finished);
+
+    Ok(cmpr_sol)
+}
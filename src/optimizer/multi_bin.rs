@@ -0,0 +1,252 @@
+use std::cmp::Reverse;
+use itertools::Itertools;
+use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use log::{debug, info};
+use ordered_float::OrderedFloat;
+use rand::{Rng, RngCore, SeedableRng};
+use rand::prelude::IteratorRandom;
+use rand_xoshiro::Xoshiro256PlusPlus;
+use crate::config::{BinPackingConfig, CompressionConfig, ContainerMode, ExplorationConfig, TuningConfig};
+use crate::consts::LAST_BIN_EXPLORE_TIME_RATIO;
+use crate::error::SparrowError;
+use crate::optimizer::compress::compression_phase;
+use crate::optimizer::explore::exploration_phase;
+use crate::optimizer::lbf::LBFBuilder;
+use crate::optimizer::separator::Separator;
+use crate::util::listener::{Phase, ReportType, SolutionListener};
+use crate::util::terminator::Terminator;
+
+/// Multi-bin (bin packing) variant of [`crate::optimizer::optimize`].
+/// Every bin is modelled as a separate strip of fixed width, with its own [`Separator`].
+/// 1. Bins are constructed greedily with the LBF constructor, opening a new bin whenever an item does not fit any existing one.
+/// 2. During the exploration time limit, the number of bins is minimized: the least filled bin is repeatedly eliminated
+///    by reinserting its items into the other bins and separating them. Items which cannot be separated migrate to other bins.
+///    Bins are only separated (not shrunk) here, a failed elimination restores all bins and disrupts them instead:
+///    an item of the least filled bin migrates to another bin, see [`disrupt_bins`].
+/// 3. During the compression time limit, the width of the last (least filled) bin is minimized with the exploration and
+///    compression phases. The other bins are kept as they are.
+///
+/// Returns one solution per bin, the last one being the least filled.
+pub fn optimize_bins(
+    instance: SPInstance,
+    mut rng: Xoshiro256PlusPlus,
+    sol_listener: &mut impl SolutionListener,
+    terminator: &mut impl Terminator,
+    expl_config: &ExplorationConfig,
+    cmpr_config: &CompressionConfig,
+    tuning: &TuningConfig,
+    bin_config: &BinPackingConfig,
+) -> Result<Vec<SPSolution>, SparrowError> {
+    let mut next_rng = || Xoshiro256PlusPlus::seed_from_u64(rng.next_u64());
+    let mut bpp_rng = next_rng();
+
+    sol_listener.enter_phase(Phase::Construction);
+
+    let mut seps = construct_bins(&instance, bin_config, tuning, &mut next_rng)?.into_iter()
+        .map(|builder| Separator::new(instance.clone(), builder.prob, next_rng(), expl_config.separator_config, *tuning, ContainerMode::Strip))
+        .collect_vec();
+    info!("[BPP] initial construction uses {} bins of {:.3} x {:.3}", seps.len(), bin_config.bin_width, seps[0].strip_height());
+
+    // Minimize the number of bins
+    sol_listener.enter_phase(Phase::Exploration);
+    terminator.new_timeout(expl_config.time_limit);
+    let mut n_failed_attempts = 0;
+    while !terminator.kill() && seps.len() > 1 && n_failed_attempts < expl_config.max_conseq_failed_attempts.unwrap_or(usize::MAX) {
+        // Bins containing locked items can never be emptied
+        let Some(target) = seps.iter().enumerate()
+            .filter(|(_, sep)| sep.locked_items_bbox().is_none())
+            .min_by_key(|(_, sep)| OrderedFloat(sep.prob.density()))
+            .map(|(i, _)| i)
+        else {
+            break;
+        };
+
+        let snapshots = seps.iter().map(|sep| sep.prob.save()).collect_vec();
+        info!("[BPP] attempting to eliminate bin {} ({:.3}%), {} bins in use", target, seps[target].prob.density() * 100.0, seps.len());
+
+        match attempt_to_eliminate_bin(&mut seps, target, terminator, sol_listener, bin_config, &mut bpp_rng)? {
+            true => {
+                seps.remove(target);
+                n_failed_attempts = 0;
+                info!("[BPP] bin eliminated, {} bins in use", seps.len());
+                seps.iter().try_for_each(|sep| sol_listener.report(ReportType::ExplFeas, &sep.prob.save(), &instance))?;
+            }
+            false => {
+                info!("[BPP] unable to eliminate bin {}", target);
+                seps.iter_mut().zip(snapshots.iter())
+                    .for_each(|(sep, snapshot)| sep.rollback(snapshot, None));
+                n_failed_attempts += 1;
+                if disrupt_bins(&mut seps, target, terminator, sol_listener, &mut bpp_rng)? {
+                    seps.iter().try_for_each(|sep| sol_listener.report(ReportType::ExplFeas, &sep.prob.save(), &instance))?;
+                }
+            }
+        }
+    }
+
+    // Minimize the fill of the last bin, by shrinking it as a classic strip
+    sol_listener.enter_phase(Phase::Compression);
+    seps.sort_by_cached_key(|sep| Reverse(OrderedFloat(sep.prob.density())));
+    let mut last_sep = seps.pop().unwrap();
+    let used_width = last_sep.prob.layout.placed_items.values()
+        .map(|pi| pi.shape.bbox.x_max)
+        .fold(0.0, f32::max);
+    last_sep.change_strip_width(used_width, Some(used_width));
+
+    terminator.new_timeout(cmpr_config.time_limit.mul_f32(LAST_BIN_EXPLORE_TIME_RATIO));
+    let expl_sols = exploration_phase(&instance, &mut last_sep, sol_listener, terminator, expl_config)?;
+    let last_expl_sol = expl_sols.last()
+        .ok_or(SparrowError::NoFeasibleSolution { phase: Phase::Exploration })?
+        .clone();
+
+    terminator.new_timeout(cmpr_config.time_limit.mul_f32(1.0 - LAST_BIN_EXPLORE_TIME_RATIO));
+    let mut cmpr_sep = Separator::new(last_sep.instance, last_sep.prob, next_rng(), cmpr_config.separator_config, *tuning, ContainerMode::Strip);
+    let last_sol = compression_phase(&instance, &mut cmpr_sep, &last_expl_sol, sol_listener, terminator, cmpr_config)?;
+
+    let bin_sols = seps.iter()
+        .map(|sep| sep.prob.save())
+        .chain(std::iter::once(last_sol))
+        .collect_vec();
+
+    sol_listener.enter_phase(Phase::Finished);
+    info!("[BPP] finished, {} bins used, last bin width: {:.3}", bin_sols.len(), bin_sols.last().unwrap().strip_width());
+
+    Ok(bin_sols)
+}
+
+/// Greedily fills bins (first-fit) in LBF order. Fixed items are all placed in the first bin.
+fn construct_bins(instance: &SPInstance, config: &BinPackingConfig, tuning: &TuningConfig, mut next_rng: impl FnMut() -> Xoshiro256PlusPlus) -> Result<Vec<LBFBuilder>, SparrowError> {
+    let mut new_bin = || {
+        let mut bin = LBFBuilder::new(instance.clone(), next_rng(), *tuning);
+        bin.prob.change_strip_width(config.bin_width);
+        bin
+    };
+
+    let mut first_bin = new_bin();
+    first_bin.enforce_fixed_items();
+    let item_order = first_bin.placement_order();
+
+    let mut bins = vec![first_bin];
+    for item_id in item_order {
+        if !bins.iter_mut().any(|bin| bin.try_place_item(item_id)) {
+            let mut bin = new_bin();
+            if !bin.try_place_item(item_id) {
+                return Err(SparrowError::ItemDoesNotFit { item_id, strip_width: config.bin_width });
+            }
+            info!("[BPP] opened bin {} for item {}", bins.len(), item_id);
+            bins.push(bin);
+        }
+    }
+    Ok(bins)
+}
+
+/// Moves all items of the target bin to the other bins and tries to separate them.
+/// Returns whether all other bins are feasible afterwards. The target bin itself is left untouched.
+fn attempt_to_eliminate_bin(
+    seps: &mut [Separator],
+    target: usize,
+    term: &impl Terminator,
+    sol_listener: &mut impl SolutionListener,
+    config: &BinPackingConfig,
+    rng: &mut impl Rng,
+) -> Result<bool, SparrowError> {
+    let others = (0..seps.len()).filter(|i| *i != target).collect_vec();
+
+    let items_to_move = seps[target].prob.layout.placed_items.values()
+        .map(|pi| pi.item_id)
+        .collect_vec();
+    for item_id in items_to_move {
+        let dest = *others.iter().choose(rng).unwrap();
+        seps[dest].insert_item(item_id)?;
+    }
+
+    for round in 0..config.max_migration_rounds {
+        let mut infeasible_bins = vec![];
+        for &i in others.iter() {
+            if seps[i].ct.get_total_loss() > 0.0 {
+                let (_, ct) = seps[i].separate(term, sol_listener)?;
+                if ct.get_total_loss() > 0.0 {
+                    infeasible_bins.push(i);
+                }
+            }
+        }
+        if infeasible_bins.is_empty() {
+            return Ok(true);
+        }
+        if term.kill() {
+            return Ok(false);
+        }
+        debug!("[BPP] round {}: {} bins remain infeasible", round, infeasible_bins.len());
+
+        // Migrate the item with the highest loss of every infeasible bin to another bin
+        for i in infeasible_bins {
+            let Some(dest) = others.iter().filter(|j| **j != i).choose(rng).copied() else {
+                continue;
+            };
+            let worst_pk = seps[i].prob.layout.placed_items.iter()
+                .filter(|(_, pi)| !pi.is_locked)
+                .map(|(pk, _)| pk)
+                .max_by_key(|pk| OrderedFloat(seps[i].ct.get_loss(*pk)));
+
+            if let Some(pk) = worst_pk {
+                let item_id = seps[i].remove_item(pk).item_id;
+                debug!("[BPP] migrating item {} from bin {} to bin {}", item_id, i, dest);
+                seps[dest].insert_item(item_id)?;
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Disrupts the bins by migrating a random item of the `source` bin to another bin. If the item cannot be separated there,
+/// the item with the highest loss of that bin migrates back to the source bin in exchange.
+/// The disruption is only kept if both bins are feasible afterwards, returns whether it was kept.
+fn disrupt_bins(
+    seps: &mut [Separator],
+    source: usize,
+    term: &impl Terminator,
+    sol_listener: &mut impl SolutionListener,
+    rng: &mut impl Rng,
+) -> Result<bool, SparrowError> {
+    let Some(dest) = (0..seps.len()).filter(|i| *i != source).choose(rng) else {
+        return Ok(false);
+    };
+    let Some(pk) = seps[source].prob.layout.placed_items.iter()
+        .filter(|(_, pi)| !pi.is_locked)
+        .map(|(pk, _)| pk)
+        .choose(rng)
+    else {
+        return Ok(false);
+    };
+    let snapshots = [seps[source].prob.save(), seps[dest].prob.save()];
+
+    let item_id = seps[source].remove_item(pk).item_id;
+    seps[dest].insert_item(item_id)?;
+    let (_, ct) = seps[dest].separate(term, sol_listener)?;
+    if ct.get_total_loss() > 0.0 {
+        let worst_pk = seps[dest].prob.layout.placed_items.iter()
+            .filter(|(_, pi)| !pi.is_locked)
+            .map(|(pk, _)| pk)
+            .max_by_key(|pk| OrderedFloat(seps[dest].ct.get_loss(*pk)));
+        if let Some(pk) = worst_pk {
+            let swapped_id = seps[dest].remove_item(pk).item_id;
+            seps[source].insert_item(swapped_id)?;
+            debug!("[BPP] disruption: swapping back item {} from bin {} to bin {}", swapped_id, dest, source);
+        }
+        for i in [source, dest] {
+            if seps[i].ct.get_total_loss() > 0.0 {
+                seps[i].separate(term, sol_listener)?;
+            }
+        }
+    }
+
+    let feasible = [source, dest].iter().all(|&i| seps[i].ct.get_total_loss() == 0.0);
+    match feasible {
+        true => info!("[BPP] disruption: migrated item {} from bin {} to bin {}", item_id, source, dest),
+        false => {
+            debug!("[BPP] disruption of bins {} and {} failed, restoring them", source, dest);
+            seps[source].rollback(&snapshots[0], None);
+            seps[dest].rollback(&snapshots[1], None);
+        }
+    }
+    Ok(feasible)
+}
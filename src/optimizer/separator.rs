@@ -1,14 +1,22 @@
+use crate::config::{ContainerMode, TuningConfig};
+use crate::error::SparrowError;
+use crate::eval::lbf_evaluator::LBFEvaluator;
+use crate::eval::sample_eval::SampleEval;
+use crate::sample::search::search_placement;
+use crate::sample::uniform_sampler::UniformBBoxSampler;
 use crate::optimizer::worker::{SepStats, SeparatorWorker};
 use crate::optimizer::Terminator;
 use crate::quantify::tracker::{CTSnapshot, CollisionTracker};
 use crate::sample::search::SampleConfig;
+use crate::util::assertions;
 use crate::util::assertions::tracker_matches_layout;
 use crate::{FMT};
 use itertools::Itertools;
-use jagua_rs::entities::PItemKey;
+use jagua_rs::entities::{Instance, PItemKey};
 use jagua_rs::probs::spp::entities::{SPInstance, SPPlacement, SPProblem, SPSolution};
 use jagua_rs::geometry::DTransformation;
-use log::{debug, log, Level};
+use jagua_rs::geometry::primitives::Rect;
+use log::{debug, log, warn, Level};
 use ordered_float::OrderedFloat;
 use rand::{Rng, SeedableRng};
 use rayon::iter::IntoParallelRefMutIterator;
@@ -16,9 +24,12 @@ use rayon::iter::ParallelIterator;
 use rayon::ThreadPool;
 use jagua_rs::Instant;
 use rand_xoshiro::Xoshiro256PlusPlus;
+use serde::{Deserialize, Serialize};
+use anyhow::{ensure, Result};
 use crate::util::listener::{ReportType, SolutionListener};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SeparatorConfig {
     pub iter_no_imprv_limit: usize,
     pub strike_limit: usize,
@@ -27,6 +38,16 @@ pub struct SeparatorConfig {
     pub sample_config: SampleConfig,
 }
 
+impl SeparatorConfig {
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.iter_no_imprv_limit > 0, "iter_no_imprv_limit must be positive");
+        ensure!(self.strike_limit > 0, "strike_limit must be positive");
+        ensure!(self.n_workers > 0, "n_workers must be positive");
+        ensure!(self.sample_config.n_container_samples + self.sample_config.n_focussed_samples > 0, "sample_config must define at least one sample");
+        Ok(())
+    }
+}
+
 pub struct Separator {
     pub instance: SPInstance,
     pub rng: Xoshiro256PlusPlus,
@@ -34,12 +55,14 @@ pub struct Separator {
     pub ct: CollisionTracker,
     pub workers: Vec<SeparatorWorker>,
     pub config: SeparatorConfig,
+    pub tuning: TuningConfig,
+    pub container_mode: ContainerMode,
     pub thread_pool: Option<ThreadPool>,
 }
 
 impl Separator {
-    pub fn new(instance: SPInstance, prob: SPProblem, mut rng: Xoshiro256PlusPlus, config: SeparatorConfig) -> Self {
-        let ct = CollisionTracker::new(&prob.layout);
+    pub fn new(instance: SPInstance, prob: SPProblem, mut rng: Xoshiro256PlusPlus, config: SeparatorConfig, tuning: TuningConfig, container_mode: ContainerMode) -> Self {
+        let ct = CollisionTracker::new(&prob.layout, tuning);
         let workers = (0..config.n_workers).map(|_|
             SeparatorWorker {
                 instance: instance.clone(),
@@ -47,6 +70,7 @@ impl Separator {
                 ct: ct.clone(),
                 rng: Xoshiro256PlusPlus::seed_from_u64(rng.random()),
                 sample_config: config.sample_config,
+                tuning,
             }).collect();
 
         let pool = if cfg!(target_arch = "wasm32") {
@@ -64,12 +88,31 @@ impl Separator {
             ct,
             workers,
             config,
+            tuning,
+            container_mode,
             thread_pool: pool,
         }
     }
 
+    /// Random number generators of the workers
+    pub fn worker_rngs(&self) -> Vec<Xoshiro256PlusPlus> {
+        self.workers.iter().map(|w| w.rng.clone()).collect()
+    }
+
+    /// Restores the random number generators of the separator and its workers, e.g. from a checkpoint.
+    /// Workers without a generator in `worker_rngs` keep their own.
+    pub fn restore_rngs(&mut self, rng: Xoshiro256PlusPlus, worker_rngs: Vec<Xoshiro256PlusPlus>) {
+        self.rng = rng;
+        if worker_rngs.len() != self.workers.len() {
+            warn!("[SEP] restoring {} worker rngs for {} workers", worker_rngs.len(), self.workers.len());
+        }
+        for (worker, rng) in self.workers.iter_mut().zip(worker_rngs) {
+            worker.rng = rng;
+        }
+    }
+
     /// Algorithm 9 from https://doi.org/10.48550/arXiv.2509.13329
-    pub fn separate(&mut self, term: &impl Terminator, sol_listener: &mut impl SolutionListener) -> (SPSolution, CTSnapshot) {
+    pub fn separate(&mut self, term: &impl Terminator, sol_listener: &mut impl SolutionListener) -> Result<(SPSolution, CTSnapshot), SparrowError> {
         let mut min_loss_sol = (self.prob.save(), self.ct.save());
         let mut min_loss = self.ct.get_total_loss();
         log!(self.config.log_level,"[SEP] separating at width: {:.3} and loss: {} ", self.prob.strip_width(), FMT().fmt2(min_loss));
@@ -88,7 +131,7 @@ impl Separator {
 
             while n_iter_no_improvement < self.config.iter_no_imprv_limit {
                 let (loss_before, w_loss_before) = (self.ct.get_total_loss(), self.ct.get_total_weighted_loss(),);
-                sep_stats += self.move_items_multi();
+                sep_stats += self.move_items_multi()?;
                 let (loss, w_loss) = (self.ct.get_total_loss(), self.ct.get_total_weighted_loss(),);
 
                 debug!("[SEP] [s:{n_strikes},i:{n_iter}] ( ) l: {} -> {}, wl: {} -> {}, (min l: {})", FMT().fmt2(loss_before), FMT().fmt2(loss), FMT().fmt2(w_loss_before), FMT().fmt2(w_loss), FMT().fmt2(min_loss));
@@ -102,7 +145,7 @@ impl Separator {
                 } else if loss < min_loss {
                     //Not all collisions are resolved, but we found a new 'best' solution
                     log!(self.config.log_level,"[SEP] [s:{n_strikes},i:{n_iter}] (*) min_l: {}",FMT().fmt2(loss));
-                    sol_listener.report(ReportType::ExplImproving, &self.prob.save(), &self.instance);
+                    sol_listener.report(ReportType::ExplImproving, &self.prob.save(), &self.instance)?;
                     if loss < min_loss * 0.98 {
                         //Reset the `iter_no_improvement` counter if the best solution is a substantial improvement
                         n_iter_no_improvement = 0;
@@ -139,28 +182,28 @@ impl Separator {
         );
 
         // Return the best solution found: a feasible one if separation was successful, otherwise the 'least' infeasible one
-        (min_loss_sol.0, min_loss_sol.1)
+        Ok((min_loss_sol.0, min_loss_sol.1))
     }
 
     /// Algorithm 10 from https://doi.org/10.48550/arXiv.2509.13329
-    fn move_items_multi(&mut self) -> SepStats {
+    fn move_items_multi(&mut self) -> Result<SepStats, SparrowError> {
         let master_sol = self.prob.save();
 
         // Define the parallel execution closure
-        let mut separate_multi = || -> SepStats {
+        let mut separate_multi = || -> Result<SepStats, SparrowError> {
             self.workers.par_iter_mut().map(|worker| {
                 // Sync the workers with the master
                 worker.load(&master_sol, &self.ct);
                 // Let all of them run `move_items` with unique random orderings in which the items are moved
                 worker.move_items()
-            }).sum()
+            }).collect::<Result<Vec<_>, _>>().map(|stats| stats.into_iter().sum())
         };
 
         // Execute the parallel separation either using the local thread pool or the global one
         let sep_report = match self.thread_pool.as_mut() {
             Some(pool) => pool.install(&mut separate_multi),
             None => separate_multi(),
-        };
+        }?;
 
         debug!("[MOD] optimizers w_o's: {:?}",self.workers.iter().map(|opt| opt.ct.get_total_weighted_loss()).collect_vec());
 
@@ -174,11 +217,12 @@ impl Separator {
         self.prob.restore(&best_sol);
         self.ct = best_ct.clone();
 
-        sep_report
+        Ok(sep_report)
     }
 
     pub fn rollback(&mut self, sol: &SPSolution, ots: Option<&CTSnapshot>) {
         debug_assert!(sol.strip_width() == self.prob.strip_width());
+        debug_assert!(assertions::locked_items_unchanged(&self.prob.save().layout_snapshot, &sol.layout_snapshot), "rollback would move locked items");
         self.prob.restore(sol);
 
         match ots {
@@ -188,7 +232,7 @@ impl Separator {
             }
             None => {
                 //otherwise, rebuild it
-                self.ct = CollisionTracker::new(&self.prob.layout);
+                self.ct = CollisionTracker::new(&self.prob.layout, self.tuning);
             }
         }
     }
@@ -197,6 +241,7 @@ impl Separator {
         debug_assert!(tracker_matches_layout(&self.ct, &self.prob.layout));
 
         let item_id = self.prob.layout.placed_items[pk].item_id;
+        debug_assert!(!self.prob.layout.placed_items[pk].is_locked, "locked item {:?} should never be moved", pk);
 
         let old_loss = self.ct.get_loss(pk);
         let old_weighted_loss = self.ct.get_weighted_loss(pk);
@@ -220,27 +265,105 @@ impl Separator {
         new_pk
     }
 
+    /// Adds an item to the layout, the collision tracker is rebuilt to accommodate it.
+    pub fn place_item(&mut self, placement: SPPlacement) -> PItemKey {
+        let pk = self.prob.place_item(placement);
+        self.ct = CollisionTracker::new(&self.prob.layout, self.tuning);
+        pk
+    }
+
+    /// Adds an item to the layout at a collision-free position if one is found, otherwise at a random position within the container.
+    pub fn insert_item(&mut self, item_id: usize) -> Result<PItemKey, SparrowError> {
+        let d_transf = {
+            let layout = &self.prob.layout;
+            let item = self.instance.item(item_id);
+            let evaluator_factory = || LBFEvaluator::new(layout, item);
+            let (best_sample, _) = search_placement(layout, item, None, evaluator_factory, self.tuning.lbf_sample_config, &self.tuning, &mut self.rng);
+
+            match best_sample {
+                Some((d_transf, SampleEval::Clear { .. })) => d_transf,
+                _ => {
+                    let bbox = layout.container.outer_cd.bbox;
+                    UniformBBoxSampler::new(bbox, item, bbox)
+                        .ok_or(SparrowError::NoPlacementFound { item_id })?
+                        .sample(&mut self.rng)
+                }
+            }
+        };
+        Ok(self.place_item(SPPlacement { item_id, d_transf }))
+    }
+
+    /// Removes an item from the layout, the collision tracker is rebuilt without it.
+    pub fn remove_item(&mut self, pk: PItemKey) -> SPPlacement {
+        debug_assert!(!self.prob.layout.placed_items[pk].is_locked, "locked item {:?} should never be removed", pk);
+        let placement = self.prob.remove_item(pk);
+        self.ct = CollisionTracker::new(&self.prob.layout, self.tuning);
+        placement
+    }
+
+    pub fn strip_height(&self) -> f32 {
+        self.prob.instance.base_strip.fixed_height
+    }
+
+    /// Changes the width of the container, the height follows from the [`ContainerMode`].
     pub fn change_strip_width(&mut self, new_width: f32, split_position: Option<f32>) {
-        //if no split position is provided, use the center of the strip
-        let split_position = split_position.unwrap_or(self.prob.strip_width() / 2.0);
-        let delta = new_width - self.prob.strip_width();
+        let new_height = self.container_mode.height_for_width(new_width, self.strip_height());
+        let split_position = split_position.map(|x| (x, self.strip_height() / 2.0));
+        self.change_container(new_width, new_height, split_position);
+    }
+
+    /// Bounding box of all locked items in the layout, if any.
+    pub fn locked_items_bbox(&self) -> Option<Rect> {
+        self.prob.layout.placed_items.values()
+            .filter(|pi| pi.is_locked)
+            .map(|pi| pi.shape.bbox)
+            .reduce(Rect::bounding_rect)
+    }
+
+    /// Checks if a container of the given dimensions still encloses all locked items.
+    pub fn container_fits_locked_items(&self, width: f32, height: f32) -> bool {
+        self.locked_items_bbox()
+            .is_none_or(|bbox| bbox.x_max <= width && bbox.y_max <= height)
+    }
+
+    /// Changes both dimensions of the container.
+    /// Unlocked items right of (above) the split position are shifted along with the right (top) edge of the container.
+    /// If no split position is provided, the center of the container is used, pushed right of (above) all locked items.
+    pub fn change_container(&mut self, new_width: f32, new_height: f32, split_position: Option<(f32, f32)>) {
+        debug_assert!(self.container_fits_locked_items(new_width, new_height), "container of {new_width} x {new_height} would not enclose all locked items");
+        let (split_x, split_y) = split_position.unwrap_or_else(|| {
+            let center = (self.prob.strip_width() / 2.0, self.strip_height() / 2.0);
+            match self.locked_items_bbox() {
+                Some(bbox) => (f32::max(center.0, bbox.x_max), f32::max(center.1, bbox.y_max)),
+                None => center,
+            }
+        });
+        let delta_x = new_width - self.prob.strip_width();
+        let delta_y = new_height - self.strip_height();
 
-        //shift all items right of the split position
+        //shift all unlocked items right of or above the split position
         let items_to_shift = self.prob.layout.placed_items.iter()
-            .filter(|(_, pi)| pi.shape.centroid().0 > split_position)
-            .map(|(k, pi)| (k, pi.d_transf))
+            .filter(|(_, pi)| !pi.is_locked)
+            .map(|(k, pi)| {
+                let centroid = pi.shape.centroid();
+                let dx = if centroid.0 > split_x { delta_x } else { 0.0 };
+                let dy = if centroid.1 > split_y { delta_y } else { 0.0 };
+                (k, pi.d_transf, (dx, dy))
+            })
+            .filter(|(_, _, (dx, dy))| *dx != 0.0 || *dy != 0.0)
             .collect_vec();
 
-        for (pik, dtransf) in items_to_shift {
+        for (pik, dtransf, delta) in items_to_shift {
             let existing_transf = dtransf.compose();
-            let new_transf = existing_transf.translate((delta, 0.0));
+            let new_transf = existing_transf.translate(delta);
             self.move_item(pik, new_transf.decompose());
         }
 
+        self.prob.instance.base_strip.fixed_height = new_height;
         self.prob.change_strip_width(new_width);
 
         //rebuild the collision tracker
-        self.ct = CollisionTracker::new(&self.prob.layout);
+        self.ct = CollisionTracker::new(&self.prob.layout, self.tuning);
 
         //rebuild the workers
         self.workers.iter_mut().for_each(|opt| {
@@ -250,8 +373,9 @@ impl Separator {
                 ct: self.ct.clone(),
                 rng: Xoshiro256PlusPlus::seed_from_u64(self.rng.random()),
                 sample_config: self.config.sample_config,
+                tuning: self.tuning,
             };
         });
-        debug!("[SEP] changed strip width to {:.3}", new_width);
+        debug!("[SEP] changed container to {:.3} x {:.3}", new_width, new_height);
     }
 }
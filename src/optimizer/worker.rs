@@ -1,4 +1,6 @@
 use crate::eval::sep_evaluator::SeparationEvaluator;
+use crate::config::TuningConfig;
+use crate::error::SparrowError;
 use crate::quantify::tracker::CollisionTracker;
 use crate::sample::search;
 use crate::sample::search::SampleConfig;
@@ -21,6 +23,7 @@ pub struct SeparatorWorker {
     pub ct: CollisionTracker,
     pub rng: Xoshiro256PlusPlus,
     pub sample_config: SampleConfig,
+    pub tuning: TuningConfig,
 }
 
 impl SeparatorWorker {
@@ -32,9 +35,11 @@ impl SeparatorWorker {
     }
 
     /// Algorithm 5 from https://doi.org/10.48550/arXiv.2509.13329
-    pub fn move_items(&mut self) -> SepStats {
-        // Collect all colliding items in a random order
-        let candidates = self.prob.layout.placed_items.keys()
+    pub fn move_items(&mut self) -> Result<SepStats, SparrowError> {
+        // Collect all colliding (unlocked) items in a random order
+        let candidates = self.prob.layout.placed_items.iter()
+            .filter(|(_, pi)| !pi.is_locked)
+            .map(|(pk, _)| pk)
             .filter(|pk| self.ct.get_loss(*pk) > 0.0)
             .collect_vec()
             .tap_mut(|v| v.shuffle(&mut self.rng));
@@ -73,10 +78,11 @@ impl SeparatorWorker {
 		    Some(pk), 
 		    evaluator_factory, // Pass closure here
 		    self.sample_config, 
+		    &self.tuning,
 		    &mut self.rng
 		);
 
-                let (new_dt, _eval) = best_sample.expect("search_placement should always return a sample");
+                let (new_dt, _eval) = best_sample.ok_or(SparrowError::NoPlacementFound { item_id })?;
 
                 // Move the item to the new position
                 self.move_item(pk, new_dt);
@@ -84,13 +90,14 @@ impl SeparatorWorker {
                 total_evals += n_evals;
             }
         }
-        SepStats { total_moves, total_evals }
+        Ok(SepStats { total_moves, total_evals })
     }
 
     pub fn move_item(&mut self, pk: PItemKey, d_transf: DTransformation) -> PItemKey {
         debug_assert!(tracker_matches_layout(&self.ct, &self.prob.layout));
 
         let item = self.instance.item(self.prob.layout.placed_items[pk].item_id);
+        debug_assert!(!self.prob.layout.placed_items[pk].is_locked, "locked item {:?} should never be moved", pk);
 
         let (old_l, old_w_l) = (self.ct.get_loss(pk), self.ct.get_weighted_loss(pk));
 
@@ -1,6 +1,5 @@
 use jagua_rs::geometry::geo_traits::DistanceTo;
 use jagua_rs::geometry::primitives::{Rect, SPolygon};
-use crate::consts::OVERLAP_PROXY_EPSILON_DIAM_RATIO;
 use crate::quantify::overlap_proxy::overlap_area_proxy;
 
 pub mod overlap_proxy;
@@ -11,9 +10,10 @@ pub mod simd;
 
 /// Quantifies a collision between two simple polygons.
 /// Algorithm 4 from https://doi.org/10.48550/arXiv.2509.13329
+/// `epsilon_diam_ratio` is the epsilon of the overlap proxy, as a ratio of the largest diameter of the two shapes.
 #[inline(always)]
-pub fn quantify_collision_poly_poly(s1: &SPolygon, s2: &SPolygon) -> f32 {
-    let epsilon = f32::max(s1.diameter, s2.diameter) * OVERLAP_PROXY_EPSILON_DIAM_RATIO;
+pub fn quantify_collision_poly_poly(s1: &SPolygon, s2: &SPolygon, epsilon_diam_ratio: f32) -> f32 {
+    let epsilon = f32::max(s1.diameter, s2.diameter) * epsilon_diam_ratio;
 
     let overlap_proxy = overlap_area_proxy(s1.surrogate(), s2.surrogate(), epsilon) + epsilon.powi(2);
 
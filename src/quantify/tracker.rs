@@ -1,7 +1,7 @@
 use jagua_rs::collision_detection::hazards::collector::{BasicHazardCollector, HazardCollector};
 use jagua_rs::collision_detection::hazards::HazardEntity;
 use jagua_rs::entities::{Layout, PItemKey};
-use crate::consts::{GLS_WEIGHT_DECAY, GLS_WEIGHT_MAX_INC_RATIO, GLS_WEIGHT_MIN_INC_RATIO};
+use crate::config::TuningConfig;
 use crate::quantify::pair_matrix::PairMatrix;
 use crate::quantify::{quantify_collision_poly_container, quantify_collision_poly_poly};
 use crate::util::assertions::tracker_matches_layout;
@@ -16,12 +16,14 @@ pub struct CollisionTracker {
     pub pk_idx_map: SecondaryMap<PItemKey, usize>,
     pub pair_collisions: PairMatrix,
     pub container_collisions: Vec<CTEntry>,
+    /// Overlap proxy and GLS weight parameters
+    pub tuning: TuningConfig,
 }
 
 pub type CTSnapshot = CollisionTracker;
 
 impl CollisionTracker {
-    pub fn new(l: &Layout) -> Self {
+    pub fn new(l: &Layout, tuning: TuningConfig) -> Self {
         let size = l.placed_items.len();
 
         // Create the tracker
@@ -32,6 +34,7 @@ impl CollisionTracker {
                 .collect(),
             pair_collisions: PairMatrix::new(size),
             container_collisions: vec![CTEntry { weight: 1.0, loss: 0.0 }; size],
+            tuning,
         };
 
         // Recompute the loss for all items
@@ -61,18 +64,25 @@ impl CollisionTracker {
         // Remove the item itself from the detector
         collector.remove_by_entity(&HazardEntity::from((pk, pi)));
 
-        // For each colliding hazard, quantify the collision and store it in the tracker
+        // For each colliding hazard, quantify the collision and store it in the tracker.
+        // Locked items behave like hazards: collisions among them or with the exterior cannot be resolved and are ignored.
         for (_, haz) in collector.iter() {
             match haz {
                 HazardEntity::PlacedItem { pk: other_pk, .. } => {
+                    if pi.is_locked && l.placed_items[*other_pk].is_locked {
+                        continue;
+                    }
                     let shape_other = &l.placed_items[*other_pk].shape;
                     let idx_other = self.pk_idx_map[*other_pk];
 
-                    let loss = quantify_collision_poly_poly(shape, shape_other);
+                    let loss = quantify_collision_poly_poly(shape, shape_other, self.tuning.overlap_proxy_epsilon_diam_ratio);
                     assert!(loss > 0.0, "loss for a collision should be > 0.0");
                     self.pair_collisions[(idx, idx_other)].loss = loss;
                 }
                 HazardEntity::Exterior => {
+                    if pi.is_locked {
+                        continue;
+                    }
                     let loss = quantify_collision_poly_container(shape, l.container.outer_cd.bbox);
                     assert!(loss > 0.0, "loss for a collision should be > 0.0");
                     self.container_collisions[idx].loss = loss;
@@ -116,6 +126,7 @@ impl CollisionTracker {
             .chain(self.container_collisions.iter())
             .map(|e| e.loss)
             .fold(0.0, |a, b| a.max(b));
+        let tuning = self.tuning;
 
         // Go over all entries (pairs) and modify their weights.
         for e in self.pair_collisions.data.iter_mut()
@@ -123,11 +134,11 @@ impl CollisionTracker {
             let multiplier = match e.loss == 0.0 {
                 true => {
                     // No collision at the moment, slowly decay the weight back to 1.0
-                    GLS_WEIGHT_DECAY
+                    tuning.gls_weight_decay
                 },
                 false => {
                     // Collision detected, increase the weight based on 'how bad' the collision is relative to the worst collision
-                    GLS_WEIGHT_MIN_INC_RATIO + (GLS_WEIGHT_MAX_INC_RATIO - GLS_WEIGHT_MIN_INC_RATIO) * (e.loss / max_loss)
+                    tuning.gls_weight_min_inc_ratio + (tuning.gls_weight_max_inc_ratio - tuning.gls_weight_min_inc_ratio) * (e.loss / max_loss)
                 },
             };
             e.weight = (e.weight * multiplier).max(1.0);
@@ -1,4 +1,3 @@
-use crate::consts::{CD_STEP_FAIL, CD_STEP_SUCCESS};
 use crate::eval::sample_eval::{SampleEval, SampleEvaluator};
 use jagua_rs::geometry::DTransformation;
 use log::trace;
@@ -18,6 +17,10 @@ pub struct CDConfig {
     pub r_step_limit: f32,
     /// Defines whether the wiggle axis (rotation) is enabled
     pub wiggle: bool,
+    /// Step size multiplier on success
+    pub step_success: f32,
+    /// Step size multiplier on failure
+    pub step_fail: f32,
 }
 
 /// Refines an initial 'sample' (transformation and evaluation) into a local minimum using a coordinate descent inspired algorithm.
@@ -40,6 +43,8 @@ pub fn refine_coord_desc(
         r_step: cd_config.r_step_init,
         r_step_limit: cd_config.r_step_limit,
         wiggle: cd_config.wiggle,
+        step_success: cd_config.step_success,
+        step_fail: cd_config.step_fail,
     };
 
     // From the CD state, ask for candidate positions to evaluate. If none provided, stop.
@@ -79,6 +84,10 @@ struct CoordinateDescent {
     pub r_step_limit: f32,
     /// Defines whether the wiggle axis is enabled
     pub wiggle: bool,
+    /// Step size multiplier on success
+    pub step_success: f32,
+    /// Step size multiplier on failure
+    pub step_fail: f32,
 }
 
 impl CoordinateDescent {
@@ -124,7 +133,7 @@ impl CoordinateDescent {
         }
 
         // Determine the step size multiplier depending on whether the new evaluation is better or worse.
-        let m = if better { CD_STEP_SUCCESS } else { CD_STEP_FAIL };
+        let m = if better { self.step_success } else { self.step_fail };
 
         // Apply the step size multiplier to the relevant steps for the current axis
         match self.axis {
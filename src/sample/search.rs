@@ -1,7 +1,8 @@
 use jagua_rs::entities::{Item, Layout, PItemKey};
 use jagua_rs::geometry::DTransformation;
 use jagua_rs::geometry::geo_enums::RotationRange;
-use crate::consts::{SND_REFINE_CD_TL_RATIOS, PRE_REFINE_CD_TL_RATIOS, UNIQUE_SAMPLE_THRESHOLD, PRE_REFINE_CD_R_STEPS, SND_REFINE_CD_R_STEPS};
+use crate::config::TuningConfig;
+use serde::{Deserialize, Serialize};
 use crate::eval::sample_eval::{SampleEval, SampleEvaluator};
 use crate::sample::best_samples::BestSamples;
 use crate::sample::coord_descent::{refine_coord_desc, CDConfig};
@@ -10,7 +11,8 @@ use log::debug;
 use rand::Rng;
 use rayon::prelude::*; // Ensure rayon is imported
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SampleConfig {
     pub n_container_samples: usize,
     pub n_focussed_samples: usize,
@@ -24,6 +26,7 @@ pub fn search_placement<E, F>(
     ref_pk: Option<PItemKey>,
     evaluator_factory: F, // [CHANGE] Accept a Factory instead of an Instance
     sample_config: SampleConfig,
+    tuning: &TuningConfig,
     rng: &mut impl Rng
 ) -> (Option<(DTransformation, SampleEval)>, usize) 
 where 
@@ -31,7 +34,7 @@ where
     F: Fn() -> E + Sync + Send, // Factory must be thread-safe
 {
     let item_min_dim = f32::min(item.shape_cd.bbox.width(), item.shape_cd.bbox.height());
-    let mut best_samples = BestSamples::new(sample_config.n_coord_descents, item_min_dim * UNIQUE_SAMPLE_THRESHOLD);
+    let mut best_samples = BestSamples::new(sample_config.n_coord_descents, item_min_dim * tuning.unique_sample_threshold);
 
     // 1. Collect all samples first
     let mut samples = Vec::with_capacity(sample_config.n_focussed_samples + sample_config.n_container_samples + 1);
@@ -83,13 +86,13 @@ where
 
     // Prerefine
     for start in best_samples.samples.clone() {
-        let descended = refine_coord_desc(start, &mut evaluator, prerefine_cd_config(item), rng);
+        let descended = refine_coord_desc(start, &mut evaluator, prerefine_cd_config(item, tuning), rng);
         best_samples.report(descended.0, descended.1);
     }
 
     // Final refine
     let final_sample = best_samples.best().map(|s|
-        refine_coord_desc(s, &mut evaluator, final_refine_cd_config(item), rng)
+        refine_coord_desc(s, &mut evaluator, final_refine_cd_config(item, tuning), rng)
     );
 
     debug!("[S] {} samples evaluated, final: {:?}", samples.len() + evaluator.n_evals(), final_sample);
@@ -97,28 +100,32 @@ where
 }
 
 // ... [Keep helper config functions same as original]
-fn prerefine_cd_config(item: &Item) -> CDConfig {
+fn prerefine_cd_config(item: &Item, tuning: &TuningConfig) -> CDConfig {
     // ... same content ...
     let item_min_dim = f32::min(item.shape_cd.bbox.width(), item.shape_cd.bbox.height());
     let wiggle = item.allowed_rotation == RotationRange::Continuous;
     CDConfig {
-        t_step_init: item_min_dim * PRE_REFINE_CD_TL_RATIOS.0,
-        t_step_limit: item_min_dim * PRE_REFINE_CD_TL_RATIOS.1,
-        r_step_init: PRE_REFINE_CD_R_STEPS.0,
-        r_step_limit: PRE_REFINE_CD_R_STEPS.1,
+        t_step_init: item_min_dim * tuning.pre_refine_cd_tl_ratios.0,
+        t_step_limit: item_min_dim * tuning.pre_refine_cd_tl_ratios.1,
+        r_step_init: tuning.pre_refine_cd_r_steps.0,
+        r_step_limit: tuning.pre_refine_cd_r_steps.1,
         wiggle,
+        step_success: tuning.cd_step_success,
+        step_fail: tuning.cd_step_fail,
     }
 }
 
-fn final_refine_cd_config(item: &Item) -> CDConfig {
+fn final_refine_cd_config(item: &Item, tuning: &TuningConfig) -> CDConfig {
     // ... same content ...
     let item_min_dim = f32::min(item.shape_cd.bbox.width(), item.shape_cd.bbox.height());
     let wiggle = item.allowed_rotation == RotationRange::Continuous;
     CDConfig {
-        t_step_init: item_min_dim * SND_REFINE_CD_TL_RATIOS.0,
-        t_step_limit: item_min_dim * SND_REFINE_CD_TL_RATIOS.1,
-        r_step_init: SND_REFINE_CD_R_STEPS.0,
-        r_step_limit: SND_REFINE_CD_R_STEPS.1,
-        wiggle
+        t_step_init: item_min_dim * tuning.snd_refine_cd_tl_ratios.0,
+        t_step_limit: item_min_dim * tuning.snd_refine_cd_tl_ratios.1,
+        r_step_init: tuning.snd_refine_cd_r_steps.0,
+        r_step_limit: tuning.snd_refine_cd_r_steps.1,
+        wiggle,
+        step_success: tuning.cd_step_success,
+        step_fail: tuning.cd_step_fail,
     }
 }
@@ -8,7 +8,7 @@ use log::warn;
 use std::collections::HashSet;
 use jagua_rs::collision_detection::hazards::collector::{BasicHazardCollector, HazardCollector};
 use jagua_rs::collision_detection::hazards::HazardEntity;
-use jagua_rs::entities::Layout;
+use jagua_rs::entities::{Layout, LayoutSnapshot};
 use jagua_rs::geometry::primitives::SPolygon;
 use jagua_rs::io::svg::SvgDrawOptions;
 use jagua_rs::probs::spp::entities::SPProblem;
@@ -24,10 +24,15 @@ pub fn tracker_matches_layout(ct: &CollisionTracker, l: &Layout) -> bool {
         assert_eq!(ct.get_pair_loss(pk1, pk1), 0.0);
         for (pk2, pi2) in l.placed_items.iter().filter(|(k, _)| *k != pk1) {
             let stored_loss = ct.get_pair_loss(pk1, pk2);
+            if pi1.is_locked && pi2.is_locked {
+                // collisions between locked items are never tracked
+                assert_eq!(stored_loss, 0.0);
+                continue;
+            }
             match collector.iter().any(|(_, he)| he == &HazardEntity::from((pk2, pi2))) {
                 true => {
-                    let calc_loss = quantify_collision_poly_poly(&pi1.shape, &pi2.shape);
-                    let calc_loss_r = quantify_collision_poly_poly(&pi2.shape, &pi1.shape);
+                    let calc_loss = quantify_collision_poly_poly(&pi1.shape, &pi2.shape, ct.tuning.overlap_proxy_epsilon_diam_ratio);
+                    let calc_loss_r = quantify_collision_poly_poly(&pi2.shape, &pi1.shape, ct.tuning.overlap_proxy_epsilon_diam_ratio);
                     if !approx_eq!(f32,calc_loss,stored_loss,epsilon = 0.10 * stored_loss) && !approx_eq!(f32,calc_loss_r,stored_loss, epsilon = 0.10 * stored_loss) {
                         let mut opp_collector = BasicHazardCollector::new();
                         l.cde().collect_poly_collisions(&pi2.shape, &mut opp_collector);
@@ -91,7 +96,7 @@ pub fn tracker_matches_layout(ct: &CollisionTracker, l: &Layout) -> bool {
                 }
                 false => {
                     if stored_loss != 0.0 {
-                        let calc_loss = quantify_collision_poly_poly(&pi1.shape, &pi2.shape);
+                        let calc_loss = quantify_collision_poly_poly(&pi1.shape, &pi2.shape, ct.tuning.overlap_proxy_epsilon_diam_ratio);
                         let mut opp_collector = BasicHazardCollector::new();
                         l.cde().collect_poly_collisions(&pi2.shape, &mut opp_collector);
                         opp_collector.remove_by_entity(&HazardEntity::from((pk2, pi2)));
@@ -123,7 +128,7 @@ pub fn tracker_matches_layout(ct: &CollisionTracker, l: &Layout) -> bool {
                 }
             }
         }
-        if collector.contains_entity(&HazardEntity::Exterior) {
+        if collector.contains_entity(&HazardEntity::Exterior) && !pi1.is_locked {
             let stored_loss = ct.get_container_loss(pk1);
             let calc_loss = quantify_collision_poly_container(&pi1.shape, l.container.outer_cd.bbox);
             assert_approx_eq!(f32, stored_loss, calc_loss, ulps = 5);
@@ -163,4 +168,15 @@ pub fn strip_width_is_in_check(prob: &SPProblem) -> bool {
     ).sum::<f32>();
     
     prob.strip_width() < 2.0 * (diameters_of_all_items)
-}
\ No newline at end of file
+}
+
+/// Checks if every locked item in `a` is present at the exact same position in `b`, and vice versa.
+pub fn locked_items_unchanged(a: &LayoutSnapshot, b: &LayoutSnapshot) -> bool {
+    let collect_locked = |l: &LayoutSnapshot| l.placed_items.values()
+        .filter(|pi| pi.is_locked)
+        .map(|pi| (pi.item_id, pi.d_transf))
+        .collect_vec();
+
+    let (locked_a, locked_b) = (collect_locked(a), collect_locked(b));
+    locked_a.len() == locked_b.len() && locked_a.iter().all(|p| locked_b.contains(p))
+}
@@ -0,0 +1,189 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use jagua_rs::geometry::DTransformation;
+use jagua_rs::Instant;
+use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use jagua_rs::probs::spp::io::ext_repr::{ExtSPInstance, ExtSPSolution};
+use log::{info, warn, Level};
+use rand_xoshiro::Xoshiro256PlusPlus;
+use serde::{Deserialize, Serialize};
+use crate::config::SparrowConfig;
+use crate::EPOCH;
+use crate::optimizer::ResumeState;
+use crate::util::{io, verify};
+use crate::consts::DEFAULT_VERIFY_TOLERANCE_RATIO;
+use crate::error::SparrowError;
+use crate::util::listener::{Phase, PhaseState, ReportType, SolutionListener};
+
+/// Everything needed to resume an interrupted run of [`crate::optimizer::optimize`] with [`crate::optimizer::resume`]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ExtCheckpoint {
+    #[serde(flatten)]
+    pub instance: ExtSPInstance,
+    pub config: SparrowConfig,
+    /// Seed of the run that was checkpointed
+    pub rng_seed: u64,
+    /// State of the random number generator of the separator of the phase
+    pub rng: Xoshiro256PlusPlus,
+    /// State of the random number generators of the workers of the separator
+    #[serde(default)]
+    pub worker_rngs: Vec<Xoshiro256PlusPlus>,
+    /// Number of failed attempts of the compression phase
+    #[serde(default)]
+    pub n_failed_attempts: i32,
+    pub phase: Phase,
+    /// Seconds spent optimizing before the checkpoint
+    pub elapsed: f64,
+    /// Seconds left in the exploration phase
+    pub expl_time_left: f64,
+    /// Seconds left in the compression phase
+    pub cmpr_time_left: f64,
+    pub best_solution: ExtSPSolution,
+    pub best_height: f32,
+    pub current_solution: ExtSPSolution,
+    pub current_height: f32,
+    /// Infeasible solutions (and their loss) in the current container
+    pub infeasible_pool: Vec<(ExtSPSolution, f32)>,
+    /// Indices of the locked (fixed or pinned) placements of the best solution, which are locked in all solutions
+    #[serde(default)]
+    pub locked_placements: Vec<usize>,
+}
+
+impl ExtCheckpoint {
+    /// Checkpoint of the state of a phase, `elapsed` is the time spent optimizing so far
+    pub fn new(state: &PhaseState, ext_instance: &ExtSPInstance, config: SparrowConfig, rng_seed: u64, elapsed: Duration) -> Self {
+        let (expl_time_left, cmpr_time_left) = match state.phase {
+            Phase::Construction | Phase::Exploration => (state.time_left, config.cmpr_cfg.time_limit),
+            Phase::Compression | Phase::Finished => (Duration::ZERO, state.time_left),
+        };
+        let export = |sol: &SPSolution| jagua_rs::probs::spp::io::export(state.instance, sol, *EPOCH);
+
+        ExtCheckpoint {
+            instance: ext_instance.clone(),
+            config,
+            rng_seed,
+            rng: state.rng.clone(),
+            worker_rngs: state.worker_rngs.clone(),
+            n_failed_attempts: state.n_failed_attempts,
+            phase: state.phase,
+            elapsed: elapsed.as_secs_f64(),
+            expl_time_left: expl_time_left.as_secs_f64(),
+            cmpr_time_left: cmpr_time_left.as_secs_f64(),
+            best_solution: export(state.best),
+            best_height: state.best_height,
+            current_solution: export(state.current),
+            current_height: state.current_height,
+            infeasible_pool: state.infeas_sol_pool.iter()
+                .map(|(sol, loss)| (export(sol), *loss))
+                .collect(),
+            // The placements of the export are in the same order as the placed items of the solution
+            locked_placements: state.best.layout_snapshot.placed_items.values()
+                .positions(|pi| pi.is_locked)
+                .collect(),
+        }
+    }
+
+    /// Converts the checkpoint into the state to resume from. `instance` must be imported from the checkpoint's instance.
+    /// The locked placements are locked again in every solution, so they are not moved after resuming.
+    pub fn resume_state(&self, instance: &SPInstance) -> ResumeState {
+        let best_sol = jagua_rs::probs::spp::io::import_solution(instance, &self.best_solution);
+        let locked = best_sol.layout_snapshot.placed_items.values()
+            .enumerate()
+            .filter(|(i, _)| self.locked_placements.contains(i))
+            .map(|(_, pi)| (pi.item_id, pi.d_transf))
+            .collect_vec();
+        let tolerance = instance.base_strip.fixed_height * DEFAULT_VERIFY_TOLERANCE_RATIO as f32;
+
+        let import = |ext_sol: &ExtSPSolution, height: f32| {
+            let mut sol = jagua_rs::probs::spp::io::import_solution(instance, ext_sol);
+            sol.strip.fixed_height = height;
+            lock_placements(&mut sol, &locked, tolerance);
+            sol
+        };
+        ResumeState {
+            phase: self.phase,
+            best_sol: import(&self.best_solution, self.best_height),
+            best_height: self.best_height,
+            current_sol: import(&self.current_solution, self.current_height),
+            current_height: self.current_height,
+            infeas_sol_pool: self.infeasible_pool.iter()
+                .map(|(ext_sol, loss)| (import(ext_sol, self.current_height), *loss))
+                .collect(),
+            rng: self.rng.clone(),
+            worker_rngs: self.worker_rngs.clone(),
+            n_failed_attempts: self.n_failed_attempts,
+            expl_time_left: Duration::from_secs_f64(self.expl_time_left),
+            cmpr_time_left: Duration::from_secs_f64(self.cmpr_time_left),
+        }
+    }
+}
+
+/// Locks a placed item of the solution for every locked placement, matched on item id and position
+fn lock_placements(sol: &mut SPSolution, locked: &[(usize, DTransformation)], tolerance: f32) {
+    for (item_id, d_transf) in locked {
+        let placed_item = sol.layout_snapshot.placed_items.values_mut()
+            .find(|pi| pi.item_id == *item_id && !pi.is_locked && verify::same_placement(&pi.d_transf, d_transf, tolerance));
+        match placed_item {
+            Some(pi) => pi.is_locked = true,
+            None => warn!("[CKPT] locked placement of item {item_id} is missing from a checkpointed solution"),
+        }
+    }
+}
+
+pub fn read_checkpoint(path: &Path) -> Result<ExtCheckpoint> {
+    let checkpoint_str = fs::read_to_string(path).context("could not read checkpoint file")?;
+    serde_json::from_str(&checkpoint_str).context("could not parse checkpoint file")
+}
+
+/// Listener which periodically writes a checkpoint of the running phase to disk.
+/// Every checkpoint overwrites the previous one atomically, so a valid checkpoint is always present.
+pub struct Checkpointer {
+    path: PathBuf,
+    interval: Duration,
+    last_checkpoint: Instant,
+    ext_instance: ExtSPInstance,
+    config: SparrowConfig,
+    rng_seed: u64,
+    start: Instant,
+    /// Time spent optimizing before this run was resumed
+    prev_elapsed: Duration,
+}
+
+impl Checkpointer {
+    pub fn new(path: PathBuf, interval: Duration, ext_instance: ExtSPInstance, config: SparrowConfig, rng_seed: u64, prev_elapsed: Duration) -> Self {
+        Checkpointer {
+            path,
+            interval,
+            last_checkpoint: Instant::now(),
+            ext_instance,
+            config,
+            rng_seed,
+            start: Instant::now(),
+            prev_elapsed,
+        }
+    }
+}
+
+impl SolutionListener for Checkpointer {
+    fn report(&mut self, _report: ReportType, _solution: &SPSolution, _instance: &SPInstance) -> Result<(), SparrowError> {
+        Ok(())
+    }
+
+    fn checkpoint_due(&self) -> bool {
+        self.last_checkpoint.elapsed() >= self.interval
+    }
+
+    fn checkpoint(&mut self, state: &PhaseState) {
+        let elapsed = self.prev_elapsed + self.start.elapsed();
+        let checkpoint = ExtCheckpoint::new(state, &self.ext_instance, self.config, self.rng_seed, elapsed);
+
+        match io::write_json_atomic(&checkpoint, &self.path, Level::Debug) {
+            Ok(()) => info!("[CKPT] checkpoint written ({:?} phase, {:.1}s elapsed)", state.phase, elapsed.as_secs_f32()),
+            Err(e) => warn!("[CKPT] failed to write checkpoint: {e:#}"),
+        }
+        self.last_checkpoint = Instant::now();
+    }
+}
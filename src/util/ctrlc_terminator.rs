@@ -1,14 +1,23 @@
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::Duration;
 use jagua_rs::Instant;
 use log::warn;
 use crate::util::terminator::Terminator;
 
+/// Terminator which reacts to Ctrl-C in two stages:
+/// the first Ctrl-C ends the current phase, the second one ends all remaining phases,
+/// so the optimization returns the best feasible solution found so far.
+/// Clones share the Ctrl-C state but track their own phase, so concurrent runs all see every Ctrl-C.
 #[derive(Debug, Clone)]
 pub struct CtrlCTerminator {
     pub timeout: Option<Instant>,
-    pub ctrlc: Arc<AtomicBool>,
+    /// Set by the second Ctrl-C, never reset
+    pub abort: Arc<AtomicBool>,
+    /// Number of Ctrl-C's received
+    pub n_interrupts: Arc<AtomicUsize>,
+    /// Number of Ctrl-C's received when the current phase of this terminator started
+    pub phase_interrupts: usize,
 }
 
 impl Default for CtrlCTerminator {
@@ -20,34 +29,55 @@ impl Default for CtrlCTerminator {
 impl CtrlCTerminator {
     /// Sets up the handler for Ctrl-C (only call once)
     pub fn new() -> Self {
-        let ctrlc = Arc::new(AtomicBool::new(false));
-        let c = ctrlc.clone();
+        let terminator = Self::detached();
+        let t = terminator.clone();
 
-        ctrlc::set_handler(move || {
-            warn!(" terminating...");
-            c.store(true, Ordering::SeqCst);
-        }).expect("Error setting Ctrl-C handler");
+        ctrlc::set_handler(move || t.interrupt()).expect("Error setting Ctrl-C handler");
 
+        terminator
+    }
+
+    /// Creates a terminator without a Ctrl-C handler, Ctrl-C's can be simulated with [`CtrlCTerminator::interrupt`]
+    pub fn detached() -> Self {
         Self {
             timeout: None,
-            ctrlc,
+            abort: Arc::new(AtomicBool::new(false)),
+            n_interrupts: Arc::new(AtomicUsize::new(0)),
+            phase_interrupts: 0,
+        }
+    }
+
+    /// Handles a Ctrl-C: the first one ends the current phase, any subsequent one ends all phases
+    pub fn interrupt(&self) {
+        let n_interrupts = self.n_interrupts.fetch_add(1, Ordering::SeqCst) + 1;
+        if n_interrupts == 1 {
+            warn!(" finishing current phase (press Ctrl-C again to stop and save the best solution)...");
+        } else {
+            warn!(" stopping, saving the best solution...");
+            self.abort.store(true, Ordering::SeqCst);
         }
     }
+
+    /// Whether the optimization was stopped by a second Ctrl-C
+    pub fn aborted(&self) -> bool {
+        self.abort.load(Ordering::SeqCst)
+    }
 }
 
 impl Terminator for CtrlCTerminator {
     fn kill(&self) -> bool {
         self.timeout.is_some_and(|timeout| Instant::now() > timeout)
-            || self.ctrlc.load(Ordering::SeqCst)
+            || self.n_interrupts.load(Ordering::SeqCst) > self.phase_interrupts
+            || self.abort.load(Ordering::SeqCst)
     }
 
     fn new_timeout(&mut self, timeout: Duration){
-        // Reset the Ctrl-C flag and set a new timeout
-        self.ctrlc.store(false, Ordering::SeqCst);
+        // Ctrl-C's received so far only ended the previous phase (the abort flag stays set), the shared state is left untouched
+        self.phase_interrupts = self.n_interrupts.load(Ordering::SeqCst);
         self.timeout = Some(Instant::now() + timeout);
     }
 
     fn timeout_at(&self) -> Option<Instant> {
         self.timeout
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,586 @@
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+use anyhow::{bail, ensure, Context, Result};
+use itertools::Itertools;
+use log::debug;
+use serde_json::json;
+use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use jagua_rs::probs::spp::io::ext_repr::ExtSPInstance;
+use crate::config::{DxfImportConfig, DxfUnits};
+use crate::consts::DXF_LABEL_HEIGHT_RATIO;
+use crate::EPOCH;
+
+/// Closed contour, without repeating the first point at the end
+pub type Contour = Vec<(f64, f64)>;
+
+/// Maximum recursion depth when flattening a spline
+const MAX_SPLINE_SUBDIVISIONS: usize = 16;
+
+/// Reads the item shape from a DXF file, in the format of the `shape` field of an item in the instance JSON.
+/// The contour with the largest area is the outer boundary of the item, all others are holes.
+pub fn read_dxf_shape(path: &Path, config: &DxfImportConfig) -> Result<serde_json::Value> {
+    let content = fs::read_to_string(path).with_context(|| format!("could not read DXF file {}", path.display()))?;
+    let contours = parse_dxf_contours(&content, config)
+        .with_context(|| format!("could not import DXF file {}", path.display()))?;
+    ext_shape(contours).with_context(|| format!("invalid shape in DXF file {}", path.display()))
+}
+
+/// Parses all closed contours of the (ASCII) DXF file.
+/// LWPOLYLINE, POLYLINE, LINE, ARC, CIRCLE and SPLINE entities are supported, open ones are chained into closed contours.
+/// Entities must lie in the xy-plane: their extrusion direction is either (0, 0, 1) or (0, 0, -1).
+pub fn parse_dxf_contours(content: &str, config: &DxfImportConfig) -> Result<Vec<Contour>> {
+    let pairs = group_pairs(content)?;
+    let scale = unit_scale(&pairs, config);
+    let tol = config.flatten_tolerance as f64;
+
+    let mut closed = vec![];
+    let mut open = vec![];
+    for entity in entities(&pairs) {
+        let (path, is_closed) = match entity.kind {
+            "LWPOLYLINE" => {
+                let vertices = entity.codes(10).zip(entity.codes(20))
+                    .map(|(x, y)| Ok((x.parse::<f64>()?, y.parse::<f64>()?)))
+                    .collect::<Result<Vec<_>>>()?;
+                // Bulges are optional, and belong to the vertex (with its 10 code) preceding them
+                let mut bulges = vec![0.0; vertices.len()];
+                let mut vertex_idx = None;
+                for &(code, value) in &entity.pairs {
+                    match code {
+                        10 => vertex_idx = Some(vertex_idx.map_or(0, |i| i + 1)),
+                        42 => if let Some(i) = vertex_idx { bulges[i] = value.parse()? },
+                        _ => {}
+                    }
+                }
+                let is_closed = entity.int(70)? & 1 == 1;
+                (polyline(&vertices, &bulges, is_closed, tol), is_closed)
+            }
+            "POLYLINE" => {
+                let vertices = entity.vertices.iter()
+                    .map(|v| Ok((v.float(10)?, v.float(20)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                let bulges = entity.vertices.iter()
+                    .map(|v| v.float(42).or(Ok(0.0)))
+                    .collect::<Result<Vec<_>>>()?;
+                let is_closed = entity.int(70)? & 1 == 1;
+                (polyline(&vertices, &bulges, is_closed, tol), is_closed)
+            }
+            "LINE" => (vec![(entity.float(10)?, entity.float(20)?), (entity.float(11)?, entity.float(21)?)], false),
+            "ARC" => {
+                let center = (entity.float(10)?, entity.float(20)?);
+                let (start, end) = (entity.float(50)?.to_radians(), entity.float(51)?.to_radians());
+                let sweep = (end - start).rem_euclid(2.0 * PI);
+                (arc(center, entity.float(40)?, start, sweep, tol), false)
+            }
+            "CIRCLE" => {
+                let mut circle = arc((entity.float(10)?, entity.float(20)?), entity.float(40)?, 0.0, 2.0 * PI, tol);
+                circle.pop();
+                (circle, true)
+            }
+            "SPLINE" => {
+                let is_closed = entity.int(70)? & 1 == 1;
+                let mut spline = spline(&entity, tol)?;
+                if is_closed && spline.len() > 1 && dist(spline[0], *spline.last().unwrap()) <= config.join_tolerance as f64 {
+                    spline.pop();
+                }
+                (spline, is_closed)
+            }
+            other => {
+                debug!("[DXF] ignoring {other} entity");
+                continue;
+            }
+        };
+        // Coordinates of these entities are in their object coordinate system, mirrored if viewed from below
+        let path = match matches!(entity.kind, "LWPOLYLINE" | "POLYLINE" | "ARC" | "CIRCLE") && entity.is_mirrored()? {
+            true => path.into_iter().map(|(x, y)| (-x, y)).collect(),
+            false => path,
+        };
+        match is_closed {
+            true => closed.push(path),
+            false => open.push(path),
+        }
+    }
+
+    closed.extend(chain(open, config.join_tolerance as f64)?);
+    ensure!(!closed.is_empty(), "no contours found");
+
+    let contours = closed.into_iter()
+        .map(|c| {
+            let mut contour = c.into_iter().map(|(x, y)| (x * scale, y * scale)).dedup().collect_vec();
+            // Some exporters repeat the first vertex of a closed polyline at the end
+            if contour.len() > 1 && contour[0] == *contour.last().unwrap() {
+                contour.pop();
+            }
+            contour
+        })
+        .filter(|c| c.len() >= 3)
+        .collect_vec();
+    debug!("[DXF] imported {} contours", contours.len());
+    Ok(contours)
+}
+
+/// Converts the contours to the JSON representation of a shape: a simple polygon, or a polygon with holes
+pub fn ext_shape(mut contours: Vec<Contour>) -> Result<serde_json::Value> {
+    contours.sort_by(|a, b| signed_area(b).abs().total_cmp(&signed_area(a).abs()));
+    let mut contours = contours.into_iter();
+    let Some(mut outer) = contours.next() else { bail!("shape has no contours") };
+    // The outer boundary is counterclockwise, holes are clockwise
+    if signed_area(&outer) < 0.0 {
+        outer.reverse();
+    }
+    let holes = contours
+        .map(|mut hole| {
+            ensure!(point_in_polygon(hole[0], &outer), "contours are disjoint, only a single outer contour per item is supported");
+            if signed_area(&hole) > 0.0 {
+                hole.reverse();
+            }
+            Ok(hole)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let points = |c: &Contour| c.iter().map(|&(x, y)| [x as f32, y as f32]).collect_vec();
+    Ok(match holes.is_empty() {
+        true => json!({ "type": "simple_polygon", "data": points(&outer) }),
+        false => json!({
+            "type": "polygon",
+            "data": { "outer": points(&outer), "inner": holes.iter().map(points).collect_vec() }
+        }),
+    })
+}
+
+/// Splits the file into its (group code, value) pairs
+fn group_pairs(content: &str) -> Result<Vec<(i32, &str)>> {
+    content.lines()
+        .map(str::trim)
+        .tuples()
+        .map(|(code, value)| {
+            let code = code.parse::<i32>().with_context(|| format!("invalid group code: {code} (only ASCII DXF is supported)"))?;
+            Ok((code, value))
+        })
+        .collect()
+}
+
+/// Factor to convert the coordinates of the file to the configured units
+fn unit_scale(pairs: &[(i32, &str)], config: &DxfImportConfig) -> f64 {
+    let file_units = pairs.iter()
+        .position(|&(code, value)| code == 9 && value == "$INSUNITS")
+        .and_then(|i| pairs.get(i + 1))
+        .and_then(|(_, value)| value.parse::<i32>().ok())
+        .and_then(DxfUnits::from_insunits);
+    match (file_units, config.units) {
+        (Some(from), Some(to)) => from.in_mm() / to.in_mm(),
+        _ => 1.0,
+    }
+}
+
+struct Entity<'a> {
+    kind: &'a str,
+    pairs: Vec<(i32, &'a str)>,
+    /// VERTEX entities of a POLYLINE
+    vertices: Vec<Entity<'a>>,
+}
+
+impl<'a> Entity<'a> {
+    fn codes(&self, code: i32) -> impl Iterator<Item = &'a str> + '_ {
+        self.pairs.iter().filter(move |(c, _)| *c == code).map(|(_, v)| *v)
+    }
+
+    fn float(&self, code: i32) -> Result<f64> {
+        let value = self.codes(code).next().with_context(|| format!("{} entity is missing group code {code}", self.kind))?;
+        value.parse().with_context(|| format!("invalid value for group code {code}: {value}"))
+    }
+
+    fn int(&self, code: i32) -> Result<i32> {
+        self.codes(code).next().map_or(Ok(0), |v| v.parse().with_context(|| format!("invalid value for group code {code}: {v}")))
+    }
+
+    fn float_or(&self, code: i32, default: f64) -> Result<f64> {
+        self.codes(code).next().map_or(Ok(default), |v| v.parse().with_context(|| format!("invalid value for group code {code}: {v}")))
+    }
+
+    /// Whether the extrusion direction is (0, 0, -1), which mirrors the x-axis of the object coordinate system.
+    /// Fails for any other direction than (0, 0, 1), as the entity is then not in the xy-plane.
+    fn is_mirrored(&self) -> Result<bool> {
+        let (x, y, z) = (self.float_or(210, 0.0)?, self.float_or(220, 0.0)?, self.float_or(230, 1.0)?);
+        ensure!(z != 0.0 && x.abs() <= 1e-9 * z.abs() && y.abs() <= 1e-9 * z.abs(),
+            "{} entity is not in the xy-plane (extrusion direction ({x}, {y}, {z}))", self.kind);
+        Ok(z < 0.0)
+    }
+}
+
+/// All entities in the ENTITIES section, with the VERTEX entities collected into their POLYLINE
+fn entities<'a>(pairs: &[(i32, &'a str)]) -> Vec<Entity<'a>> {
+    let start = pairs.windows(2)
+        .position(|w| w[0] == (0, "SECTION") && w[1] == (2, "ENTITIES"))
+        .map_or(pairs.len(), |i| i + 2);
+
+    let mut entities: Vec<Entity> = vec![];
+    let mut in_polyline = false;
+    for (code, value) in &pairs[start..] {
+        match (*code, *value) {
+            (0, "ENDSEC") => break,
+            (0, "SEQEND") => in_polyline = false,
+            (0, "VERTEX") if in_polyline => {
+                entities.last_mut().unwrap().vertices.push(Entity { kind: "VERTEX", pairs: vec![], vertices: vec![] });
+            }
+            (0, kind) => {
+                in_polyline = kind == "POLYLINE";
+                entities.push(Entity { kind, pairs: vec![], vertices: vec![] });
+            }
+            (code, value) => {
+                if let Some(entity) = entities.last_mut() {
+                    match entity.vertices.last_mut() {
+                        Some(vertex) if in_polyline => vertex.pairs.push((code, value)),
+                        _ => entity.pairs.push((code, value)),
+                    }
+                }
+            }
+        }
+    }
+    entities
+}
+
+/// Flattens a polyline with (optional) bulges. For closed polylines the first point is not repeated.
+fn polyline(vertices: &[(f64, f64)], bulges: &[f64], is_closed: bool, tol: f64) -> Vec<(f64, f64)> {
+    let n_segments = match is_closed {
+        true => vertices.len(),
+        false => vertices.len().saturating_sub(1),
+    };
+    let mut points = vec![];
+    for i in 0..n_segments {
+        let (p1, p2) = (vertices[i], vertices[(i + 1) % vertices.len()]);
+        points.push(p1);
+        if bulges[i] != 0.0 {
+            let segment = bulge_arc(p1, p2, bulges[i], tol);
+            points.extend(&segment[1..segment.len() - 1]);
+        }
+    }
+    if !is_closed && let Some(last) = vertices.last() {
+        points.push(*last);
+    }
+    points
+}
+
+/// Arc from `p1` to `p2` with the given bulge (tangent of a quarter of the included angle, positive if counterclockwise)
+fn bulge_arc(p1: (f64, f64), p2: (f64, f64), bulge: f64, tol: f64) -> Vec<(f64, f64)> {
+    let theta = 4.0 * bulge.atan();
+    let (dx, dy) = (p2.0 - p1.0, p2.1 - p1.1);
+    let chord = (dx * dx + dy * dy).sqrt();
+    if chord < tol {
+        // Degenerate segment (e.g. a repeated vertex), no arc to flatten
+        return vec![p1, p2];
+    }
+    let radius = chord / (2.0 * (theta / 2.0).sin());
+    let h = radius * (theta / 2.0).cos();
+    let center = ((p1.0 + p2.0) / 2.0 - dy / chord * h, (p1.1 + p2.1) / 2.0 + dx / chord * h);
+    let start = (p1.1 - center.1).atan2(p1.0 - center.0);
+
+    let mut points = arc(center, radius.abs(), start, theta, tol);
+    // Snap the endpoints to the vertices, to avoid gaps due to rounding
+    *points.first_mut().unwrap() = p1;
+    *points.last_mut().unwrap() = p2;
+    points
+}
+
+/// Points along an arc (both ends included) deviating at most `tol` from the true arc. A negative sweep is clockwise.
+pub fn arc(center: (f64, f64), radius: f64, start: f64, sweep: f64, tol: f64) -> Vec<(f64, f64)> {
+    let max_step = match tol < radius {
+        true => 2.0 * (1.0 - tol / radius).acos(),
+        false => PI / 2.0,
+    };
+    let n = ((sweep.abs() / max_step).ceil() as usize).max(1);
+    (0..=n)
+        .map(|i| {
+            let angle = start + sweep * i as f64 / n as f64;
+            (center.0 + radius * angle.cos(), center.1 + radius * angle.sin())
+        })
+        .collect()
+}
+
+/// Flattens a (rational) B-spline, or the polyline through its fit points if it has no control points
+fn spline(entity: &Entity, tol: f64) -> Result<Vec<(f64, f64)>> {
+    let parse = |code| entity.codes(code).map(|v| v.parse::<f64>().map_err(anyhow::Error::from)).collect::<Result<Vec<_>>>();
+    let ctrl_points = parse(10)?.into_iter().zip(parse(20)?).collect_vec();
+    if ctrl_points.is_empty() {
+        let fit_points = parse(11)?.into_iter().zip(parse(21)?).collect_vec();
+        ensure!(fit_points.len() >= 2, "SPLINE entity has neither control points nor fit points");
+        return Ok(fit_points);
+    }
+    let degree = entity.int(71)? as usize;
+    let knots = parse(40)?;
+    let weights = match parse(41)? {
+        w if w.len() == ctrl_points.len() => w,
+        _ => vec![1.0; ctrl_points.len()],
+    };
+    ensure!(degree >= 1 && knots.len() == ctrl_points.len() + degree + 1, "SPLINE entity has an invalid number of knots");
+
+    let eval = |t: f64| {
+        // Knot span containing t
+        let k = (degree..ctrl_points.len()).rev().find(|&i| knots[i] <= t).unwrap_or(degree);
+        // De Boor's algorithm in homogeneous coordinates
+        let mut d = (0..=degree)
+            .map(|j| {
+                let ((x, y), w) = (ctrl_points[j + k - degree], weights[j + k - degree]);
+                [x * w, y * w, w]
+            })
+            .collect_vec();
+        for r in 1..=degree {
+            for j in (r..=degree).rev() {
+                let (left, right) = (knots[j + k - degree], knots[j + 1 + k - r]);
+                let alpha = if right > left { (t - left) / (right - left) } else { 0.0 };
+                d[j] = [0, 1, 2].map(|c| (1.0 - alpha) * d[j - 1][c] + alpha * d[j][c]);
+            }
+        }
+        (d[degree][0] / d[degree][2], d[degree][1] / d[degree][2])
+    };
+
+    let (t_start, t_end) = (knots[degree], knots[ctrl_points.len()]);
+    let n_initial = ctrl_points.len() * 4;
+    let ts = (0..=n_initial).map(|i| t_start + (t_end - t_start) * i as f64 / n_initial as f64).collect_vec();
+    let mut points = vec![eval(t_start)];
+    for (&t0, &t1) in ts.iter().tuple_windows() {
+        flatten_curve(&eval, t0, t1, tol, &mut points);
+    }
+    Ok(points)
+}
+
+/// Adds the points of the curve in ]t0, t1] until the chords deviate at most `tol` from the curve
+pub fn flatten_curve(eval: &impl Fn(f64) -> (f64, f64), t0: f64, t1: f64, tol: f64, points: &mut Vec<(f64, f64)>) {
+    subdivide(eval, (t0, eval(t0)), (t1, eval(t1)), tol, 0, points);
+}
+
+fn subdivide(eval: &impl Fn(f64) -> (f64, f64), (t0, p0): (f64, (f64, f64)), (t1, p1): (f64, (f64, f64)), tol: f64, depth: usize, points: &mut Vec<(f64, f64)>) {
+    let tm = (t0 + t1) / 2.0;
+    let pm = eval(tm);
+    if depth < MAX_SPLINE_SUBDIVISIONS && dist_to_segment(pm, p0, p1) > tol {
+        subdivide(eval, (t0, p0), (tm, pm), tol, depth + 1, points);
+        subdivide(eval, (tm, pm), (t1, p1), tol, depth + 1, points);
+    } else {
+        points.push(p1);
+    }
+}
+
+/// Chains open paths into closed contours by matching their endpoints
+fn chain(mut open: Vec<Vec<(f64, f64)>>, join_tol: f64) -> Result<Vec<Contour>> {
+    let mut contours = vec![];
+    while let Some(mut contour) = open.pop() {
+        loop {
+            let (first, last) = (contour[0], *contour.last().unwrap());
+            if contour.len() > 2 && dist(first, last) <= join_tol {
+                contour.pop();
+                contours.push(contour);
+                break;
+            }
+            let next = open.iter().position(|p| dist(p[0], last) <= join_tol || dist(*p.last().unwrap(), last) <= join_tol);
+            match next {
+                Some(i) => {
+                    let mut path = open.swap_remove(i);
+                    if dist(path[0], last) > join_tol {
+                        path.reverse();
+                    }
+                    contour.extend(&path[1..]);
+                }
+                None => bail!("open contour: no entity connects to ({:.3}, {:.3}) (join tolerance: {join_tol})", last.0, last.1),
+            }
+        }
+    }
+    Ok(contours)
+}
+
+pub fn dist(a: (f64, f64), b: (f64, f64)) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+pub fn dist_to_segment(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len_sq = dx * dx + dy * dy;
+    let t = match len_sq > 0.0 {
+        true => (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len_sq).clamp(0.0, 1.0),
+        false => 0.0,
+    };
+    dist(p, (a.0 + t * dx, a.1 + t * dy))
+}
+
+/// Shoelace formula, positive for counterclockwise contours
+pub fn signed_area(contour: &Contour) -> f64 {
+    contour.iter().circular_tuple_windows()
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum::<f64>() / 2.0
+}
+
+pub fn point_in_polygon(p: (f64, f64), polygon: &Contour) -> bool {
+    polygon.iter().circular_tuple_windows()
+        .filter(|(a, b)| (a.1 > p.1) != (b.1 > p.1) && p.0 < (b.0 - a.0) * (p.1 - a.1) / (b.1 - a.1) + a.0)
+        .count() % 2 == 1
+}
+
+/// Layer of the container outline in exported DXF files
+pub const LAYER_CONTAINER: &str = "CONTAINER";
+/// Layer of the contours of items which were free to move during optimization
+pub const LAYER_ITEMS: &str = "ITEMS";
+/// Layer of the contours of locked (fixed or pinned) items
+pub const LAYER_ITEMS_LOCKED: &str = "ITEMS_LOCKED";
+/// Layer of the item ids and the instance label
+pub const LAYER_LABELS: &str = "LABELS";
+
+/// Exports the layout of a solution as an (R12, ASCII) DXF file for cutting machines.
+/// Every placed item is drawn with its original contour (as defined in the instance), transformed by its placement.
+/// Locked items are drawn on a separate layer.
+pub fn layout_to_dxf(ext_instance: &ExtSPInstance, instance: &SPInstance, solution: &SPSolution, units: Option<DxfUnits>) -> Result<String> {
+    let ext_solution = jagua_rs::probs::spp::io::export(instance, solution, *EPOCH);
+    let mut dxf = DxfWriter::default();
+    let (width, height) = (solution.strip_width() as f64, solution.strip.fixed_height as f64);
+    let text_height = f64::min(width, height) * DXF_LABEL_HEIGHT_RATIO;
+
+    // The placements of the export are in the same order as the placed items of the solution
+    let locked = solution.layout_snapshot.placed_items.values().map(|pi| pi.is_locked).collect_vec();
+    debug_assert!(locked.len() == ext_solution.layout.placed_items.len());
+
+    for (i, ext_pi) in ext_solution.layout.placed_items.iter().enumerate() {
+        let item = ext_instance.items.iter()
+            .find(|item| item.base.id == ext_pi.item_id)
+            .with_context(|| format!("placed item {} does not exist in the instance", ext_pi.item_id))?;
+        let contours = ext_shape_contours(&serde_json::to_value(&item.base.shape)?)?;
+
+        let (sin, cos) = (ext_pi.transformation.rotation as f64).sin_cos();
+        let (tx, ty) = (ext_pi.transformation.translation.0 as f64, ext_pi.transformation.translation.1 as f64);
+        let transform = |&(x, y): &(f64, f64)| (x * cos - y * sin + tx, x * sin + y * cos + ty);
+
+        let layer = match locked.get(i).copied().unwrap_or(false) {
+            true => LAYER_ITEMS_LOCKED,
+            false => LAYER_ITEMS,
+        };
+        for contour in &contours {
+            dxf.polyline(layer, &contour.iter().map(transform).collect_vec());
+        }
+
+        // Label the item at the center of its bounding box
+        let outer = contours[0].iter().map(transform).collect_vec();
+        let (x_min, x_max) = outer.iter().map(|p| p.0).minmax().into_option().unwrap();
+        let (y_min, y_max) = outer.iter().map(|p| p.1).minmax().into_option().unwrap();
+        dxf.text(LAYER_LABELS, ((x_min + x_max) / 2.0, (y_min + y_max) / 2.0), text_height, &ext_pi.item_id.to_string());
+    }
+
+    dxf.polyline(LAYER_CONTAINER, &[(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)]);
+    let label = format!("{} - width: {:.3}, density: {:.3}%", ext_instance.name, width, solution.density(instance) * 100.0);
+    dxf.text(LAYER_LABELS, (width / 2.0, height + 2.0 * text_height), 2.0 * text_height, &label);
+
+    Ok(dxf.finish(units, (width, height)))
+}
+
+/// Closed contours of a shape in the JSON representation of the instance (the first one is the outer boundary)
+pub fn ext_shape_contours(shape: &serde_json::Value) -> Result<Vec<Contour>> {
+    let points = |v: &serde_json::Value| -> Result<Contour> {
+        let points = serde_json::from_value::<Vec<(f64, f64)>>(v.clone())?;
+        ensure!(points.len() >= 3, "contour has less than 3 points");
+        Ok(points)
+    };
+    let polygon = |v: &serde_json::Value| -> Result<Vec<Contour>> {
+        let mut contours = vec![points(&v["outer"])?];
+        for hole in v["inner"].as_array().into_iter().flatten() {
+            contours.push(points(hole)?);
+        }
+        Ok(contours)
+    };
+    let data = &shape["data"];
+    match shape["type"].as_str() {
+        Some("simple_polygon") => Ok(vec![points(data)?]),
+        Some("polygon") => polygon(data),
+        Some("multi_polygon") => Ok(data.as_array().into_iter().flatten().map(polygon).collect::<Result<Vec<_>>>()?.concat()),
+        Some("rectangle") => {
+            let field = |name: &str| data[name].as_f64().with_context(|| format!("rectangle is missing {name}"));
+            let (x_min, y_min, w, h) = (field("x_min")?, field("y_min")?, field("width")?, field("height")?);
+            Ok(vec![vec![(x_min, y_min), (x_min + w, y_min), (x_min + w, y_min + h), (x_min, y_min + h)]])
+        }
+        other => bail!("unsupported shape type: {other:?}"),
+    }
+}
+
+/// Writes the entities of an R12 DXF file, which is understood by virtually all CAD and CAM software
+#[derive(Default)]
+struct DxfWriter {
+    entities: String,
+}
+
+impl DxfWriter {
+    fn pair(out: &mut String, code: i32, value: impl std::fmt::Display) {
+        out.push_str(&format!("{code}\n{value}\n"));
+    }
+
+    /// Closed polyline
+    fn polyline(&mut self, layer: &str, points: &[(f64, f64)]) {
+        let out = &mut self.entities;
+        for (code, value) in [(0, "POLYLINE"), (8, layer), (66, "1"), (70, "1")] {
+            Self::pair(out, code, value);
+        }
+        for (code, value) in [(10, 0.0), (20, 0.0), (30, 0.0)] {
+            Self::pair(out, code, value);
+        }
+        for &(x, y) in points {
+            Self::pair(out, 0, "VERTEX");
+            Self::pair(out, 8, layer);
+            Self::pair(out, 10, x);
+            Self::pair(out, 20, y);
+            Self::pair(out, 30, 0.0);
+        }
+        Self::pair(out, 0, "SEQEND");
+        Self::pair(out, 8, layer);
+    }
+
+    /// Single line of text, centered at `pos`
+    fn text(&mut self, layer: &str, pos: (f64, f64), height: f64, text: &str) {
+        let out = &mut self.entities;
+        Self::pair(out, 0, "TEXT");
+        Self::pair(out, 8, layer);
+        for (code, value) in [(10, pos.0), (20, pos.1), (30, 0.0), (40, height)] {
+            Self::pair(out, code, value);
+        }
+        Self::pair(out, 1, text);
+        Self::pair(out, 72, 1);
+        for (code, value) in [(11, pos.0), (21, pos.1), (31, 0.0)] {
+            Self::pair(out, code, value);
+        }
+        Self::pair(out, 73, 2);
+    }
+
+    fn finish(self, units: Option<DxfUnits>, (width, height): (f64, f64)) -> String {
+        let mut out = String::new();
+        // R12 has no header variable for the units ($INSUNITS was introduced in R2000), so they are only noted in a comment
+        if let Some(units) = units {
+            Self::pair(&mut out, 999, format!("units: {units:?}").to_lowercase());
+        }
+        Self::pair(&mut out, 0, "SECTION");
+        Self::pair(&mut out, 2, "HEADER");
+        Self::pair(&mut out, 9, "$ACADVER");
+        Self::pair(&mut out, 1, "AC1009");
+        for (var, (x, y)) in [("$EXTMIN", (0.0, 0.0)), ("$EXTMAX", (width, height))] {
+            Self::pair(&mut out, 9, var);
+            Self::pair(&mut out, 10, x);
+            Self::pair(&mut out, 20, y);
+        }
+        Self::pair(&mut out, 0, "ENDSEC");
+
+        let layers = [(LAYER_CONTAINER, 7), (LAYER_ITEMS, 5), (LAYER_ITEMS_LOCKED, 1), (LAYER_LABELS, 3)];
+        Self::pair(&mut out, 0, "SECTION");
+        Self::pair(&mut out, 2, "TABLES");
+        Self::pair(&mut out, 0, "TABLE");
+        Self::pair(&mut out, 2, "LAYER");
+        Self::pair(&mut out, 70, layers.len());
+        for (name, color) in layers {
+            Self::pair(&mut out, 0, "LAYER");
+            Self::pair(&mut out, 2, name);
+            Self::pair(&mut out, 70, 0);
+            Self::pair(&mut out, 62, color);
+            Self::pair(&mut out, 6, "CONTINUOUS");
+        }
+        Self::pair(&mut out, 0, "ENDTAB");
+        Self::pair(&mut out, 0, "ENDSEC");
+
+        Self::pair(&mut out, 0, "SECTION");
+        Self::pair(&mut out, 2, "ENTITIES");
+        out.push_str(&self.entities);
+        Self::pair(&mut out, 0, "ENDSEC");
+        Self::pair(&mut out, 0, "EOF");
+        out
+    }
+}
@@ -0,0 +1,109 @@
+use std::f64::consts::PI;
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use jagua_rs::entities::Instance;
+use jagua_rs::geometry::geo_enums::RotationRange;
+use jagua_rs::geometry::DTransformation;
+use jagua_rs::probs::spp::entities::SPInstance;
+use jagua_rs::probs::spp::io::ext_repr::ExtSPInstance;
+use crate::config::SparrowConfig;
+use crate::consts::DEFAULT_VERIFY_TOLERANCE_RATIO;
+use crate::util::dxf::{self, Contour};
+use crate::util::lower_bound::LowerBound;
+use crate::util::verify;
+
+/// Number of rotations sampled to check whether an item with continuous rotation fits the container
+const CONTINUOUS_ROTATION_SAMPLES: usize = 360;
+
+/// Summary of an instance, as reported by `sparrow inspect`
+pub struct InstanceReport {
+    pub name: String,
+    pub items: Vec<ItemReport>,
+    pub total_demand: usize,
+    pub lower_bound: LowerBound,
+    /// Largest dimensions of the container during optimization, `None` if a dimension is not bounded
+    pub max_container_dims: (Option<f32>, Option<f32>),
+    pub warnings: Vec<String>,
+}
+
+pub struct ItemReport {
+    pub id: usize,
+    pub demand: usize,
+    /// Number of vertices of the original shape (including holes)
+    pub n_vertices: usize,
+    /// Number of vertices of the shape used for collision detection, after simplification
+    pub n_vertices_simplified: usize,
+    /// Area of the simplified shape divided by the area of its convex hull
+    pub convex_hull_ratio: f32,
+    pub n_poles: usize,
+    pub allowed_rotation: RotationRange,
+    pub fixed_placement: Option<DTransformation>,
+    /// Whether the item fits the container (and its allowed area) at any of its allowed rotations
+    pub fits: bool,
+}
+
+/// Analyzes the items of an instance without optimizing it. `instance` must be imported from `ext_instance` with `config`.
+pub fn inspect_instance(ext_instance: &ExtSPInstance, instance: &SPInstance, config: &SparrowConfig) -> Result<InstanceReport> {
+    let max_container_dims = config.max_container_dims(ext_instance.strip_height);
+    let mut items = vec![];
+    let mut warnings = vec![];
+
+    for ext_item in &ext_instance.items {
+        let item = instance.item(ext_item.base.id as usize);
+        let contours = dxf::ext_shape_contours(&serde_json::to_value(&ext_item.base.shape)?)
+            .with_context(|| format!("invalid shape of item {}", item.id))?;
+        let surrogate = item.shape_cd.surrogate();
+
+        let (mut max_width, mut max_height) = max_container_dims;
+        if let Some(area) = item.allowed_area {
+            max_width = Some(max_width.map_or(area.width(), |w| w.min(area.width())));
+            max_height = Some(max_height.map_or(area.height(), |h| h.min(area.height())));
+        }
+        let fits = fits_at_any_rotation(&contours[0], &item.allowed_rotation, max_width, max_height);
+        if !fits {
+            warnings.push(format!("item {} does not fit the container (or its allowed area) at any allowed rotation", item.id));
+        }
+
+        items.push(ItemReport {
+            id: item.id,
+            demand: ext_item.demand as usize,
+            n_vertices: contours.iter().map(|c| c.len()).sum(),
+            n_vertices_simplified: item.shape_cd.vertices.len(),
+            convex_hull_ratio: item.shape_cd.area / surrogate.convex_hull_area,
+            n_poles: surrogate.poles.len(),
+            allowed_rotation: item.allowed_rotation.clone(),
+            fixed_placement: item.fixed_placement,
+            fits,
+        });
+    }
+
+    let tolerance = ext_instance.strip_height as f64 * DEFAULT_VERIFY_TOLERANCE_RATIO;
+    let max_dims = (max_container_dims.0.map(|w| w as f64), max_container_dims.1.map(|h| h as f64));
+    let fixed_violations = verify::verify_fixed_placements(ext_instance, instance, max_dims, tolerance)?;
+    warnings.extend(fixed_violations.iter().map(|v| format!("fixed items: {v}")));
+
+    Ok(InstanceReport {
+        name: ext_instance.name.clone(),
+        total_demand: items.iter().map(|i| i.demand).sum(),
+        items,
+        lower_bound: LowerBound::new(ext_instance, instance, config.container_mode)?,
+        max_container_dims,
+        warnings,
+    })
+}
+
+fn fits_at_any_rotation(contour: &Contour, allowed_rotation: &RotationRange, max_width: Option<f32>, max_height: Option<f32>) -> bool {
+    let rotations = match allowed_rotation {
+        RotationRange::None => vec![0.0],
+        RotationRange::Discrete(rotations) => rotations.iter().map(|&r| r as f64).collect_vec(),
+        RotationRange::Continuous => (0..CONTINUOUS_ROTATION_SAMPLES)
+            .map(|i| i as f64 * 2.0 * PI / CONTINUOUS_ROTATION_SAMPLES as f64)
+            .collect_vec(),
+    };
+    rotations.into_iter().any(|r| {
+        let (sin, cos) = r.sin_cos();
+        let (x_min, x_max) = contour.iter().map(|&(x, y)| x * cos - y * sin).minmax().into_option().unwrap();
+        let (y_min, y_max) = contour.iter().map(|&(x, y)| x * sin + y * cos).minmax().into_option().unwrap();
+        max_width.is_none_or(|w| x_max - x_min <= w as f64) && max_height.is_none_or(|h| y_max - y_min <= h as f64)
+    })
+}
@@ -6,34 +6,256 @@ use std::path::Path;
 use serde::{Deserialize, Serialize};
 use svg::Document;
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use jagua_rs::io::import::Importer;
+use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
 use jagua_rs::probs::spp::io::ext_repr::{ExtSPInstance, ExtSPSolution};
-use crate::EPOCH;
+use crate::config::{ContainerMode, DxfImportConfig, SparrowConfig, DEFAULT_SPARROW_CONFIG};
+use crate::consts::DEFAULT_VERIFY_TOLERANCE_RATIO;
+use crate::util::{dxf, svg_import, verify};
+use crate::util::verify::InvalidFixedPlacements;
+use crate::util::listener::{Phase, ReportType, WarmStartReport};
+use crate::{EPOCH, EXPORT_LIVE_SVG, EXPORT_ONLY_FINAL_SVG};
 
 #[derive(Parser)]
+#[command(
+    subcommand_negates_reqs = true,
+    after_help = "Pressing Ctrl-C once ends the current phase (exploration moves on to compression).\n\
+                  Pressing Ctrl-C a second time stops the optimization and saves the best feasible solution found (JSON + SVG)."
+)]
 pub struct MainCli {
-    /// Path to input file (mandatory)
-    #[arg(short = 'i', long, help = "Path to the input JSON file, or a solution JSON file for warm starting")]
-    pub input: String,
+    #[command(subcommand)]
+    pub command: Option<MainCommand>,
+
+    /// Path to input file (mandatory, unless dumping the config)
+    #[arg(short = 'i', long, required_unless_present_any = &["dump_config", "resume"], help = "Path to the input JSON (or SVG) file, or a solution JSON file for warm starting")]
+    pub input: Option<String>,
+
+    /// Configuration file, overriding the defaults (CLI arguments take precedence over it)
+    #[arg(long, global = true, help = "Path to a JSON or TOML configuration file (a solution JSON reuses its embedded config)")]
+    pub config: Option<String>,
+
+    /// Print the effective configuration and exit
+    #[arg(long, help = "Print the effective configuration as JSON and exit")]
+    pub dump_config: bool,
+
+    /// Directory for all output files which are not given an explicit path
+    #[arg(long, global = true, default_value = "output", help = "Directory to write the output files to")]
+    pub output_dir: String,
+
+    /// Path of the final solution JSON
+    #[arg(long, help = "Path to write the final solution JSON to [default: <OUTPUT_DIR>/final_{name}.json]")]
+    pub output_json: Option<String>,
+
+    /// Path of the final solution SVG
+    #[arg(long, help = "Path to write the final solution SVG to [default: <OUTPUT_DIR>/final_{name}.svg]")]
+    pub output_svg: Option<String>,
+
+    /// Export the final layout as DXF
+    #[arg(long, help = "Also export the final layout as DXF to <OUTPUT_DIR>/final_{name}.dxf")]
+    pub dxf: bool,
+
+    /// Path of the final layout DXF
+    #[arg(long, help = "Path to write the final layout DXF to (implies --dxf)")]
+    pub output_dxf: Option<String>,
+
+    /// Export the final layout as a CSV placement table
+    #[arg(long, help = "Also export the final layout as a CSV placement table to <OUTPUT_DIR>/final_{name}.csv")]
+    pub csv: bool,
+
+    /// Path of the final layout CSV
+    #[arg(long, help = "Path to write the final layout CSV to (implies --csv)")]
+    pub output_csv: Option<String>,
+
+    /// Interval between checkpoints in seconds
+    #[arg(long, help = "Periodically write a checkpoint to <OUTPUT_DIR>/checkpoint_{name}.json (interval in seconds)")]
+    pub checkpoint_interval: Option<u64>,
+
+    /// Checkpoint to resume from
+    #[arg(long, conflicts_with_all = &["input", "config", "append", "bin_width", "knapsack_width"], help = "Resume an interrupted run from a checkpoint file, with the time that was left")]
+    pub resume: Option<String>,
+
+    /// Stop as soon as a solution of at most this width is found
+    #[arg(long, global = true, help = "Stop as soon as a feasible solution of at most this width is found")]
+    pub target_width: Option<f32>,
+
+    /// Stop as soon as a solution of at least this density is found
+    #[arg(long, global = true, help = "Stop as soon as a feasible solution with at least this density (between 0 and 1) is found")]
+    pub target_density: Option<f32>,
+
+    /// Stop a phase after this many seconds without improvement
+    #[arg(long, global = true, help = "End a phase when the best solution has not improved for this many seconds")]
+    pub stall: Option<u64>,
+
+    /// Stop as soon as this file exists
+    #[arg(long, global = true, help = "Stop (and save the best solution) as soon as this file exists")]
+    pub stop_file: Option<String>,
+
+    /// Hard wall clock limit in seconds
+    #[arg(long, global = true, help = "Stop after this many seconds of wall clock time, regardless of the phase time limits")]
+    pub max_wall_time: Option<u64>,
+
+    /// Which SVG files to export
+    #[arg(long, global = true, value_enum, value_delimiter = ',', help = "Which SVG files to export, e.g. final,live [default: final,intermediate, or as set by the live_svg/only_final_svg features]")]
+    pub svg: Option<Vec<SvgMode>>,
+
+    /// Report types exported as intermediate SVG files
+    #[arg(long, global = true, value_enum, value_delimiter = ',', help = "Only export intermediate solutions of these report types [default: all but expl_improving]")]
+    pub svg_reports: Option<Vec<ReportType>>,
+
+    /// Maximum number of intermediate SVG files
+    #[arg(long, global = true, help = "Keep at most this many intermediate SVG files (the oldest are removed)")]
+    pub svg_max_files: Option<usize>,
+
+    /// Port of the live viewer
+    #[arg(long, value_name = "PORT", help = "Serve a live viewer of the optimization on http://localhost:<PORT>")]
+    pub serve: Option<u16>,
+
+    /// Path of the JSONL progress stream
+    #[arg(long, global = true, help = "Append one JSON object per reported solution to this file (\"-\" for stdout, the log is then written to stderr)")]
+    pub progress_jsonl: Option<String>,
+
+    /// Include the placements in the JSONL progress stream
+    #[arg(long, global = true, requires = "progress_jsonl", help = "Include the full placements of every solution in the JSONL progress stream")]
+    pub progress_placements: bool,
+
+    /// Path of the log file
+    #[arg(long, global = true, help = "Path to write the log file to [default: <OUTPUT_DIR>/log.txt]")]
+    pub log_file: Option<String>,
 
     /// Global time limit in seconds (mutually exclusive with -e and -c)
-    #[arg(short = 't', long, conflicts_with_all = &["exploration", "compression"], help = "Set a global time limit (in seconds)")]
+    #[arg(short = 't', long, global = true, conflicts_with_all = &["exploration", "compression"], help = "Set a global time limit (in seconds)")]
     pub global_time: Option<u64>,
 
     /// Exploration time limit in seconds (requires compression time)
-    #[arg(short = 'e', long, requires = "compression", help = "Set the exploration phase time limit (in seconds)")]
+    #[arg(short = 'e', long, global = true, requires = "compression", help = "Set the exploration phase time limit (in seconds)")]
     pub exploration: Option<u64>,
 
     /// Compression time limit in seconds (requires exploration time)
-    #[arg(short = 'c', long, requires = "exploration", help = "Set the compression phase time limit (in seconds)")]
+    #[arg(short = 'c', long, global = true, requires = "exploration", help = "Set the compression phase time limit (in seconds)")]
     pub compression: Option<u64>,
 
     /// Enable early and automatic termination
-    #[arg(short = 'x', long, help = "Enable early termination of the optimization process")]
+    #[arg(short = 'x', long, global = true, help = "Enable early termination of the optimization process")]
     pub early_termination: bool,
 
-    #[arg(short = 's', long, help = "Fixed seed for the random number generator")]
+    #[arg(short = 's', long, global = true, help = "Fixed seed for the random number generator")]
     pub rng_seed: Option<u64>,
+
+    /// Geometry of the container to minimize
+    #[arg(short = 'm', long, global = true, value_enum, help = "Geometry of the container to minimize [default: square]")]
+    pub container_mode: Option<ContainerModeArg>,
+
+    /// Width/height ratio of the container (requires the aspect-ratio mode)
+    #[arg(short = 'a', long, global = true, required_if_eq("container_mode", "aspect-ratio"), help = "Width/height ratio of the container (aspect-ratio mode only)")]
+    pub aspect_ratio: Option<f32>,
+
+    /// Enables the multi-bin mode with bins of the given width
+    #[arg(short = 'b', long, help = "Minimize the number of bins of this width (height from the instance) instead of a single container")]
+    pub bin_width: Option<f32>,
+
+    /// Enables the knapsack mode with a container of the given width
+    #[arg(short = 'k', long, conflicts_with = "bin_width", help = "Maximize the value of items placed in a container of this width (height from the instance)")]
+    pub knapsack_width: Option<f32>,
+
+    /// Instance with newly arrived items to add to the solution provided as input
+    #[arg(long, conflicts_with_all = &["bin_width", "knapsack_width"], help = "Path to an instance JSON file whose items are added to the solution JSON provided as input")]
+    pub append: Option<String>,
+
+    /// Allow the placements of the previous solution to move when appending items
+    #[arg(long, requires = "append", help = "Use the previous placements as a starting point instead of locking them (only with --append)")]
+    pub soft_pin: bool,
+}
+
+#[derive(Subcommand)]
+pub enum MainCommand {
+    /// Optimize all instances in a directory, or matching a glob pattern
+    Batch(BatchCli),
+    /// Report the shape complexity, constraints and lower bound of an instance without optimizing it
+    Inspect(InspectCli),
+}
+
+#[derive(Args)]
+pub struct BatchCli {
+    /// Directory or glob pattern of the instances to optimize
+    #[arg(help = "Directory containing instance JSON (or SVG) files, or a glob pattern (e.g. 'data/input/*.json')")]
+    pub inputs: String,
+
+    /// Number of concurrent runs, the workers of every run are divided among them
+    #[arg(short = 'j', long, default_value_t = 1, help = "Number of instances optimized concurrently")]
+    pub jobs: usize,
+}
+
+#[derive(Args)]
+pub struct InspectCli {
+    /// Instance to inspect
+    #[arg(help = "Path to the instance JSON (or SVG) file")]
+    pub instance: String,
+}
+
+/// Arguments of the `verify` binary
+#[derive(Parser)]
+#[command(about = "Verifies a solution with the exact shapes of the instance, exits with a non-zero code if it is infeasible")]
+pub struct VerifyCli {
+    /// Solution JSON file to verify
+    #[arg(help = "Path to the solution JSON file (e.g. output/final_{name}.json)")]
+    pub solution: String,
+
+    /// Absolute tolerance of the geometric checks
+    #[arg(short = 't', long, help = "Absolute tolerance of the geometric checks [default: 1e-5 * height of the container]")]
+    pub tolerance: Option<f64>,
+
+    /// Minimum separation between items, overriding the one in the embedded config
+    #[arg(long, help = "Minimum distance between items [default: min_item_separation of the config embedded in the solution]")]
+    pub min_separation: Option<f64>,
+
+    /// Allow items to be placed fewer times than their demand
+    #[arg(long, help = "Allow items to be placed fewer times than their demand (e.g. knapsack solutions)")]
+    pub partial: bool,
+
+    /// Path to write the violations to as JSON
+    #[arg(long, help = "Path to write a JSON report of all violations to")]
+    pub report: Option<String>,
+}
+
+/// Kind of SVG files exported during a run, each one is enabled independently of the others
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SvgMode {
+    /// No SVG files at all (overrides the other modes)
+    None,
+    /// The final solution
+    Final,
+    /// Intermediate solutions in `<OUTPUT_DIR>/sols_{name}`
+    Intermediate,
+    /// A continuously updated SVG for `data/live/live_viewer.html`
+    Live,
+}
+
+impl SvgMode {
+    /// Default modes, determined by the `live_svg` and `only_final_svg` features (`live_svg` takes precedence)
+    pub const DEFAULT: &'static [SvgMode] = match (EXPORT_LIVE_SVG, EXPORT_ONLY_FINAL_SVG) {
+        (true, _) => &[SvgMode::Final, SvgMode::Intermediate, SvgMode::Live],
+        (false, true) => &[SvgMode::Final],
+        (false, false) => &[SvgMode::Final, SvgMode::Intermediate],
+    };
+
+    /// Whether `mode` is enabled by the selected `modes`
+    pub fn enabled(modes: &[SvgMode], mode: SvgMode) -> bool {
+        modes.contains(&mode) && !modes.contains(&SvgMode::None)
+    }
+}
+
+/// CLI counterpart of [`crate::config::ContainerMode`]
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContainerModeArg {
+    /// Fixed height (from the instance), minimize width
+    Strip,
+    /// Minimize the side of a square container
+    Square,
+    /// Minimize the width while keeping a fixed width/height ratio
+    AspectRatio,
+    /// Minimize the area of a free rectangle
+    Free,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -41,11 +263,129 @@ pub struct ExtSPOutput {
     #[serde(flatten)]
     pub instance: ExtSPInstance,
     pub solution: ExtSPSolution,
+    /// Final container of the solution, its height differs from the strip height of the instance outside strip mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<ExtContainer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ExtRunMetadata>,
+}
+
+/// Dimensions of the container of a solution and the mode in which they were optimized
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct ExtContainer {
+    pub mode: ContainerMode,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl ExtContainer {
+    pub fn new(solution: &SPSolution, mode: ContainerMode) -> Self {
+        ExtContainer { mode, width: solution.strip_width(), height: solution.strip.fixed_height }
+    }
+}
+
+/// Output of the multi-bin mode: the instance and one solution per bin
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ExtMultiBinOutput {
+    #[serde(flatten)]
+    pub instance: ExtSPInstance,
+    pub bins: Vec<ExtSPSolution>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ExtRunMetadata>,
+}
+
+/// Describes how a solution was produced
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ExtRunMetadata {
+    /// Seed of the random number generator actually used (also when none was provided)
+    pub rng_seed: u64,
+    /// Version of `sparrow`
+    pub version: String,
+    /// The effective configuration
+    pub config: SparrowConfig,
+    pub phase_times: ExtPhaseTimes,
+    /// Density of all feasible solutions reported during the run
+    pub density_history: Vec<ExtDensityRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lower_bound: Option<ExtLowerBound>,
+    /// Placements carried over from the initial solution and newly placed ones, for warm started runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warm_start: Option<WarmStartReport>,
 }
 
-pub fn init_logger(level_filter: LevelFilter, log_file_path: &Path) -> Result<()> {
+/// Lower bound on the objective, see [`crate::util::lower_bound::LowerBound`]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ExtLowerBound {
+    pub bound: f32,
+    pub item_area: f32,
+    pub item_width: f32,
+    /// Gap between the objective of the final solution and the bound, in percent
+    pub gap: Option<f32>,
+}
+
+/// Wall time spent in every phase, in seconds
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ExtPhaseTimes {
+    pub construction: f64,
+    pub exploration: f64,
+    pub compression: f64,
+    pub total: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ExtDensityRecord {
+    /// Seconds since the start of the run
+    pub time: f64,
+    pub phase: Phase,
+    pub strip_width: f32,
+    pub density: f32,
+}
+
+/// A single event of a [`crate::util::jsonl_listener::JsonlProgressListener`], written as one line of JSON
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ExtProgressEvent {
+    /// Wall clock time of the event (RFC 3339)
+    pub timestamp: String,
+    /// Seconds since the start of the run
+    pub time: f64,
+    /// Input file of the run, only in batch mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+    pub report: ReportType,
+    pub phase: Option<Phase>,
+    pub strip_width: f32,
+    pub strip_height: f32,
+    pub density: f32,
+    /// Total (unweighted) loss of all collisions, zero for feasible solutions
+    pub total_loss: f32,
+    /// Number of items involved in a collision, zero for feasible solutions
+    pub n_colliding_items: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placements: Option<ExtSPSolution>,
+}
+
+/// Knapsack attributes of an item, read from the `value` and `priority` fields of the items in the input file
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ExtItemValue {
+    pub id: usize,
+    #[serde(default)]
+    pub value: Option<f32>,
+    #[serde(default)]
+    pub priority: i32,
+}
+
+#[derive(Deserialize)]
+struct ExtItemValues {
+    items: Vec<ExtItemValue>,
+}
+
+/// Logs to the console (stderr if `log_to_stderr`, e.g. when stdout is used for the progress stream, stdout otherwise) and to a file
+pub fn init_logger(level_filter: LevelFilter, log_file_path: &Path, log_to_stderr: bool) -> Result<()> {
     //remove old log file
     let _ = fs::remove_file(log_file_path);
+    if let Some(parent) = log_file_path.parent() {
+        fs::create_dir_all(parent).context("could not create parent directory for log file")?;
+    }
     fern::Dispatch::new()
         // Perform allocation-free log formatting
         .format(|out, message, record| {
@@ -70,7 +410,10 @@ pub fn init_logger(level_filter: LevelFilter, log_file_path: &Path) -> Result<()
         })
         // Add blanket level filter -
         .level(level_filter)
-        .chain(std::io::stdout())
+        .chain(match log_to_stderr {
+            true => fern::Output::from(std::io::stderr()),
+            false => fern::Output::from(std::io::stdout()),
+        })
         .chain(fern::log_file(log_file_path)?)
         .apply()?;
     log!(
@@ -99,6 +442,10 @@ pub fn write_svg(document: &Document, path: &Path, log_lvl: Level) -> Result<()>
 }
 
 pub fn write_json(json: &impl Serialize, path: &Path, log_lvl: Level) -> Result<()> {
+    //make sure the parent directory exists
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("could not create parent directory for json file")?;
+    }
     let file = File::create(path)?;
     serde_json::to_writer_pretty(file, json)?;
     log!(log_lvl,
@@ -111,18 +458,166 @@ pub fn write_json(json: &impl Serialize, path: &Path, log_lvl: Level) -> Result<
     Ok(())
 }
 
+/// Writes the JSON to a temporary file first and then renames it, so `path` never contains a partially written file
+pub fn write_json_atomic(json: &impl Serialize, path: &Path, log_lvl: Level) -> Result<()> {
+    let tmp_path = path.with_extension("json.tmp");
+    write_json(json, &tmp_path, Level::Trace)?;
+    fs::rename(&tmp_path, path).context("could not move temporary json file into place")?;
+    log!(log_lvl, "[IO] json atomically exported to file://{}", fs::canonicalize(path)?.display());
+    Ok(())
+}
+
+pub fn write_dxf(dxf: &str, path: &Path, log_lvl: Level) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("could not create parent directory for dxf file")?;
+    }
+    fs::write(path, dxf).context("could not write dxf file")?;
+    log!(log_lvl, "[IO] dxf exported to file://{}", fs::canonicalize(path)?.display());
+    Ok(())
+}
+
+pub fn write_csv(csv: &str, path: &Path, log_lvl: Level) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("could not create parent directory for csv file")?;
+    }
+    fs::write(path, csv).context("could not write csv file")?;
+    log!(log_lvl, "[IO] csv exported to file://{}", fs::canonicalize(path)?.display());
+    Ok(())
+}
+
 pub fn read_spp_input(path: &Path) -> Result<(ExtSPInstance, Option<ExtSPSolution>)> {
-    let input_str = fs::read_to_string(path).context("could not read input file")?;
+    read_spp_input_with(path, &DEFAULT_SPARROW_CONFIG)
+}
+
+/// Same as [`read_spp_input`], using the import configuration of `config` for items which refer to a DXF file
+/// and for SVG instances (files with the `.svg` extension)
+pub fn read_spp_input_with(path: &Path, config: &SparrowConfig) -> Result<(ExtSPInstance, Option<ExtSPSolution>)> {
+    let input_json = read_spp_input_json(path, config)?;
+
     //try parsing a full output (instance + solution)
-    match serde_json::from_str::<ExtSPOutput>(&input_str) {
+    match serde_json::from_value::<ExtSPOutput>(input_json.clone()) {
         Ok(ext_output) => {
             Ok((ext_output.instance, Some(ext_output.solution)))
         }
         Err(_) => {
             //try parsing just the instance
-            let ext_instance = serde_json::from_str::<ExtSPInstance>(&input_str)
+            let ext_instance = serde_json::from_value::<ExtSPInstance>(input_json)
                 .context("could not parse instance from input file")?;
             Ok((ext_instance, None))
         }
     }
 }
+
+/// Reads an input file as JSON, with the shapes of items referring to a DXF file resolved. SVG instances are converted to JSON.
+fn read_spp_input_json(path: &Path, config: &SparrowConfig) -> Result<serde_json::Value> {
+    if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("svg")) {
+        return svg_import::read_svg_instance_json(path, &config.svg_import);
+    }
+    let input_str = fs::read_to_string(path).context("could not read input file")?;
+    let mut input_json = serde_json::from_str::<serde_json::Value>(&input_str).context("could not parse input file as JSON")?;
+    let dxf_dir = path.parent().unwrap_or(Path::new("."));
+    for items_ptr in ["/items", "/instance/items"] {
+        if let Some(items) = input_json.pointer_mut(items_ptr).and_then(|i| i.as_array_mut()) {
+            resolve_dxf_shapes(items, dxf_dir, &config.dxf_import)?;
+        }
+    }
+    Ok(input_json)
+}
+
+/// Imports the instance with the collision detection settings of `config` and checks its fixed placements up front,
+/// failing with [`InvalidFixedPlacements`] if they can never be part of a feasible solution
+/// (overlapping each other, outside the container or their allowed area, or at a disallowed rotation).
+pub fn import_instance(ext_instance: &ExtSPInstance, config: &SparrowConfig) -> Result<SPInstance> {
+    let instance = import_instance_unchecked(ext_instance, config)?;
+
+    let (max_width, max_height) = config.max_container_dims(ext_instance.strip_height);
+    let tolerance = ext_instance.strip_height as f64 * DEFAULT_VERIFY_TOLERANCE_RATIO;
+    let max_dims = (max_width.map(|w| w as f64), max_height.map(|h| h as f64));
+    let violations = verify::verify_fixed_placements(ext_instance, &instance, max_dims, tolerance)?;
+    if !violations.is_empty() {
+        return Err(InvalidFixedPlacements { violations }.into());
+    }
+    Ok(instance)
+}
+
+/// Imports the instance with the collision detection settings of `config`, without checking its fixed placements
+pub fn import_instance_unchecked(ext_instance: &ExtSPInstance, config: &SparrowConfig) -> Result<SPInstance> {
+    let importer = Importer::new(config.cde_config, config.poly_simpl_tolerance, config.min_item_separation, config.narrow_concavity_cutoff_ratio);
+    Ok(jagua_rs::probs::spp::io::import_instance(&importer, ext_instance)?)
+}
+
+/// Imports the shape of every item without an inline `shape`, from the DXF file in its `dxf` field (relative to `dxf_dir`)
+fn resolve_dxf_shapes(items: &mut [serde_json::Value], dxf_dir: &Path, dxf_config: &DxfImportConfig) -> Result<()> {
+    for item in items.iter_mut().filter(|item| item.get("shape").is_none()) {
+        if let Some(dxf_path) = item.get("dxf").and_then(|d| d.as_str()) {
+            let shape = dxf::read_dxf_shape(&dxf_dir.join(dxf_path), dxf_config)?;
+            item["shape"] = shape;
+        }
+    }
+    Ok(())
+}
+
+/// Reads a configuration file (JSON, or TOML if the extension is `.toml`).
+/// The file only needs to contain the parameters that differ from [`DEFAULT_SPARROW_CONFIG`], at any depth.
+/// If the file is a solution JSON with an embedded config (in its `metadata`), that config is used instead.
+pub fn read_config(path: &Path) -> Result<SparrowConfig> {
+    let config_str = fs::read_to_string(path).context("could not read config file")?;
+    let mut overrides = match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => {
+            let toml_value = toml::from_str::<toml::Value>(&config_str).context("could not parse TOML config file")?;
+            serde_json::to_value(toml_value)?
+        }
+        _ => serde_json::from_str::<serde_json::Value>(&config_str).context("could not parse JSON config file")?,
+    };
+    if overrides.get("solution").is_some() || overrides.get("bins").is_some() {
+        let embedded = overrides.pointer_mut("/metadata/config").map(serde_json::Value::take);
+        overrides = embedded.context("solution file does not contain an embedded config")?;
+    }
+
+    let mut config = serde_json::to_value(DEFAULT_SPARROW_CONFIG)?;
+    merge_json(&mut config, overrides);
+    let config = serde_json::from_value::<SparrowConfig>(config)
+        .with_context(|| format!("invalid config file {}", path.display()))?;
+    config.validate().with_context(|| format!("invalid config file {}", path.display()))?;
+    Ok(config)
+}
+
+/// Recursively overwrites the fields of `base` with those present in `overrides`
+fn merge_json(base: &mut serde_json::Value, overrides: serde_json::Value) {
+    match (base, overrides) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(base_value) => merge_json(base_value, value),
+                    None => { base.insert(key, value); }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+/// Reads the knapsack attributes of the items of an input file, in any format supported by [`read_spp_input_with`]
+/// (including solution files). Items are identified by their `id`, see [`crate::optimizer::knapsack::ItemValue::resolve`].
+pub fn read_item_values(path: &Path, config: &SparrowConfig) -> Result<Vec<ExtItemValue>> {
+    let mut input_json = read_spp_input_json(path, config)?;
+    let instance_json = match input_json.get("instance") {
+        Some(_) => input_json["instance"].take(),
+        None => input_json,
+    };
+    let ext_values = serde_json::from_value::<ExtItemValues>(instance_json)
+        .context("could not parse item values from input file")?;
+    Ok(ext_values.items)
+}
+
+/// Appends the items of `extra` to `base`. The appended items are renumbered to follow the items of `base`,
+/// so placements referring to items of `base` remain valid for the merged instance.
+pub fn merge_instances(base: &ExtSPInstance, extra: &ExtSPInstance) -> ExtSPInstance {
+    let mut merged = base.clone();
+    let offset = base.items.len();
+    merged.items.extend(extra.items.iter().cloned().enumerate().map(|(i, mut item)| {
+        item.base.id = (offset + i) as u64;
+        item
+    }));
+    merged
+}
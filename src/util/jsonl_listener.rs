@@ -0,0 +1,142 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use anyhow::{Context, Result};
+use jagua_rs::Instant;
+use jagua_rs::probs::spp::entities::{SPInstance, SPProblem, SPSolution};
+use log::warn;
+use crate::EPOCH;
+use crate::config::{TuningConfig, DEFAULT_TUNING_CONFIG};
+use crate::consts::JSONL_PROGRESS_MIN_INTERVAL;
+use crate::quantify::tracker::CollisionTracker;
+use crate::util::io::ExtProgressEvent;
+use crate::error::SparrowError;
+use crate::util::listener::{Phase, ReportType, SolutionListener};
+
+/// Listener which writes every reported solution as a line of JSON ([`ExtProgressEvent`]) to a file or stdout,
+/// so runs can be followed by other tools without parsing the log.
+/// Intermediate (infeasible or improving) solutions are written at most every [`JSONL_PROGRESS_MIN_INTERVAL`].
+pub struct JsonlProgressListener {
+    /// Shared by the listeners of all runs writing to the same stream, see [`JsonlProgressListener::for_input`]
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    start: Instant,
+    phase: Option<Phase>,
+    /// Whether to include the full placements of every solution
+    pub include_placements: bool,
+    /// Input file of the run, added to every event
+    pub input: Option<String>,
+    /// Problem used to quantify the collisions of infeasible solutions
+    prob: Option<SPProblem>,
+    /// Tuning parameters of the run, used to quantify collisions
+    tuning: TuningConfig,
+    /// When the last intermediate solution was written
+    last_intermediate: Option<Instant>,
+}
+
+impl JsonlProgressListener {
+    pub fn new(writer: Box<dyn Write + Send>, include_placements: bool) -> Self {
+        Self {
+            writer: Arc::new(Mutex::new(writer)),
+            start: Instant::now(),
+            phase: None,
+            include_placements,
+            input: None,
+            prob: None,
+            tuning: DEFAULT_TUNING_CONFIG,
+            last_intermediate: None,
+        }
+    }
+
+    /// Appends to the file at `path` (created if it does not exist), or writes to stdout if `path` is "-"
+    pub fn open(path: &str, include_placements: bool) -> Result<Self> {
+        let writer: Box<dyn Write + Send> = match path {
+            "-" => Box::new(std::io::stdout()),
+            _ => {
+                if let Some(parent) = Path::new(path).parent() {
+                    std::fs::create_dir_all(parent).context("could not create parent directory for progress file")?;
+                }
+                let file = OpenOptions::new().create(true).append(true).open(path)
+                    .context("could not open progress file")?;
+                Box::new(file)
+            }
+        };
+        Ok(Self::new(writer, include_placements))
+    }
+
+    /// Quantifies collisions with the tuning parameters of the run instead of the defaults
+    pub fn with_tuning(mut self, tuning: TuningConfig) -> Self {
+        self.tuning = tuning;
+        self
+    }
+
+    /// Listener for another run writing to the same stream (e.g. in batch mode), its events are labelled with `input`
+    pub fn for_input(&self, input: &str) -> Self {
+        Self {
+            writer: self.writer.clone(),
+            start: Instant::now(),
+            phase: None,
+            include_placements: self.include_placements,
+            input: Some(input.to_string()),
+            prob: None,
+            tuning: self.tuning,
+            last_intermediate: None,
+        }
+    }
+
+    /// Total loss and number of colliding items of the solution
+    fn quantify_collisions(&mut self, solution: &SPSolution, instance: &SPInstance) -> (f32, usize) {
+        let prob = self.prob.get_or_insert_with(|| SPProblem::new(instance.clone()));
+        prob.restore(solution);
+        let ct = CollisionTracker::new(&prob.layout, self.tuning);
+        let n_colliding = prob.layout.placed_items.keys()
+            .filter(|pk| ct.get_loss(*pk) > 0.0)
+            .count();
+        (ct.get_total_loss(), n_colliding)
+    }
+}
+
+impl SolutionListener for JsonlProgressListener {
+    fn report(&mut self, report: ReportType, solution: &SPSolution, instance: &SPInstance) -> Result<(), SparrowError> {
+        let (total_loss, n_colliding_items) = match report {
+            ReportType::ExplInfeas | ReportType::ExplImproving => {
+                // Skipped before quantifying the collisions, which is expensive
+                if self.last_intermediate.is_some_and(|t| t.elapsed() < JSONL_PROGRESS_MIN_INTERVAL) {
+                    return Ok(());
+                }
+                self.last_intermediate = Some(Instant::now());
+                self.quantify_collisions(solution, instance)
+            }
+            ReportType::ExplFeas | ReportType::CmprFeas | ReportType::Final => (0.0, 0),
+        };
+        let event = ExtProgressEvent {
+            timestamp: jiff::Timestamp::now().to_string(),
+            time: self.start.elapsed().as_secs_f64(),
+            input: self.input.clone(),
+            report,
+            phase: self.phase,
+            strip_width: solution.strip_width(),
+            strip_height: solution.strip.fixed_height,
+            density: solution.density(instance),
+            total_loss,
+            n_colliding_items,
+            placements: self.include_placements
+                .then(|| jagua_rs::probs::spp::io::export(instance, solution, *EPOCH)),
+        };
+
+        // Written as a whole, so lines of concurrent runs do not interleave
+        let result = serde_json::to_string(&event).map_err(anyhow::Error::from)
+            .and_then(|line| {
+                let mut writer = self.writer.lock().unwrap();
+                writeln!(writer, "{line}").and_then(|_| writer.flush()).map_err(anyhow::Error::from)
+            });
+        if let Err(e) = result {
+            warn!("[JSONL] failed to write progress event: {e}");
+        }
+        Ok(())
+    }
+
+    fn enter_phase(&mut self, phase: Phase) {
+        self.phase = Some(phase);
+    }
+}
@@ -1,11 +1,67 @@
+use std::time::Duration;
 use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use rand_xoshiro::Xoshiro256PlusPlus;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use crate::error::SparrowError;
 
 /// Trait for listeners that can receive solutions during the optimization process
 pub trait SolutionListener {
-    fn report(&mut self, report: ReportType, solution: &SPSolution, instance: &SPInstance);
+    /// Receives a solution, an error aborts the optimization
+    fn report(&mut self, report: ReportType, solution: &SPSolution, instance: &SPInstance) -> Result<(), SparrowError>;
+
+    /// Notifies the listener that the optimization process entered a new phase
+    fn enter_phase(&mut self, _phase: Phase) {}
+
+    /// Whether the listener wants to receive the state of the current phase through [`SolutionListener::checkpoint`]
+    fn checkpoint_due(&self) -> bool {
+        false
+    }
+
+    /// Receives the state of the current phase, from which the optimization can be resumed
+    fn checkpoint(&mut self, _state: &PhaseState) {}
+
+    /// Receives which items of an initial solution were carried over and which were newly placed, when warm starting
+    fn warm_start(&mut self, _report: &WarmStartReport) {}
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Outcome of the construction of a warm started run, see [`crate::optimizer::optimize`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WarmStartReport {
+    /// Item id of every placement carried over from the initial solution
+    pub carried_over: Vec<usize>,
+    /// Item id of every placement added to complete the demand, including fixed items placed (or moved) to their fixed placement
+    pub newly_placed: Vec<usize>,
+}
+
+/// State of the exploration or compression phase, from which the optimization can be resumed.
+/// See [`crate::util::checkpoint`] for more details.
+pub struct PhaseState<'a> {
+    pub phase: Phase,
+    pub instance: &'a SPInstance,
+    /// Best feasible solution found so far
+    pub best: &'a SPSolution,
+    /// Height of the container of the best feasible solution
+    pub best_height: f32,
+    /// Current (possibly infeasible) solution
+    pub current: &'a SPSolution,
+    /// Height of the current container
+    pub current_height: f32,
+    /// Infeasible solutions (and their loss) in the current container
+    pub infeas_sol_pool: &'a [(SPSolution, f32)],
+    /// Random number generator of the separator
+    pub rng: &'a Xoshiro256PlusPlus,
+    /// Random number generators of the workers of the separator
+    pub worker_rngs: Vec<Xoshiro256PlusPlus>,
+    /// Number of failed compression attempts, zero in the exploration phase
+    pub n_failed_attempts: i32,
+    /// Time left in the current phase
+    pub time_left: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
 pub enum ReportType {
     /// Report contains a feasible solution reached by the exploration phase.
     ExplFeas,
@@ -19,11 +75,109 @@ pub enum ReportType {
     Final
 }
 
+/// Phases of the optimization process, in the order they are entered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Construction of the initial solution
+    Construction,
+    Exploration,
+    Compression,
+    /// The final solution has been reported
+    Finished,
+}
+
 /// A dummy implementation of the `SolutionListener` trait that does nothing.
 pub struct DummySolListener;
 
 impl SolutionListener for DummySolListener {
-    fn report(&mut self, _report: ReportType, _solution: &SPSolution, _instance: &SPInstance) {
+    fn report(&mut self, _report: ReportType, _solution: &SPSolution, _instance: &SPInstance) -> Result<(), SparrowError> {
         // Do nothing
+        Ok(())
+    }
+}
+
+impl<L: SolutionListener + ?Sized> SolutionListener for &mut L {
+    fn report(&mut self, report: ReportType, solution: &SPSolution, instance: &SPInstance) -> Result<(), SparrowError> {
+        (**self).report(report, solution, instance)
+    }
+
+    fn enter_phase(&mut self, phase: Phase) {
+        (**self).enter_phase(phase);
+    }
+
+    fn checkpoint_due(&self) -> bool {
+        (**self).checkpoint_due()
+    }
+
+    fn checkpoint(&mut self, state: &PhaseState) {
+        (**self).checkpoint(state);
+    }
+
+    fn warm_start(&mut self, report: &WarmStartReport) {
+        (**self).warm_start(report);
+    }
+}
+
+/// Allows optional listeners
+impl<L: SolutionListener> SolutionListener for Option<L> {
+    fn report(&mut self, report: ReportType, solution: &SPSolution, instance: &SPInstance) -> Result<(), SparrowError> {
+        match self {
+            Some(l) => l.report(report, solution, instance),
+            None => Ok(()),
+        }
+    }
+
+    fn enter_phase(&mut self, phase: Phase) {
+        if let Some(l) = self {
+            l.enter_phase(phase);
+        }
+    }
+
+    fn checkpoint_due(&self) -> bool {
+        self.as_ref().is_some_and(|l| l.checkpoint_due())
+    }
+
+    fn checkpoint(&mut self, state: &PhaseState) {
+        if let Some(l) = self {
+            l.checkpoint(state);
+        }
+    }
+
+    fn warm_start(&mut self, report: &WarmStartReport) {
+        if let Some(l) = self {
+            l.warm_start(report);
+        }
+    }
+}
+
+/// Forwards everything to both listeners
+impl<A: SolutionListener, B: SolutionListener> SolutionListener for (A, B) {
+    fn report(&mut self, report: ReportType, solution: &SPSolution, instance: &SPInstance) -> Result<(), SparrowError> {
+        self.0.report(report.clone(), solution, instance)?;
+        self.1.report(report, solution, instance)
+    }
+
+    fn enter_phase(&mut self, phase: Phase) {
+        self.0.enter_phase(phase);
+        self.1.enter_phase(phase);
+    }
+
+    fn checkpoint_due(&self) -> bool {
+        self.0.checkpoint_due() || self.1.checkpoint_due()
+    }
+
+    fn checkpoint(&mut self, state: &PhaseState) {
+        if self.0.checkpoint_due() {
+            self.0.checkpoint(state);
+        }
+        if self.1.checkpoint_due() {
+            self.1.checkpoint(state);
+        }
+    }
+
+    fn warm_start(&mut self, report: &WarmStartReport) {
+        self.0.warm_start(report);
+        self.1.warm_start(report);
     }
 }
@@ -0,0 +1,165 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use anyhow::{Context, Result};
+use jagua_rs::Instant;
+use jagua_rs::io::svg::s_layout_to_svg;
+use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use log::{debug, info, warn};
+use serde::Serialize;
+use crate::consts::{DRAW_OPTIONS, LIVE_SERVER_MIN_INTERVAL, LIVE_SERVER_READ_TIMEOUT, LIVE_SERVER_WRITE_TIMEOUT};
+use crate::error::SparrowError;
+use crate::util::listener::{Phase, ReportType, SolutionListener};
+
+const VIEWER_HTML: &str = include_str!("../../data/live/sse_viewer.html");
+
+/// Listener which serves a live viewer on `http://localhost:<port>`.
+/// Every report is streamed to the connected browsers as a server-sent event containing the SVG and some statistics.
+/// Intermediate (infeasible or improving) solutions are only rendered while a browser is connected, and at most every [`LIVE_SERVER_MIN_INTERVAL`].
+pub struct LiveServer {
+    pub port: u16,
+    state: Arc<ServerState>,
+    start: Instant,
+    phase: Option<Phase>,
+    /// When the last intermediate solution was streamed
+    last_intermediate: Option<Instant>,
+}
+
+#[derive(Default)]
+struct ServerState {
+    /// Last event sent, replayed to newly connected clients
+    last_event: Mutex<Option<String>>,
+    /// Open event streams
+    clients: Mutex<Vec<TcpStream>>,
+}
+
+#[derive(Serialize)]
+struct LiveEvent<'a> {
+    report: ReportType,
+    phase: Option<Phase>,
+    /// Seconds since the start of the server
+    time: f64,
+    strip_width: f32,
+    strip_height: f32,
+    density: f32,
+    n_items: usize,
+    svg: &'a str,
+}
+
+impl LiveServer {
+    /// Starts the server on localhost in a background thread, every connection is handled in its own thread.
+    /// Port 0 binds to any free port, see [`LiveServer::port`].
+    pub fn start(port: u16) -> Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .with_context(|| format!("could not bind the live viewer to port {port}"))?;
+        let port = listener.local_addr()?.port();
+        let state = Arc::new(ServerState::default());
+        {
+            let state = state.clone();
+            thread::Builder::new()
+                .name("live-server".to_string())
+                .spawn(move || {
+                    for stream in listener.incoming().flatten() {
+                        let state = state.clone();
+                        let spawned = thread::Builder::new()
+                            .name("live-connection".to_string())
+                            .spawn(move || {
+                                if let Err(e) = handle_connection(stream, &state) {
+                                    debug!("[LIVE] connection error: {e:#}");
+                                }
+                            });
+                        if let Err(e) = spawned {
+                            warn!("[LIVE] could not spawn connection thread: {e}");
+                        }
+                    }
+                })
+                .context("could not spawn live viewer thread")?;
+        }
+        info!("[LIVE] live viewer available at http://localhost:{port}");
+
+        Ok(Self { port, state, start: Instant::now(), phase: None, last_intermediate: None })
+    }
+
+    /// Number of browsers connected to the event stream
+    pub fn n_clients(&self) -> usize {
+        self.state.clients.lock().unwrap().len()
+    }
+
+    fn broadcast(&self, event: String) {
+        let mut clients = self.state.clients.lock().unwrap();
+        // Drop all clients which can no longer be written to
+        clients.retain_mut(|client| client.write_all(event.as_bytes()).and_then(|_| client.flush()).is_ok());
+        *self.state.last_event.lock().unwrap() = Some(event);
+    }
+}
+
+impl SolutionListener for LiveServer {
+    fn report(&mut self, report: ReportType, solution: &SPSolution, instance: &SPInstance) -> Result<(), SparrowError> {
+        // Feasible and final solutions are always rendered, to be replayed to browsers which connect later
+        if matches!(report, ReportType::ExplInfeas | ReportType::ExplImproving) {
+            let throttled = self.last_intermediate.is_some_and(|t| t.elapsed() < LIVE_SERVER_MIN_INTERVAL);
+            if throttled || self.n_clients() == 0 {
+                return Ok(());
+            }
+            self.last_intermediate = Some(Instant::now());
+        }
+        let title = format!("{:.3}_{:?}", solution.strip_width(), report);
+        let svg = s_layout_to_svg(&solution.layout_snapshot, instance, DRAW_OPTIONS, &title).to_string();
+        let event = LiveEvent {
+            report,
+            phase: self.phase,
+            time: self.start.elapsed().as_secs_f64(),
+            strip_width: solution.strip_width(),
+            strip_height: solution.strip.fixed_height,
+            density: solution.density(instance),
+            n_items: solution.layout_snapshot.placed_items.len(),
+            svg: &svg,
+        };
+        match serde_json::to_string(&event) {
+            Ok(json) => self.broadcast(format!("event: solution\ndata: {json}\n\n")),
+            Err(e) => warn!("[LIVE] failed to serialize event: {e}"),
+        }
+        Ok(())
+    }
+
+    fn enter_phase(&mut self, phase: Phase) {
+        self.phase = Some(phase);
+        self.broadcast(format!("event: phase\ndata: {}\n\n", serde_json::to_string(&phase).unwrap()));
+    }
+}
+
+/// Serves the viewer on `/` and the event stream on `/events`
+fn handle_connection(mut stream: TcpStream, state: &ServerState) -> Result<()> {
+    stream.set_read_timeout(Some(LIVE_SERVER_READ_TIMEOUT))?;
+    stream.set_write_timeout(Some(LIVE_SERVER_WRITE_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Skip the headers
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    match path {
+        "/" | "/index.html" => {
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", VIEWER_HTML.len(), VIEWER_HTML)?;
+        }
+        "/events" => {
+            // Lock the clients first, so no event is missed between the response and the registration
+            let mut clients = state.clients.lock().unwrap();
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n")?;
+            if let Some(event) = state.last_event.lock().unwrap().as_ref() {
+                stream.write_all(event.as_bytes())?;
+            }
+            stream.flush()?;
+            clients.push(stream);
+        }
+        _ => {
+            write!(stream, "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")?;
+        }
+    }
+    Ok(())
+}
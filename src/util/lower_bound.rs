@@ -0,0 +1,125 @@
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use jagua_rs::entities::Instance;
+use jagua_rs::geometry::geo_enums::RotationRange;
+use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use jagua_rs::probs::spp::io::ext_repr::ExtSPInstance;
+use crate::config::ContainerMode;
+use crate::util::dxf::{self, Contour};
+use crate::util::io::ExtLowerBound;
+
+/// Lower bound on the objective of the container mode (the width of the container, or its area for
+/// [`ContainerMode::FreeRectangle`]), computed from the original (not simplified) shapes of the items.
+#[derive(Debug, Clone, Copy)]
+pub struct LowerBound {
+    pub container_mode: ContainerMode,
+    /// Total area of all items, including their demand
+    pub item_area: f32,
+    /// Smallest width that still fits the largest item at its best allowed rotation: its width,
+    /// or in square and fixed aspect ratio mode, the width of the smallest container of that ratio enclosing it
+    pub item_width: f32,
+    pub bound: f32,
+}
+
+impl LowerBound {
+    pub fn new(ext_instance: &ExtSPInstance, instance: &SPInstance, container_mode: ContainerMode) -> Result<Self> {
+        // Width/height ratio of the container, the height of the items also bounds the width in the modes which fix it
+        let ratio = match container_mode {
+            ContainerMode::Square => 1.0,
+            ContainerMode::FixedAspectRatio(ratio) => ratio as f64,
+            ContainerMode::Strip | ContainerMode::FreeRectangle => 0.0,
+        };
+        let (mut item_area, mut min_width, mut min_enclosing_width) = (0.0, 0.0_f64, 0.0_f64);
+        for ext_item in &ext_instance.items {
+            let contours = dxf::ext_shape_contours(&serde_json::to_value(&ext_item.base.shape)?)
+                .with_context(|| format!("invalid shape of item {}", ext_item.base.id))?;
+            let area = dxf::signed_area(&contours[0]).abs() - contours[1..].iter().map(|h| dxf::signed_area(h).abs()).sum::<f64>();
+            item_area += area * ext_item.demand as f64;
+
+            let (width, enclosing_width) = min_extents(&contours[0], &instance.item(ext_item.base.id as usize).allowed_rotation, ratio);
+            min_width = min_width.max(width);
+            min_enclosing_width = min_enclosing_width.max(enclosing_width);
+        }
+
+        let height = ext_instance.strip_height as f64;
+        let (item_width, bound) = match container_mode {
+            ContainerMode::Strip => (min_width, f64::max(item_area / height, min_width)),
+            ContainerMode::Square | ContainerMode::FixedAspectRatio(_) => (min_enclosing_width, f64::max((item_area * ratio).sqrt(), min_enclosing_width)),
+            // Both dimensions are free, only the area of the items bounds the area of the container
+            ContainerMode::FreeRectangle => (min_width, item_area),
+        };
+        Ok(LowerBound { container_mode, item_area: item_area as f32, item_width: item_width as f32, bound: bound as f32 })
+    }
+
+    /// Gap between the objective of the solution and the bound, as a percentage of the objective
+    pub fn gap(&self, solution: &SPSolution) -> f32 {
+        let objective = self.container_mode.objective(solution.strip_width(), solution.strip.fixed_height);
+        (objective - self.bound) / objective * 100.0
+    }
+
+    pub fn to_ext(&self, gap: Option<f32>) -> ExtLowerBound {
+        ExtLowerBound {
+            bound: self.bound,
+            item_area: self.item_area,
+            item_width: self.item_width,
+            gap,
+        }
+    }
+}
+
+/// Smallest width of the bounding box of a contour over its allowed rotations, and the smallest width of
+/// a container with width/height `ratio` enclosing it
+fn min_extents(contour: &Contour, allowed_rotation: &RotationRange, ratio: f64) -> (f64, f64) {
+    let extents = |rotation: f64| {
+        let (sin, cos) = rotation.sin_cos();
+        let (x_min, x_max) = contour.iter().map(|&(x, y)| x * cos - y * sin).minmax().into_option().unwrap();
+        let (y_min, y_max) = contour.iter().map(|&(x, y)| x * sin + y * cos).minmax().into_option().unwrap();
+        (x_max - x_min, y_max - y_min)
+    };
+    match allowed_rotation {
+        RotationRange::None => {
+            let (w, h) = extents(0.0);
+            (w, w.max(ratio * h))
+        }
+        RotationRange::Discrete(rotations) => rotations.iter()
+            .map(|&r| extents(r as f64))
+            .fold((f64::INFINITY, f64::INFINITY), |(min_w, min_e), (w, h)| (min_w.min(w), min_e.min(w.max(ratio * h)))),
+        RotationRange::Continuous => {
+            // No rotation brings both sides below the minimum width of the shape
+            let width = min_hull_width(contour);
+            (width, width.max(ratio * width))
+        }
+    }
+}
+
+/// Smallest width of a contour over all rotations: the smallest distance between an edge of its convex hull
+/// and the hull vertex furthest away from it.
+fn min_hull_width(contour: &Contour) -> f64 {
+    let hull = convex_hull(contour);
+    hull.iter().circular_tuple_windows()
+        .map(|(&a, &b)| {
+            let len = dxf::dist(a, b);
+            hull.iter().map(|&p| ((b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)).abs() / len).fold(0.0, f64::max)
+        })
+        .filter(|w| w.is_finite())
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Convex hull of the points, counterclockwise (monotone chain)
+fn convex_hull(points: &[(f64, f64)]) -> Contour {
+    let points = points.iter().copied().sorted_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1))).collect_vec();
+    let cross = |o: (f64, f64), a: (f64, f64), b: (f64, f64)| (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0);
+    let mut hull: Contour = vec![];
+    for pass in [points.clone(), points.into_iter().rev().collect_vec()] {
+        let start = hull.len();
+        for p in pass {
+            while hull.len() >= start + 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0 {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        // The last point of each chain is the first of the next
+        hull.pop();
+    }
+    hull
+}
@@ -1,10 +1,23 @@
 pub mod assertions;
 
 pub mod io;
+pub mod dxf;
+pub mod svg_import;
 pub mod bit_reversal_iterator;
 pub mod listener;
 pub mod svg_exporter;
+pub mod placement_table;
+pub mod verify;
+pub mod lower_bound;
+pub mod inspect;
+pub mod run_recorder;
+pub mod checkpoint;
+pub mod progress;
+pub mod jsonl_listener;
 pub mod terminator;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod ctrlc_terminator;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod live_server;
@@ -0,0 +1,63 @@
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use jagua_rs::probs::spp::io::ext_repr::ExtSPInstance;
+use crate::EPOCH;
+use crate::util::dxf;
+
+pub const PLACEMENT_TABLE_HEADER: &str = "record,item_id,instance,x,y,rotation,mirrored,locked,bbox_x_min,bbox_y_min,bbox_x_max,bbox_y_max,area,value";
+
+/// Flat CSV table of a solution, for tools which cannot handle the nested JSON output.
+/// Every placed item is an `item` row with its id, the index of the copy among those with the same id,
+/// its translation, rotation (in degrees), whether it is mirrored and locked, its bounding box and its area.
+/// Translations and rotations are those of the JSON output, bounding boxes and areas are of the original shapes (as defined in the instance).
+/// These are followed by `strip_width`, `strip_height` and `density` rows, which only fill the `value` column.
+pub fn placements_to_csv(ext_instance: &ExtSPInstance, instance: &SPInstance, solution: &SPSolution) -> Result<String> {
+    let ext_solution = jagua_rs::probs::spp::io::export(instance, solution, *EPOCH);
+
+    // The placements of the export are in the same order as the placed items of the solution
+    let locked = solution.layout_snapshot.placed_items.values().map(|pi| pi.is_locked).collect_vec();
+    let placed_items = ext_solution.layout.placed_items.iter()
+        .zip(locked)
+        .sorted_by(|(a, _), (b, _)| {
+            let ((ax, ay), (bx, by)) = (a.transformation.translation, b.transformation.translation);
+            a.item_id.cmp(&b.item_id).then(ax.total_cmp(&bx)).then(ay.total_cmp(&by))
+        })
+        .collect_vec();
+
+    let mut csv = String::from(PLACEMENT_TABLE_HEADER);
+    csv.push('\n');
+    let mut n_copies = vec![0; instance.items.len()];
+    for (ext_pi, is_locked) in placed_items {
+        let item_id = ext_pi.item_id as usize;
+        let item = ext_instance.items.iter()
+            .find(|item| item.base.id == ext_pi.item_id)
+            .with_context(|| format!("placed item {item_id} does not exist in the instance"))?;
+        let contours = dxf::ext_shape_contours(&serde_json::to_value(&item.base.shape)?)?;
+        let area = dxf::signed_area(&contours[0]).abs() - contours[1..].iter().map(|h| dxf::signed_area(h).abs()).sum::<f64>();
+
+        let (rotation, (x, y)) = (ext_pi.transformation.rotation, ext_pi.transformation.translation);
+        let (sin, cos) = (rotation as f64).sin_cos();
+        let (x_min, y_min, x_max, y_max) = contours[0].iter()
+            .map(|&(px, py)| (px * cos - py * sin + x as f64, px * sin + py * cos + y as f64))
+            .fold((f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY), |(x0, y0, x1, y1), (px, py)| {
+                (x0.min(px), y0.min(py), x1.max(px), y1.max(py))
+            });
+        // jagua-rs never mirrors items, the column is part of the table for downstream tools
+        let mirrored = false;
+        csv.push_str(&format!(
+            "item,{},{},{},{},{},{},{},{},{},{},{},{},\n",
+            item_id, n_copies[item_id], x, y, rotation.to_degrees(), mirrored, is_locked,
+            x_min, y_min, x_max, y_max, area
+        ));
+        n_copies[item_id] += 1;
+    }
+    for (record, value) in [
+        ("strip_width", solution.strip_width()),
+        ("strip_height", solution.strip.fixed_height),
+        ("density", solution.density(instance)),
+    ] {
+        csv.push_str(&format!("{record},,,,,,,,,,,,,{value}\n"));
+    }
+    Ok(csv)
+}
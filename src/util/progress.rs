@@ -0,0 +1,80 @@
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU32, Ordering};
+use jagua_rs::Instant;
+use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use crate::error::SparrowError;
+use crate::util::listener::{ReportType, SolutionListener};
+
+/// Shared record of the best feasible solution of a run, used by the quality based terminators
+/// (see [`crate::util::terminator::StopCriteria`]).
+/// All clones share the same state: add one to the solution listener of the run and give the others to the terminators.
+#[derive(Debug, Clone)]
+pub struct Progress {
+    inner: Arc<ProgressInner>,
+}
+
+#[derive(Debug)]
+struct ProgressInner {
+    /// Bits of the (f32) width of the narrowest feasible solution
+    best_width: AtomicU32,
+    /// Bits of the (f32) highest density of a feasible solution
+    best_density: AtomicU32,
+    last_improvement: Mutex<Option<Instant>>,
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(ProgressInner {
+                best_width: AtomicU32::new(f32::INFINITY.to_bits()),
+                best_density: AtomicU32::new(0.0f32.to_bits()),
+                last_improvement: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Width of the narrowest feasible solution so far (infinite if none was reported)
+    pub fn best_width(&self) -> f32 {
+        f32::from_bits(self.inner.best_width.load(Ordering::Relaxed))
+    }
+
+    /// Highest density of a feasible solution so far (zero if none was reported)
+    pub fn best_density(&self) -> f32 {
+        f32::from_bits(self.inner.best_density.load(Ordering::Relaxed))
+    }
+
+    /// Last time the width or density of the best feasible solution improved
+    pub fn last_improvement(&self) -> Option<Instant> {
+        *self.inner.last_improvement.lock().unwrap()
+    }
+}
+
+impl SolutionListener for Progress {
+    fn report(&mut self, report: ReportType, solution: &SPSolution, instance: &SPInstance) -> Result<(), SparrowError> {
+        match report {
+            ReportType::ExplFeas | ReportType::CmprFeas | ReportType::Final => {
+                let (width, density) = (solution.strip_width(), solution.density(instance));
+                let mut improved = false;
+                if width < self.best_width() {
+                    self.inner.best_width.store(width.to_bits(), Ordering::Relaxed);
+                    improved = true;
+                }
+                if density > self.best_density() {
+                    self.inner.best_density.store(density.to_bits(), Ordering::Relaxed);
+                    improved = true;
+                }
+                if improved {
+                    *self.inner.last_improvement.lock().unwrap() = Some(Instant::now());
+                }
+            }
+            ReportType::ExplInfeas | ReportType::ExplImproving => {}
+        }
+        Ok(())
+    }
+}
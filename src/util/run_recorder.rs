@@ -0,0 +1,108 @@
+use std::time::Duration;
+use jagua_rs::Instant;
+use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use crate::config::SparrowConfig;
+use crate::error::SparrowError;
+use crate::util::io::{ExtDensityRecord, ExtPhaseTimes, ExtRunMetadata};
+use crate::util::listener::{Phase, ReportType, SolutionListener, WarmStartReport};
+use crate::util::lower_bound::LowerBound;
+
+/// Records the wall time of every phase and the density of all feasible solutions reported during a run
+pub struct RunRecorder {
+    start: Instant,
+    phase_starts: Vec<(Phase, Duration)>,
+    pub density_history: Vec<ExtDensityRecord>,
+    lower_bound: Option<LowerBound>,
+    final_gap: Option<f32>,
+    warm_start: Option<WarmStartReport>,
+}
+
+impl RunRecorder {
+    pub fn new() -> Self {
+        RunRecorder {
+            start: Instant::now(),
+            phase_starts: vec![],
+            density_history: vec![],
+            lower_bound: None,
+            final_gap: None,
+            warm_start: None,
+        }
+    }
+
+    /// Records the gap between the final solution and `lower_bound` in the metadata
+    pub fn with_lower_bound(mut self, lower_bound: Option<LowerBound>) -> Self {
+        self.lower_bound = lower_bound;
+        self
+    }
+
+    /// Wall time spent in every phase, in seconds
+    pub fn phase_times(&self) -> ExtPhaseTimes {
+        let end = match self.phase_starts.last() {
+            Some((Phase::Finished, end)) => *end,
+            _ => self.start.elapsed(),
+        };
+        let time_in = |phase: Phase| {
+            self.phase_starts.iter().enumerate()
+                .filter(|(_, (p, _))| *p == phase)
+                .map(|(i, (_, start))| {
+                    let phase_end = self.phase_starts.get(i + 1).map_or(end, |(_, s)| *s);
+                    (phase_end - *start).as_secs_f64()
+                })
+                .sum()
+        };
+        ExtPhaseTimes {
+            construction: time_in(Phase::Construction),
+            exploration: time_in(Phase::Exploration),
+            compression: time_in(Phase::Compression),
+            total: end.as_secs_f64(),
+        }
+    }
+
+    /// Metadata of the recorded run, to be embedded in the output
+    pub fn metadata(&self, rng_seed: u64, config: SparrowConfig) -> ExtRunMetadata {
+        ExtRunMetadata {
+            rng_seed,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            config,
+            phase_times: self.phase_times(),
+            density_history: self.density_history.clone(),
+            lower_bound: self.lower_bound.map(|lb| lb.to_ext(self.final_gap)),
+            warm_start: self.warm_start.clone(),
+        }
+    }
+}
+
+impl Default for RunRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SolutionListener for RunRecorder {
+    fn report(&mut self, report_type: ReportType, solution: &SPSolution, instance: &SPInstance) -> Result<(), SparrowError> {
+        let phase = match report_type {
+            ReportType::ExplFeas => Phase::Exploration,
+            ReportType::CmprFeas => Phase::Compression,
+            ReportType::Final => Phase::Finished,
+            ReportType::ExplInfeas | ReportType::ExplImproving => return Ok(()),
+        };
+        if report_type == ReportType::Final {
+            self.final_gap = self.lower_bound.map(|lb| lb.gap(solution));
+        }
+        self.density_history.push(ExtDensityRecord {
+            time: self.start.elapsed().as_secs_f64(),
+            phase,
+            strip_width: solution.strip_width(),
+            density: solution.density(instance),
+        });
+        Ok(())
+    }
+
+    fn enter_phase(&mut self, phase: Phase) {
+        self.phase_starts.push((phase, self.start.elapsed()));
+    }
+
+    fn warm_start(&mut self, report: &WarmStartReport) {
+        self.warm_start = Some(report.clone());
+    }
+}
@@ -1,8 +1,11 @@
-use std::path::Path;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 use jagua_rs::io::svg::s_layout_to_svg;
 use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
-use log::Level;
+use log::{warn, Level};
+use anyhow::Context;
 use crate::consts::DRAW_OPTIONS;
+use crate::error::SparrowError;
 use crate::util::io;
 use crate::util::listener::{SolutionListener, ReportType};
 pub struct SvgExporter {
@@ -13,6 +16,11 @@ pub struct SvgExporter {
     pub intermediate_dir: Option<String>,
     /// Path to write the live SVG file to, if provided
     pub live_path: Option<String>,
+    /// Report types which are exported to the intermediate directory
+    pub intermediate_filter: Vec<ReportType>,
+    /// Maximum number of intermediate SVG files, older ones are removed
+    pub max_intermediate_files: Option<usize>,
+    intermediate_files: VecDeque<PathBuf>,
 }
 
 impl SvgExporter {
@@ -21,8 +29,9 @@ impl SvgExporter {
         if let Some(intermediate_dir) = &intermediate_dir
             && let Ok(files_in_dir) = std::fs::read_dir(Path::new(intermediate_dir)) {
                 for file in files_in_dir.flatten() {
-                    if file.path().extension().unwrap_or_default() == "svg" {
-                        std::fs::remove_file(file.path()).unwrap();
+                    if file.path().extension().unwrap_or_default() == "svg"
+                        && let Err(e) = std::fs::remove_file(file.path()) {
+                        warn!("[SVG] failed to remove {}: {e}", file.path().display());
                     }
                 }
             }
@@ -32,12 +41,15 @@ impl SvgExporter {
             final_path,
             intermediate_dir,
             live_path,
+            intermediate_filter: vec![ReportType::ExplFeas, ReportType::ExplInfeas, ReportType::CmprFeas, ReportType::Final],
+            max_intermediate_files: None,
+            intermediate_files: VecDeque::new(),
         }
     }
 }
 
 impl SolutionListener for SvgExporter{
-    fn report(&mut self, report_type: ReportType, solution: &SPSolution, instance: &SPInstance) {
+    fn report(&mut self, report_type: ReportType, solution: &SPSolution, instance: &SPInstance) -> Result<(), SparrowError> {
         let suffix = match report_type {
             ReportType::CmprFeas => "cmpr",
             ReportType::ExplInfeas => "expl_nf",
@@ -48,18 +60,37 @@ impl SolutionListener for SvgExporter{
         let file_name = format!("{}_{:.3}_{}", self.svg_counter, solution.strip_width(), suffix);
         if let Some(live_path) = &self.live_path {
             let svg = s_layout_to_svg(&solution.layout_snapshot, instance, DRAW_OPTIONS, file_name.as_str());
-            io::write_svg(&svg, Path::new(live_path), Level::Trace).expect("failed to write live svg");
+            // A missed snapshot is not worth aborting the optimization for
+            if let Err(e) = io::write_svg(&svg, Path::new(live_path), Level::Trace) {
+                warn!("[SVG] failed to write live svg: {e}");
+            }
         }
-        if let Some(intermediate_dir) = &self.intermediate_dir && report_type != ReportType::ExplImproving {
+        if let Some(intermediate_dir) = &self.intermediate_dir && self.intermediate_filter.contains(&report_type) {
             let svg = s_layout_to_svg(&solution.layout_snapshot, instance, DRAW_OPTIONS, file_name.as_str());
-            let file_path = &*format!("{intermediate_dir}/{file_name}.svg");
-            io::write_svg(&svg, Path::new(file_path), Level::Trace).expect("failed to write intermediate svg");
-            self.svg_counter += 1;
+            let file_path = PathBuf::from(format!("{intermediate_dir}/{file_name}.svg"));
+            match io::write_svg(&svg, &file_path, Level::Trace) {
+                Ok(()) => {
+                    self.svg_counter += 1;
+                    self.intermediate_files.push_back(file_path);
+                }
+                Err(e) => warn!("[SVG] failed to write intermediate svg {}: {e}", file_path.display()),
+            }
+            if let Some(max_files) = self.max_intermediate_files {
+                while self.intermediate_files.len() > max_files {
+                    if let Some(oldest) = self.intermediate_files.pop_front()
+                        && let Err(e) = std::fs::remove_file(&oldest) {
+                        warn!("[SVG] failed to remove {}: {e}", oldest.display());
+                    }
+                }
+            }
         }
         if let Some(final_path) = &self.final_path && report_type == ReportType::Final {
-            let stem = Path::new(final_path).file_stem().unwrap();
-            let svg = s_layout_to_svg(&solution.layout_snapshot, instance, DRAW_OPTIONS, stem.to_str().unwrap());
-            io::write_svg(&svg, Path::new(final_path), Level::Info).expect("failed to write final svg");
+            let title = Path::new(final_path).file_stem().map_or("final".into(), |stem| stem.to_string_lossy());
+            let svg = s_layout_to_svg(&solution.layout_snapshot, instance, DRAW_OPTIONS, &title);
+            io::write_svg(&svg, Path::new(final_path), Level::Info)
+                .context("failed to write final svg")
+                .map_err(SparrowError::Listener)?;
         }
+        Ok(())
     }
-}
\ No newline at end of file
+}
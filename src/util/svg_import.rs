@@ -0,0 +1,460 @@
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+use log::debug;
+use serde_json::json;
+use svg::node::element::path::{Command, Data, Position};
+use svg::node::element::tag::Type;
+use svg::node::Attributes;
+use svg::parser::Event;
+use jagua_rs::probs::spp::io::ext_repr::ExtSPInstance;
+use crate::config::SvgImportConfig;
+use crate::util::dxf::{self, Contour};
+
+/// Affine transformation `[a, b, c, d, e, f]`, mapping (x, y) to (a*x + c*y + e, b*x + d*y + f), as in SVG's `matrix()`
+type Transform = [f64; 6];
+
+const IDENTITY: Transform = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/// Elements whose content is not rendered by itself
+const NON_RENDERED: [&str; 6] = ["defs", "clipPath", "mask", "pattern", "symbol", "marker"];
+
+/// Reads a strip packing instance from an SVG file, with one item per `path`, `polygon`, `polyline`, `rect`, `circle` or `ellipse` element.
+/// - `data-demand` and `data-orientations` (degrees, comma separated) on an element define the demand (default 1) and allowed orientations of its item.
+///   `data-value` and `data-priority` define its value and priority in knapsack mode.
+/// - A `g` element with a `data-item` attribute is a single item consisting of all its shapes (e.g. an outline and its holes).
+/// - The strip height is taken from `data-strip-height` on the root element, from the height of the element marked with `data-container`,
+///   or from the height of the `viewBox`, in that order.
+/// - The name of the instance is `data-name` on the root element, or the file stem.
+///
+/// The largest contour of an item is its outer boundary, all others are holes.
+/// The y-axis is flipped, so the items are not mirrored compared to how they are drawn.
+pub fn read_svg_instance(path: &Path, config: &SvgImportConfig) -> Result<ExtSPInstance> {
+    serde_json::from_value(read_svg_instance_json(path, config)?).context("could not build instance from SVG")
+}
+
+/// Same as [`read_svg_instance`], but returns the instance as JSON, including the knapsack attributes of the items
+pub fn read_svg_instance_json(path: &Path, config: &SvgImportConfig) -> Result<serde_json::Value> {
+    let content = fs::read_to_string(path).with_context(|| format!("could not read SVG file {}", path.display()))?;
+    let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("svg_instance");
+    svg_instance_json(&content, name, config).with_context(|| format!("could not import SVG file {}", path.display()))
+}
+
+/// Parses an instance from the content of an SVG file, see [`read_svg_instance`]
+pub fn parse_svg_instance(content: &str, default_name: &str, config: &SvgImportConfig) -> Result<ExtSPInstance> {
+    serde_json::from_value(svg_instance_json(content, default_name, config)?).context("could not build instance from SVG")
+}
+
+fn svg_instance_json(content: &str, default_name: &str, config: &SvgImportConfig) -> Result<serde_json::Value> {
+    let tol = config.flatten_tolerance as f64;
+    let mut stack: Vec<Frame> = vec![];
+    let mut root: Option<Attributes> = None;
+    let mut items: Vec<SvgItem> = vec![];
+    let mut container_height = None;
+
+    for event in svg::Parser::new(content) {
+        let (tag, tag_type, attrs) = match event {
+            Event::Tag(tag, tag_type, attrs) => (tag, tag_type, attrs),
+            Event::Error(e) => bail!("invalid SVG: {e}"),
+            _ => continue,
+        };
+        if tag_type == Type::End {
+            stack.pop();
+            continue;
+        }
+        if root.is_none() && tag == "svg" {
+            root = Some(attrs.clone());
+        }
+
+        let parent = stack.last().copied().unwrap_or(Frame { transform: IDENTITY, skip: false, item: None });
+        let mut frame = Frame {
+            transform: match attrs.get("transform") {
+                Some(t) => compose(parent.transform, parse_transform(t)?),
+                None => parent.transform,
+            },
+            skip: parent.skip || NON_RENDERED.contains(&tag),
+            item: parent.item,
+        };
+        let label = attrs.get("id").map_or_else(|| format!("<{tag}> #{}", items.len()), |id| id.to_string());
+
+        if !frame.skip {
+            if tag == "g" && attrs.contains_key("data-item") && frame.item.is_none() {
+                items.push(SvgItem::new(label, &attrs)?);
+                frame.item = Some(items.len() - 1);
+            } else {
+                // Flatten in the local coordinates, with the tolerance scaled to match the final coordinates
+                let scale = (frame.transform[0] * frame.transform[3] - frame.transform[1] * frame.transform[2]).abs().sqrt();
+                let local_tol = if scale > 0.0 { tol / scale } else { tol };
+                if let Some(contours) = shape_contours(tag, &attrs, local_tol).with_context(|| format!("invalid element {label}"))? {
+                    let contours = contours.into_iter()
+                        .map(|c| finish_contour(c, &frame.transform))
+                        .filter(|c| c.len() >= 3)
+                        .collect_vec();
+                    if attrs.contains_key("data-container") {
+                        let ys = contours.iter().flatten().map(|p| p.1).collect_vec();
+                        container_height = ys.iter().copied().reduce(f64::max).zip(ys.iter().copied().reduce(f64::min)).map(|(max, min)| max - min);
+                    } else if let Some(i) = frame.item {
+                        items[i].contours.extend(contours);
+                    } else if !contours.is_empty() {
+                        let mut item = SvgItem::new(label, &attrs)?;
+                        item.contours = contours;
+                        items.push(item);
+                    } else {
+                        debug!("[SVG] ignoring element {label} without closed contours");
+                    }
+                }
+            }
+        }
+        if tag_type == Type::Start {
+            stack.push(frame);
+        }
+    }
+
+    let root = root.context("no <svg> root element found")?;
+    let name = root.get("data-name").map_or(default_name, |n| &**n);
+    let strip_height = match (number(&root, "data-strip-height")?, container_height) {
+        (Some(height), _) | (None, Some(height)) => height,
+        (None, None) => match root.get("viewBox") {
+            Some(view_box) => *numbers(view_box)?.get(3).context("invalid viewBox")?,
+            None => number(&root, "height")?.context("strip height not defined, add data-strip-height to the root element")?,
+        },
+    };
+    if items.is_empty() {
+        bail!("no items found");
+    }
+
+    let ext_items = items.into_iter().enumerate()
+        .map(|(id, SvgItem { label, contours, demand, orientations, value, priority })| {
+            let shape = dxf::ext_shape(contours).with_context(|| format!("invalid shape for item {label}"))?;
+            let mut ext_item = json!({ "id": id, "demand": demand, "shape": shape, "priority": priority });
+            if let Some(orientations) = orientations {
+                ext_item["allowed_orientations"] = json!(orientations);
+            }
+            if let Some(value) = value {
+                ext_item["value"] = json!(value);
+            }
+            Ok(ext_item)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    debug!("[SVG] imported {} items from {name}", ext_items.len());
+
+    Ok(json!({ "name": name, "items": ext_items, "strip_height": strip_height }))
+}
+
+#[derive(Clone, Copy)]
+struct Frame {
+    /// Transformation from the element's coordinates to the root's
+    transform: Transform,
+    /// Whether the element is not rendered (e.g. inside `defs`)
+    skip: bool,
+    /// Index of the item (`g` with `data-item`) this element belongs to
+    item: Option<usize>,
+}
+
+struct SvgItem {
+    label: String,
+    contours: Vec<Contour>,
+    demand: u64,
+    orientations: Option<Vec<f32>>,
+    value: Option<f32>,
+    priority: i32,
+}
+
+impl SvgItem {
+    fn new(label: String, attrs: &Attributes) -> Result<Self> {
+        let demand = match attrs.get("data-demand") {
+            Some(d) => d.trim().parse().with_context(|| format!("invalid data-demand of {label}"))?,
+            None => 1,
+        };
+        let orientations = attrs.get("data-orientations")
+            .map(|o| numbers(o).map(|o| o.into_iter().map(|a| a as f32).collect_vec()))
+            .transpose()
+            .with_context(|| format!("invalid data-orientations of {label}"))?;
+        let value = attrs.get("data-value")
+            .map(|v| v.trim().parse())
+            .transpose()
+            .with_context(|| format!("invalid data-value of {label}"))?;
+        let priority = match attrs.get("data-priority") {
+            Some(p) => p.trim().parse().with_context(|| format!("invalid data-priority of {label}"))?,
+            None => 0,
+        };
+        Ok(SvgItem { label, contours: vec![], demand, orientations, value, priority })
+    }
+}
+
+/// Contours of a shape element, in its local coordinates. `None` if the element is not a shape.
+fn shape_contours(tag: &str, attrs: &Attributes, tol: f64) -> Result<Option<Vec<Contour>>> {
+    let get = |name: &str| number(attrs, name).map(|n| n.unwrap_or(0.0));
+    let contours = match tag {
+        "path" => path_contours(attrs.get("d").map_or("", |d| &**d), tol)?,
+        "polygon" | "polyline" => {
+            let points = numbers(attrs.get("points").map_or("", |p| &**p))?.into_iter().tuples().collect_vec();
+            vec![points]
+        }
+        "rect" => {
+            // Rounded corners are ignored
+            let (x, y, w, h) = (get("x")?, get("y")?, get("width")?, get("height")?);
+            vec![vec![(x, y), (x + w, y), (x + w, y + h), (x, y + h)]]
+        }
+        "circle" => {
+            let mut circle = dxf::arc((get("cx")?, get("cy")?), get("r")?, 0.0, 2.0 * PI, tol);
+            circle.pop();
+            vec![circle]
+        }
+        "ellipse" => {
+            let (cx, cy, rx, ry) = (get("cx")?, get("cy")?, get("rx")?, get("ry")?);
+            let mut ellipse = dxf::arc((0.0, 0.0), 1.0, 0.0, 2.0 * PI, tol / rx.max(ry))
+                .into_iter()
+                .map(|(u, v)| (cx + rx * u, cy + ry * v))
+                .collect_vec();
+            ellipse.pop();
+            vec![ellipse]
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(contours))
+}
+
+/// Flattens the subpaths of SVG path data. Open subpaths are closed implicitly.
+pub fn path_contours(d: &str, tol: f64) -> Result<Vec<Contour>> {
+    let data = Data::parse(d).context("invalid path data")?;
+    let mut contours = vec![];
+    let mut current: Contour = vec![];
+    let (mut pos, mut start) = ((0.0, 0.0), (0.0, 0.0));
+    // Last control points of the previous command, to reflect for smooth curves
+    let (mut last_cubic, mut last_quad) = (None, None);
+
+    for command in data.iter() {
+        let (mut next_cubic, mut next_quad) = (None, None);
+        match command {
+            Command::Move(position, params) => {
+                for (i, p) in params.iter().map(|&v| v as f64).tuples().enumerate() {
+                    let p = absolute(position, pos, p);
+                    if i == 0 {
+                        contours.push(std::mem::take(&mut current));
+                        start = p;
+                    }
+                    // Subsequent pairs are implicit line commands
+                    current.push(p);
+                    pos = p;
+                }
+            }
+            Command::Line(position, params) => {
+                for p in params.iter().map(|&v| v as f64).tuples() {
+                    pos = absolute(position, pos, p);
+                    current.push(pos);
+                }
+            }
+            Command::HorizontalLine(position, params) => {
+                for &x in params.iter() {
+                    pos = (absolute(position, pos, (x as f64, 0.0)).0, pos.1);
+                    current.push(pos);
+                }
+            }
+            Command::VerticalLine(position, params) => {
+                for &y in params.iter() {
+                    pos = (pos.0, absolute(position, pos, (0.0, y as f64)).1);
+                    current.push(pos);
+                }
+            }
+            Command::CubicCurve(position, params) => {
+                for (x1, y1, x2, y2, x, y) in params.iter().map(|&v| v as f64).tuples() {
+                    let (c1, c2, p) = (absolute(position, pos, (x1, y1)), absolute(position, pos, (x2, y2)), absolute(position, pos, (x, y)));
+                    cubic(pos, c1, c2, p, tol, &mut current);
+                    (pos, next_cubic) = (p, Some(c2));
+                }
+            }
+            Command::SmoothCubicCurve(position, params) => {
+                for (x2, y2, x, y) in params.iter().map(|&v| v as f64).tuples() {
+                    let c1 = reflect(next_cubic.or(last_cubic), pos);
+                    let (c2, p) = (absolute(position, pos, (x2, y2)), absolute(position, pos, (x, y)));
+                    cubic(pos, c1, c2, p, tol, &mut current);
+                    (pos, next_cubic) = (p, Some(c2));
+                }
+            }
+            Command::QuadraticCurve(position, params) => {
+                for (x1, y1, x, y) in params.iter().map(|&v| v as f64).tuples() {
+                    let (q, p) = (absolute(position, pos, (x1, y1)), absolute(position, pos, (x, y)));
+                    quadratic(pos, q, p, tol, &mut current);
+                    (pos, next_quad) = (p, Some(q));
+                }
+            }
+            Command::SmoothQuadraticCurve(position, params) => {
+                for (x, y) in params.iter().map(|&v| v as f64).tuples() {
+                    let q = reflect(next_quad.or(last_quad), pos);
+                    let p = absolute(position, pos, (x, y));
+                    quadratic(pos, q, p, tol, &mut current);
+                    (pos, next_quad) = (p, Some(q));
+                }
+            }
+            Command::EllipticalArc(position, params) => {
+                for (rx, ry, phi, large_arc, sweep, x, y) in params.iter().map(|&v| v as f64).tuples() {
+                    let p = absolute(position, pos, (x, y));
+                    current.extend(elliptical_arc(pos, (rx, ry), phi, large_arc != 0.0, sweep != 0.0, p, tol));
+                    pos = p;
+                }
+            }
+            Command::Close => {
+                contours.push(std::mem::take(&mut current));
+                // A new subpath without a move command starts where the closed one started
+                current.push(start);
+                pos = start;
+            }
+        }
+        (last_cubic, last_quad) = (next_cubic, next_quad);
+    }
+    contours.push(current);
+    Ok(contours.into_iter().filter(|c| c.len() >= 3).collect())
+}
+
+fn absolute(position: &Position, pos: (f64, f64), p: (f64, f64)) -> (f64, f64) {
+    match position {
+        Position::Absolute => p,
+        Position::Relative => (pos.0 + p.0, pos.1 + p.1),
+    }
+}
+
+/// Reflection of the previous control point around `pos`, or `pos` itself if there is none
+fn reflect(ctrl: Option<(f64, f64)>, pos: (f64, f64)) -> (f64, f64) {
+    ctrl.map_or(pos, |c| (2.0 * pos.0 - c.0, 2.0 * pos.1 - c.1))
+}
+
+/// Adds the points of a cubic Bézier curve (except its start) to `points`
+fn cubic(p0: (f64, f64), c1: (f64, f64), c2: (f64, f64), p1: (f64, f64), tol: f64, points: &mut Contour) {
+    let eval = |t: f64| {
+        let s = 1.0 - t;
+        let [w0, w1, w2, w3] = [s * s * s, 3.0 * s * s * t, 3.0 * s * t * t, t * t * t];
+        (w0 * p0.0 + w1 * c1.0 + w2 * c2.0 + w3 * p1.0, w0 * p0.1 + w1 * c1.1 + w2 * c2.1 + w3 * p1.1)
+    };
+    // Start from a few segments, a single chord can miss an S-shaped curve
+    for (t0, t1) in [0.0, 0.25, 0.5, 0.75, 1.0].into_iter().tuple_windows() {
+        dxf::flatten_curve(&eval, t0, t1, tol, points);
+    }
+}
+
+fn quadratic(p0: (f64, f64), q: (f64, f64), p1: (f64, f64), tol: f64, points: &mut Contour) {
+    let c1 = (p0.0 + 2.0 / 3.0 * (q.0 - p0.0), p0.1 + 2.0 / 3.0 * (q.1 - p0.1));
+    let c2 = (p1.0 + 2.0 / 3.0 * (q.0 - p1.0), p1.1 + 2.0 / 3.0 * (q.1 - p1.1));
+    cubic(p0, c1, c2, p1, tol, points);
+}
+
+/// Points of an SVG elliptical arc (except its start), converted to center parameterization as in the SVG spec (appendix B.2.4)
+fn elliptical_arc(p0: (f64, f64), (rx, ry): (f64, f64), phi: f64, large_arc: bool, sweep: bool, p1: (f64, f64), tol: f64) -> Contour {
+    let (mut rx, mut ry) = (rx.abs(), ry.abs());
+    if p0 == p1 {
+        return vec![];
+    }
+    if rx == 0.0 || ry == 0.0 {
+        return vec![p1];
+    }
+    let (sin, cos) = phi.to_radians().sin_cos();
+    let (dx, dy) = ((p0.0 - p1.0) / 2.0, (p0.1 - p1.1) / 2.0);
+    let (x1, y1) = (cos * dx + sin * dy, -sin * dx + cos * dy);
+
+    // Scale up the radii if no ellipse can connect both points
+    let lambda = x1 * x1 / (rx * rx) + y1 * y1 / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+    let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+    let coef = sign * (num / den).max(0.0).sqrt();
+    let (cx1, cy1) = (coef * rx * y1 / ry, -coef * ry * x1 / rx);
+    let center = (cos * cx1 - sin * cy1 + (p0.0 + p1.0) / 2.0, sin * cx1 + cos * cy1 + (p0.1 + p1.1) / 2.0);
+
+    let theta = ((y1 - cy1) / ry).atan2((x1 - cx1) / rx);
+    let mut delta = ((-y1 - cy1) / ry).atan2((-x1 - cx1) / rx) - theta;
+    if sweep && delta < 0.0 {
+        delta += 2.0 * PI;
+    } else if !sweep && delta > 0.0 {
+        delta -= 2.0 * PI;
+    }
+
+    let mut points = dxf::arc((0.0, 0.0), 1.0, theta, delta, tol / rx.max(ry))
+        .into_iter()
+        .skip(1)
+        .map(|(u, v)| (center.0 + rx * u * cos - ry * v * sin, center.1 + rx * u * sin + ry * v * cos))
+        .collect_vec();
+    // Snap the end to the target point, to avoid gaps due to rounding
+    *points.last_mut().unwrap() = p1;
+    points
+}
+
+/// Transforms the contour to the root's coordinates, flips the y-axis and removes duplicate points
+fn finish_contour(contour: Contour, transform: &Transform) -> Contour {
+    let mut contour = contour.into_iter()
+        .map(|p| apply(transform, p))
+        .map(|(x, y)| (x, -y))
+        .dedup()
+        .collect_vec();
+    if contour.len() > 1 && contour[0] == *contour.last().unwrap() {
+        contour.pop();
+    }
+    contour
+}
+
+fn parse_transform(transform: &str) -> Result<Transform> {
+    let mut result = IDENTITY;
+    let parts = transform.split(')')
+        .map(|p| p.trim_matches(|c: char| c.is_whitespace() || c == ','))
+        .filter(|p| !p.is_empty());
+    for part in parts {
+        let (name, args) = part.split_once('(').with_context(|| format!("invalid transform: {transform}"))?;
+        let args = numbers(args)?;
+        let translation = |tx: f64, ty: f64| [1.0, 0.0, 0.0, 1.0, tx, ty];
+        let rotation = |angle: f64| {
+            let (sin, cos) = angle.to_radians().sin_cos();
+            [cos, sin, -sin, cos, 0.0, 0.0]
+        };
+        let t = match (name.trim(), args.as_slice()) {
+            ("matrix", &[a, b, c, d, e, f]) => [a, b, c, d, e, f],
+            ("translate", &[tx]) => translation(tx, 0.0),
+            ("translate", &[tx, ty]) => translation(tx, ty),
+            ("scale", &[s]) => [s, 0.0, 0.0, s, 0.0, 0.0],
+            ("scale", &[sx, sy]) => [sx, 0.0, 0.0, sy, 0.0, 0.0],
+            ("rotate", &[angle]) => rotation(angle),
+            ("rotate", &[angle, cx, cy]) => compose(compose(translation(cx, cy), rotation(angle)), translation(-cx, -cy)),
+            ("skewX", &[angle]) => [1.0, 0.0, angle.to_radians().tan(), 1.0, 0.0, 0.0],
+            ("skewY", &[angle]) => [1.0, angle.to_radians().tan(), 0.0, 1.0, 0.0, 0.0],
+            (name, args) => bail!("unsupported transform {name} with {} arguments", args.len()),
+        };
+        result = compose(result, t);
+    }
+    Ok(result)
+}
+
+/// Transformation applying `n` first, then `m`
+fn compose(m: Transform, n: Transform) -> Transform {
+    [
+        m[0] * n[0] + m[2] * n[1],
+        m[1] * n[0] + m[3] * n[1],
+        m[0] * n[2] + m[2] * n[3],
+        m[1] * n[2] + m[3] * n[3],
+        m[0] * n[4] + m[2] * n[5] + m[4],
+        m[1] * n[4] + m[3] * n[5] + m[5],
+    ]
+}
+
+fn apply(m: &Transform, (x, y): (f64, f64)) -> (f64, f64) {
+    (m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5])
+}
+
+/// Numbers separated by commas and/or whitespace
+fn numbers(s: &str) -> Result<Vec<f64>> {
+    s.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|n| !n.is_empty())
+        .map(|n| n.parse::<f64>().with_context(|| format!("invalid number: {n}")))
+        .collect()
+}
+
+/// Numeric attribute, ignoring its unit (e.g. `mm` or `px`)
+fn number(attrs: &Attributes, name: &str) -> Result<Option<f64>> {
+    attrs.get(name)
+        .map(|v| v.trim().trim_end_matches(|c: char| c.is_ascii_alphabetic() || c == '%'))
+        .map(|v| v.parse::<f64>().with_context(|| format!("invalid {name}: {v}")))
+        .transpose()
+}
@@ -1,5 +1,11 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
 use jagua_rs::Instant;
+use log::info;
+use crate::consts::STOP_FILE_POLL_INTERVAL;
+use crate::util::progress::Progress;
 
 /// Generic trait for any struct that can determine if the optimization process should terminate.
 pub trait Terminator {
@@ -13,6 +19,11 @@ pub trait Terminator {
     fn timeout_at(&self) -> Option<Instant>;
 }
 
+/// Time left until the timeout of the terminator (zero if no timeout is set)
+pub fn time_left(term: &impl Terminator) -> Duration {
+    term.timeout_at().map_or(Duration::ZERO, |timeout| timeout.saturating_duration_since(Instant::now()))
+}
+
 #[derive(Debug, Clone)]
 pub struct BasicTerminator {
     pub timeout: Option<Instant>,
@@ -42,4 +53,306 @@ impl Terminator for BasicTerminator {
     fn timeout_at(&self) -> Option<Instant> {
         self.timeout
     }
-}
\ No newline at end of file
+}
+
+/// Terminator which can be cancelled from any thread, through any of its clones
+#[derive(Debug, Clone, Default)]
+pub struct CancellationTerminator {
+    pub timeout: Option<Instant>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationTerminator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Terminates the optimization (all remaining phases) as soon as possible
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+impl Terminator for CancellationTerminator {
+    fn kill(&self) -> bool {
+        self.timeout.is_some_and(|timeout| Instant::now() > timeout) || self.is_cancelled()
+    }
+
+    fn new_timeout(&mut self, timeout: Duration) {
+        self.timeout = Some(Instant::now() + timeout);
+    }
+
+    fn timeout_at(&self) -> Option<Instant> {
+        self.timeout
+    }
+}
+
+impl<T: Terminator + ?Sized> Terminator for Box<T> {
+    fn kill(&self) -> bool {
+        (**self).kill()
+    }
+
+    fn new_timeout(&mut self, timeout: Duration) {
+        (**self).new_timeout(timeout)
+    }
+
+    fn timeout_at(&self) -> Option<Instant> {
+        (**self).timeout_at()
+    }
+}
+
+/// Terminates as soon as any of its terminators does (never if it is empty).
+/// New timeouts are forwarded to all of them.
+pub struct AnyOf(pub Vec<Box<dyn Terminator + Send>>);
+
+/// Terminates once all of its terminators do (never if it is empty).
+/// New timeouts are forwarded to all of them.
+pub struct AllOf(pub Vec<Box<dyn Terminator + Send>>);
+
+impl Terminator for AnyOf {
+    fn kill(&self) -> bool {
+        self.0.iter().any(|t| t.kill())
+    }
+
+    fn new_timeout(&mut self, timeout: Duration) {
+        self.0.iter_mut().for_each(|t| t.new_timeout(timeout));
+    }
+
+    fn timeout_at(&self) -> Option<Instant> {
+        self.0.iter().filter_map(|t| t.timeout_at()).min()
+    }
+}
+
+impl Terminator for AllOf {
+    fn kill(&self) -> bool {
+        !self.0.is_empty() && self.0.iter().all(|t| t.kill())
+    }
+
+    fn new_timeout(&mut self, timeout: Duration) {
+        self.0.iter_mut().for_each(|t| t.new_timeout(timeout));
+    }
+
+    fn timeout_at(&self) -> Option<Instant> {
+        self.0.iter().filter_map(|t| t.timeout_at()).max()
+    }
+}
+
+/// Terminates at a fixed (wall clock) deadline, regardless of the timeouts of the individual phases
+#[derive(Debug, Clone)]
+pub struct DeadlineTerminator {
+    pub deadline: Instant,
+    reported: Arc<AtomicBool>,
+}
+
+impl DeadlineTerminator {
+    /// Terminates once `duration` has passed from now
+    pub fn after(duration: Duration) -> Self {
+        Self { deadline: Instant::now() + duration, reported: Arc::new(AtomicBool::new(false)) }
+    }
+}
+
+impl Terminator for DeadlineTerminator {
+    fn kill(&self) -> bool {
+        let kill = Instant::now() > self.deadline;
+        if kill { log_once(&self.reported, || "wall clock deadline reached".to_string()) }
+        kill
+    }
+
+    fn new_timeout(&mut self, _timeout: Duration) {}
+
+    fn timeout_at(&self) -> Option<Instant> {
+        Some(self.deadline)
+    }
+}
+
+/// Terminates as soon as a file exists at `path`, e.g. created with `touch` by a user or job scheduler.
+/// The file system is polled at most once every [`STOP_FILE_POLL_INTERVAL`].
+#[derive(Debug, Clone)]
+pub struct StopFileTerminator {
+    pub path: PathBuf,
+    created: Instant,
+    /// Milliseconds since creation of the last poll
+    last_poll_ms: Arc<AtomicU64>,
+    found: Arc<AtomicBool>,
+    reported: Arc<AtomicBool>,
+}
+
+impl StopFileTerminator {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            created: Instant::now(),
+            last_poll_ms: Arc::new(AtomicU64::new(0)),
+            found: Arc::new(AtomicBool::new(false)),
+            reported: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+impl Terminator for StopFileTerminator {
+    fn kill(&self) -> bool {
+        if !self.found.load(Ordering::Relaxed) {
+            let now_ms = self.created.elapsed().as_millis() as u64;
+            let last_poll_ms = self.last_poll_ms.load(Ordering::Relaxed);
+            if now_ms >= last_poll_ms + STOP_FILE_POLL_INTERVAL.as_millis() as u64 {
+                self.last_poll_ms.store(now_ms, Ordering::Relaxed);
+                self.found.store(self.path.exists(), Ordering::Relaxed);
+            }
+        }
+        let kill = self.found.load(Ordering::Relaxed);
+        if kill { log_once(&self.reported, || format!("stop file {} found", self.path.display())) }
+        kill
+    }
+
+    fn new_timeout(&mut self, _timeout: Duration) {}
+
+    fn timeout_at(&self) -> Option<Instant> {
+        None
+    }
+}
+
+/// Terminates once the best feasible solution tracked by `progress` is at most `target_width` wide
+#[derive(Debug, Clone)]
+pub struct TargetWidthTerminator {
+    pub target_width: f32,
+    pub progress: Progress,
+    reported: Arc<AtomicBool>,
+}
+
+impl TargetWidthTerminator {
+    pub fn new(target_width: f32, progress: Progress) -> Self {
+        Self { target_width, progress, reported: Arc::new(AtomicBool::new(false)) }
+    }
+}
+
+impl Terminator for TargetWidthTerminator {
+    fn kill(&self) -> bool {
+        let kill = self.progress.best_width() <= self.target_width;
+        if kill { log_once(&self.reported, || format!("target width reached ({:.3} <= {:.3})", self.progress.best_width(), self.target_width)) }
+        kill
+    }
+
+    fn new_timeout(&mut self, _timeout: Duration) {}
+
+    fn timeout_at(&self) -> Option<Instant> {
+        None
+    }
+}
+
+/// Terminates once the best feasible solution tracked by `progress` has a density of at least `target_density`
+#[derive(Debug, Clone)]
+pub struct TargetDensityTerminator {
+    pub target_density: f32,
+    pub progress: Progress,
+    reported: Arc<AtomicBool>,
+}
+
+impl TargetDensityTerminator {
+    pub fn new(target_density: f32, progress: Progress) -> Self {
+        Self { target_density, progress, reported: Arc::new(AtomicBool::new(false)) }
+    }
+}
+
+impl Terminator for TargetDensityTerminator {
+    fn kill(&self) -> bool {
+        let kill = self.progress.best_density() >= self.target_density;
+        if kill { log_once(&self.reported, || format!("target density reached ({:.3}% >= {:.3}%)", self.progress.best_density() * 100.0, self.target_density * 100.0)) }
+        kill
+    }
+
+    fn new_timeout(&mut self, _timeout: Duration) {}
+
+    fn timeout_at(&self) -> Option<Instant> {
+        None
+    }
+}
+
+/// Terminates when the solution tracked by `progress` has not improved for `max_stall` within the current phase.
+/// Every new timeout (i.e. every new phase) gives the optimization at least `max_stall` to improve again.
+#[derive(Debug, Clone)]
+pub struct StallTerminator {
+    pub max_stall: Duration,
+    pub progress: Progress,
+    phase_start: Instant,
+}
+
+impl StallTerminator {
+    pub fn new(max_stall: Duration, progress: Progress) -> Self {
+        Self { max_stall, progress, phase_start: Instant::now() }
+    }
+
+    /// Whether the solution has not improved for longer than `max_stall` within the current phase at the given instant
+    pub fn stalled_at(&self, now: Instant) -> bool {
+        let since = match self.progress.last_improvement() {
+            Some(last_improvement) if last_improvement > self.phase_start => last_improvement,
+            _ => self.phase_start,
+        };
+        now.saturating_duration_since(since) > self.max_stall
+    }
+}
+
+impl Terminator for StallTerminator {
+    fn kill(&self) -> bool {
+        self.stalled_at(Instant::now())
+    }
+
+    fn new_timeout(&mut self, _timeout: Duration) {
+        self.phase_start = Instant::now();
+    }
+
+    fn timeout_at(&self) -> Option<Instant> {
+        None
+    }
+}
+
+/// Optional stop criteria on top of the time limits. Stops as soon as any of the criteria is met.
+#[derive(Debug, Clone, Default)]
+pub struct StopCriteria {
+    /// Stop once a solution at most this wide is found
+    pub target_width: Option<f32>,
+    /// Stop once a solution with at least this density (in [0, 1]) is found
+    pub target_density: Option<f32>,
+    /// Stop a phase if the solution has not improved for this long
+    pub max_stall: Option<Duration>,
+    /// Stop once this file exists
+    pub stop_file: Option<PathBuf>,
+    /// Stop after this much wall clock time, regardless of the time limits of the phases
+    pub max_wall_time: Option<Duration>,
+}
+
+impl StopCriteria {
+    /// Combines `base` with terminators for all criteria.
+    /// The returned [`Progress`] (if any criterion requires it) has to be added to the solution listener of the run.
+    pub fn build(&self, base: impl Terminator + Send + 'static) -> (AnyOf, Option<Progress>) {
+        let needs_progress = self.target_width.is_some() || self.target_density.is_some() || self.max_stall.is_some();
+        let progress = needs_progress.then(Progress::new);
+
+        let mut terminators: Vec<Box<dyn Terminator + Send>> = vec![Box::new(base)];
+        if let (Some(target_width), Some(progress)) = (self.target_width, &progress) {
+            terminators.push(Box::new(TargetWidthTerminator::new(target_width, progress.clone())));
+        }
+        if let (Some(target_density), Some(progress)) = (self.target_density, &progress) {
+            terminators.push(Box::new(TargetDensityTerminator::new(target_density, progress.clone())));
+        }
+        if let (Some(max_stall), Some(progress)) = (self.max_stall, &progress) {
+            terminators.push(Box::new(StallTerminator::new(max_stall, progress.clone())));
+        }
+        if let Some(stop_file) = &self.stop_file {
+            terminators.push(Box::new(StopFileTerminator::new(stop_file.clone())));
+        }
+        if let Some(max_wall_time) = self.max_wall_time {
+            terminators.push(Box::new(DeadlineTerminator::after(max_wall_time)));
+        }
+        (AnyOf(terminators), progress)
+    }
+}
+
+fn log_once(reported: &AtomicBool, msg: impl FnOnce() -> String) {
+    if !reported.swap(true, Ordering::Relaxed) {
+        info!("[TERM] {}, stopping", msg());
+    }
+}
@@ -0,0 +1,338 @@
+use std::fmt::{Display, Formatter};
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use jagua_rs::entities::Instance;
+use jagua_rs::geometry::geo_enums::RotationRange;
+use jagua_rs::geometry::{normalize_rotation, DTransformation};
+use jagua_rs::probs::spp::entities::{SPInstance, SPPlacement, SPProblem};
+use jagua_rs::probs::spp::io::ext_repr::{ExtSPInstance, ExtSPSolution};
+use serde::Serialize;
+use crate::EPOCH;
+use crate::util::dxf::{self, Contour};
+use crate::util::io::ExtContainer;
+
+/// Maximum difference between two rotations (in radians) to consider them equal
+const ROTATION_TOLERANCE: f32 = 1e-4;
+
+/// Configuration of [`verify_solution`]
+#[derive(Debug, Clone, Copy)]
+pub struct VerifyConfig {
+    /// Absolute tolerance of the geometric checks (containment, overlap, separation and fixed placements)
+    pub tolerance: f64,
+    /// Minimum distance required between every pair of items
+    pub min_item_separation: Option<f64>,
+    /// Only require that no item is placed more often than its demand (e.g. for knapsack solutions)
+    pub allow_partial: bool,
+}
+
+/// A violated constraint of a solution. `placement` is the index of the item in the placements of the solution.
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Violation {
+    UnknownItem { placement: usize, item_id: usize },
+    OutsideContainer { placement: usize, item_id: usize, distance: f64 },
+    OutsideAllowedArea { item_id: usize, distance: f64 },
+    Overlap { placements: (usize, usize), item_ids: (usize, usize), depth: f64 },
+    Separation { placements: (usize, usize), item_ids: (usize, usize), distance: f64, required: f64 },
+    Demand { item_id: usize, placed: usize, demand: usize },
+    FixedPlacementChanged { item_id: usize },
+    DisallowedRotation { item_id: usize, rotation: f32 },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::UnknownItem { placement, item_id } =>
+                write!(f, "placement #{placement}: item {item_id} does not exist in the instance"),
+            Violation::OutsideContainer { placement, item_id, distance } =>
+                write!(f, "placement #{placement}: item {item_id} lies {distance:.6} outside the container"),
+            Violation::OutsideAllowedArea { item_id, distance } =>
+                write!(f, "item {item_id} lies {distance:.6} outside its allowed area"),
+            Violation::Overlap { placements: (p1, p2), item_ids: (id1, id2), depth } =>
+                write!(f, "placements #{p1} and #{p2}: items {id1} and {id2} overlap (depth {depth:.6})"),
+            Violation::Separation { placements: (p1, p2), item_ids: (id1, id2), distance, required } =>
+                write!(f, "placements #{p1} and #{p2}: items {id1} and {id2} are {distance:.6} apart, {required:.6} required"),
+            Violation::Demand { item_id, placed, demand } =>
+                write!(f, "item {item_id} is placed {placed} times, demand is {demand}"),
+            Violation::FixedPlacementChanged { item_id } =>
+                write!(f, "item {item_id} is not placed at its fixed placement"),
+            Violation::DisallowedRotation { item_id, rotation } =>
+                write!(f, "item {item_id} is placed at a disallowed rotation of {:.3}°", rotation.to_degrees()),
+        }
+    }
+}
+
+/// Verifies a solution with the exact (original, not simplified) shapes of the instance, independently of the
+/// collision detection used during optimization. Returns all violated constraints, an empty list if the solution is feasible.
+/// `instance` must be imported from `ext_instance`.
+/// Containment is checked against `container` if provided, otherwise against the strip width of the solution by the strip height of the instance.
+pub fn verify_solution(ext_instance: &ExtSPInstance, instance: &SPInstance, ext_solution: &ExtSPSolution, container: Option<&ExtContainer>, config: &VerifyConfig) -> Result<Vec<Violation>> {
+    let tol = config.tolerance;
+    let solution = jagua_rs::probs::spp::io::import_solution(instance, ext_solution);
+    let (width, height) = match container {
+        Some(container) => (container.width as f64, container.height as f64),
+        None => (solution.strip_width() as f64, solution.strip.fixed_height as f64),
+    };
+    let mut violations = vec![];
+
+    // Original contours of every placed item, in the coordinates of the container
+    let mut placed = vec![];
+    for (i, ext_pi) in ext_solution.layout.placed_items.iter().enumerate() {
+        let item_id = ext_pi.item_id as usize;
+        let Some(ext_item) = ext_instance.items.iter().find(|item| item.base.id == ext_pi.item_id) else {
+            violations.push(Violation::UnknownItem { placement: i, item_id });
+            continue;
+        };
+        let (rotation, translation) = (ext_pi.transformation.rotation, ext_pi.transformation.translation);
+        let contours = original_contours(&ext_item.base.shape, item_id)?;
+        placed.push(PlacedShape::new(i, item_id, contours, rotation, translation));
+    }
+
+    violations.extend(containment_violations(&placed, width, height, tol));
+    violations.extend(overlap_violations(&placed, config.min_item_separation.unwrap_or(0.0), tol));
+
+    for ext_item in &ext_instance.items {
+        let item_id = ext_item.base.id as usize;
+        let placed = ext_solution.layout.placed_items.iter().filter(|pi| pi.item_id == ext_item.base.id).count();
+        let demand = ext_item.demand as usize;
+        if placed > demand || (placed < demand && !config.allow_partial) {
+            violations.push(Violation::Demand { item_id, placed, demand });
+        }
+    }
+
+    let placed_items = solution.layout_snapshot.placed_items.values().collect_vec();
+    for item in instance.items() {
+        if let Some(fixed) = item.fixed_placement {
+            let unchanged = placed_items.iter()
+                .any(|pi| pi.item_id == item.id && same_placement(&pi.d_transf, &fixed, tol as f32));
+            if !unchanged {
+                violations.push(Violation::FixedPlacementChanged { item_id: item.id });
+            }
+        }
+    }
+    for pi in &placed_items {
+        let rotation = pi.d_transf.rotation();
+        if !rotation_allowed(&instance.item(pi.item_id).allowed_rotation, rotation) {
+            violations.push(Violation::DisallowedRotation { item_id: pi.item_id, rotation });
+        }
+    }
+
+    Ok(violations)
+}
+
+/// Checks the fixed placements of an instance before optimization: fixed items may not overlap each other, must lie
+/// inside a container of at most `max_container_dims` (a dimension is unbounded if `None`, e.g. the height in square mode)
+/// and inside their allowed area, and must be at an allowed rotation.
+/// `placement` of the violations is the index of the item among the fixed items.
+pub fn verify_fixed_placements(ext_instance: &ExtSPInstance, instance: &SPInstance, max_container_dims: (Option<f64>, Option<f64>), tolerance: f64) -> Result<Vec<Violation>> {
+    let mut violations = vec![];
+    let mut placed = vec![];
+    for (i, (item_id, rotation, translation)) in exported_fixed_placements(instance).into_iter().enumerate() {
+        let item = instance.item(item_id);
+        let ext_item = ext_instance.items.iter().find(|ext_item| ext_item.base.id as usize == item_id)
+            .with_context(|| format!("item {item_id} does not exist in the instance"))?;
+        let contours = original_contours(&ext_item.base.shape, item_id)?;
+        let ps = PlacedShape::new(i, item_id, contours, rotation, translation);
+
+        if let Some(area) = item.allowed_area {
+            let (x_min, y_min, x_max, y_max) = ps.bbox;
+            let distance = [area.x_min as f64 - x_min, x_max - area.x_max as f64, area.y_min as f64 - y_min, y_max - area.y_max as f64]
+                .into_iter()
+                .fold(0.0, f64::max);
+            if distance > tolerance {
+                violations.push(Violation::OutsideAllowedArea { item_id, distance });
+            }
+        }
+        let fixed = item.fixed_placement.unwrap();
+        if !rotation_allowed(&item.allowed_rotation, fixed.rotation()) {
+            violations.push(Violation::DisallowedRotation { item_id, rotation: fixed.rotation() });
+        }
+        placed.push(ps);
+    }
+    let (width, height) = (max_container_dims.0.unwrap_or(f64::INFINITY), max_container_dims.1.unwrap_or(f64::INFINITY));
+    violations.extend(containment_violations(&placed, width, height, tolerance));
+    violations.extend(overlap_violations(&placed, 0.0, tolerance));
+    Ok(violations)
+}
+
+/// The fixed placements of an instance as exported transformations `(item_id, rotation, translation)`, which apply to the
+/// original shapes of the items (the internal placements apply to the centered, simplified shapes)
+fn exported_fixed_placements(instance: &SPInstance) -> Vec<(usize, f32, (f32, f32))> {
+    let fixed = instance.items()
+        .filter_map(|item| item.fixed_placement.map(|d_transf| (item, d_transf)))
+        .collect_vec();
+    let mut prob = SPProblem::new(instance.clone());
+    // the strip has to hold every fixed item, wherever it is placed
+    let width = fixed.iter()
+        .map(|(item, d_transf)| d_transf.translation().0 + item.shape_cd.diameter)
+        .fold(prob.strip_width(), f32::max);
+    prob.change_strip_width(width);
+    for (item, d_transf) in fixed {
+        prob.place_item(SPPlacement { item_id: item.id, d_transf });
+    }
+    jagua_rs::probs::spp::io::export(instance, &prob.save(), *EPOCH).layout.placed_items.iter()
+        .map(|pi| (pi.item_id as usize, pi.transformation.rotation, pi.transformation.translation))
+        .collect()
+}
+
+/// Fixed placements of an instance which cannot be part of any feasible solution, see [`verify_fixed_placements`]
+#[derive(Serialize, Debug, Clone)]
+pub struct InvalidFixedPlacements {
+    pub violations: Vec<Violation>,
+}
+
+impl Display for InvalidFixedPlacements {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} invalid fixed placements: {}", self.violations.len(), self.violations.iter().join("; "))
+    }
+}
+
+impl std::error::Error for InvalidFixedPlacements {}
+
+fn containment_violations(placed: &[PlacedShape], width: f64, height: f64, tol: f64) -> Vec<Violation> {
+    placed.iter()
+        .filter_map(|ps| {
+            let distance = ps.contours[0].iter()
+                .map(|&(x, y)| [-x, x - width, -y, y - height].into_iter().fold(0.0, f64::max))
+                .fold(0.0, f64::max);
+            (distance > tol).then_some(Violation::OutsideContainer { placement: ps.placement, item_id: ps.item_id, distance })
+        })
+        .collect()
+}
+
+/// Overlapping pairs of shapes, and pairs closer than `sep` (if positive)
+fn overlap_violations(placed: &[PlacedShape], sep: f64, tol: f64) -> Vec<Violation> {
+    let mut violations = vec![];
+    for (a, b) in placed.iter().tuple_combinations() {
+        if !a.bbox_within(b, sep + tol) {
+            continue;
+        }
+        let pair = ((a.placement, b.placement), (a.item_id, b.item_id));
+        let depth = f64::max(a.penetration(b), b.penetration(a));
+        if depth > tol {
+            violations.push(Violation::Overlap { placements: pair.0, item_ids: pair.1, depth });
+        } else if sep > 0.0 {
+            let distance = f64::min(a.distance(b), b.distance(a));
+            if distance < sep - tol {
+                violations.push(Violation::Separation { placements: pair.0, item_ids: pair.1, distance, required: sep });
+            }
+        }
+    }
+    violations
+}
+
+/// Outer contour and holes of the original shape of an item
+fn original_contours(shape: &impl Serialize, item_id: usize) -> Result<Vec<Contour>> {
+    dxf::ext_shape_contours(&serde_json::to_value(shape)?).with_context(|| format!("invalid shape of item {item_id}"))
+}
+
+fn rotation_allowed(allowed_rotation: &RotationRange, rotation: f32) -> bool {
+    match allowed_rotation {
+        RotationRange::None => same_rotation(rotation, 0.0),
+        RotationRange::Discrete(rotations) => rotations.iter().any(|&r| same_rotation(rotation, r)),
+        RotationRange::Continuous => true,
+    }
+}
+
+fn same_rotation(a: f32, b: f32) -> bool {
+    normalize_rotation(a - b).abs() <= ROTATION_TOLERANCE
+}
+
+/// Whether two placements have the same rotation and the same translation, within `tol`
+pub fn same_placement(a: &DTransformation, b: &DTransformation, tol: f32) -> bool {
+    let ((ax, ay), (bx, by)) = (a.translation(), b.translation());
+    same_rotation(a.rotation(), b.rotation()) && (ax - bx).abs() <= tol && (ay - by).abs() <= tol
+}
+
+/// Original shape of a placed item: its outer contour followed by its holes
+struct PlacedShape {
+    placement: usize,
+    item_id: usize,
+    contours: Vec<Contour>,
+    /// (x_min, y_min, x_max, y_max)
+    bbox: (f64, f64, f64, f64),
+}
+
+impl PlacedShape {
+    /// The contours of an item, rotated (in radians) and translated
+    fn new(placement: usize, item_id: usize, contours: Vec<Contour>, rotation: f32, translation: (f32, f32)) -> Self {
+        let (sin, cos) = (rotation as f64).sin_cos();
+        let (tx, ty) = (translation.0 as f64, translation.1 as f64);
+        let contours = contours.into_iter()
+            .map(|c| c.into_iter().map(|(x, y)| (x * cos - y * sin + tx, x * sin + y * cos + ty)).collect_vec())
+            .collect_vec();
+        let (x_min, x_max) = contours[0].iter().map(|p| p.0).minmax().into_option().unwrap();
+        let (y_min, y_max) = contours[0].iter().map(|p| p.1).minmax().into_option().unwrap();
+        PlacedShape { placement, item_id, contours, bbox: (x_min, y_min, x_max, y_max) }
+    }
+
+    fn edges(&self) -> impl Iterator<Item = ((f64, f64), (f64, f64))> + '_ {
+        self.contours.iter().flat_map(|c| c.iter().copied().circular_tuple_windows())
+    }
+
+    fn bbox_within(&self, other: &PlacedShape, margin: f64) -> bool {
+        let (a, b) = (self.bbox, other.bbox);
+        a.0 <= b.2 + margin && b.0 <= a.2 + margin && a.1 <= b.3 + margin && b.1 <= a.3 + margin
+    }
+
+    /// Whether the point lies inside the outer contour and outside all holes
+    fn contains(&self, p: (f64, f64)) -> bool {
+        dxf::point_in_polygon(p, &self.contours[0]) && !self.contours[1..].iter().any(|hole| dxf::point_in_polygon(p, hole))
+    }
+
+    fn boundary_distance(&self, p: (f64, f64)) -> f64 {
+        self.edges().map(|(a, b)| dxf::dist_to_segment(p, a, b)).fold(f64::INFINITY, f64::min)
+    }
+
+    /// How deep the boundary (or an interior point) of `self` lies inside `other`, zero if they do not overlap
+    fn penetration(&self, other: &PlacedShape) -> f64 {
+        let depth = |p: (f64, f64)| match other.contains(p) {
+            true => other.boundary_distance(p),
+            false => 0.0,
+        };
+        let mut max_depth = self.interior_point().map_or(0.0, depth);
+        for (p, q) in self.edges() {
+            // Split the edge where it crosses the boundary of the other shape, every part lies either inside or outside
+            let mut ts = other.edges().filter_map(|(r, s)| intersection(p, q, r, s)).collect_vec();
+            ts.extend([0.0, 1.0]);
+            ts.sort_by(f64::total_cmp);
+            for (&t0, &t1) in ts.iter().tuple_windows() {
+                let t = (t0 + t1) / 2.0;
+                max_depth = f64::max(max_depth, depth((p.0 + t * (q.0 - p.0), p.1 + t * (q.1 - p.1))));
+            }
+        }
+        max_depth
+    }
+
+    /// Smallest distance from a vertex of `self` to the boundary of `other`.
+    /// For shapes which do not overlap, the minimum of both directions is the distance between them.
+    fn distance(&self, other: &PlacedShape) -> f64 {
+        self.contours[0].iter().map(|&p| other.boundary_distance(p)).fold(f64::INFINITY, f64::min)
+    }
+
+    /// A point inside the shape: the middle of the widest interior span on the horizontal line through the center of its bounding box
+    fn interior_point(&self) -> Option<(f64, f64)> {
+        let y = (self.bbox.1 + self.bbox.3) / 2.0;
+        let xs = self.edges()
+            .filter(|(a, b)| (a.1 > y) != (b.1 > y))
+            .map(|(a, b)| a.0 + (y - a.1) * (b.0 - a.0) / (b.1 - a.1))
+            .sorted_by(f64::total_cmp)
+            .collect_vec();
+        xs.chunks_exact(2)
+            .max_by(|a, b| (a[1] - a[0]).total_cmp(&(b[1] - b[0])))
+            .map(|span| ((span[0] + span[1]) / 2.0, y))
+    }
+}
+
+/// Parameter along `p`-`q` where it intersects segment `r`-`s`, if they intersect
+fn intersection(p: (f64, f64), q: (f64, f64), r: (f64, f64), s: (f64, f64)) -> Option<f64> {
+    let (d1, d2) = ((q.0 - p.0, q.1 - p.1), (s.0 - r.0, s.1 - r.1));
+    let denom = d1.0 * d2.1 - d1.1 * d2.0;
+    if denom == 0.0 {
+        return None;
+    }
+    let (dx, dy) = (r.0 - p.0, r.1 - p.1);
+    let t = (dx * d2.1 - dy * d2.0) / denom;
+    let u = (dx * d1.1 - dy * d1.0) / denom;
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then_some(t)
+}
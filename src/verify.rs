@@ -0,0 +1,72 @@
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+
+use anyhow::{Context, Result};
+use clap::Parser as Clap;
+use jagua_rs::io::import::Importer;
+use serde_json::json;
+use sparrow::config::DEFAULT_SPARROW_CONFIG;
+use sparrow::consts::DEFAULT_VERIFY_TOLERANCE_RATIO;
+use sparrow::util::io;
+use sparrow::util::io::{ExtSPOutput, VerifyCli};
+use sparrow::util::verify::{verify_solution, VerifyConfig};
+
+/// Exit code if the solution is infeasible, errors (e.g. an unreadable file) exit with 2
+const EXIT_INFEASIBLE: u8 = 1;
+const EXIT_ERROR: u8 = 2;
+
+fn main() -> ExitCode {
+    let args = VerifyCli::parse();
+    match verify(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(EXIT_INFEASIBLE),
+        Err(e) => {
+            eprintln!("[VERIFY] error: {e:#}");
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+
+/// Returns whether the solution is feasible
+fn verify(args: &VerifyCli) -> Result<bool> {
+    let output_str = fs::read_to_string(&args.solution).context("could not read solution file")?;
+    let output = serde_json::from_str::<ExtSPOutput>(&output_str)
+        .context("could not parse solution file (multi-bin outputs are not supported)")?;
+
+    // Import with the config the solution was produced with, if it is embedded
+    let config = output.metadata.as_ref().map_or(DEFAULT_SPARROW_CONFIG, |m| m.config);
+    let importer = Importer::new(config.cde_config, config.poly_simpl_tolerance, config.min_item_separation, config.narrow_concavity_cutoff_ratio);
+    let instance = jagua_rs::probs::spp::io::import_instance(&importer, &output.instance)?;
+
+    // Outside strip mode, the height of the container is not the strip height of the instance
+    let height = output.container.map_or(output.instance.strip_height, |c| c.height);
+    let verify_config = VerifyConfig {
+        tolerance: args.tolerance.unwrap_or(height as f64 * DEFAULT_VERIFY_TOLERANCE_RATIO),
+        min_item_separation: args.min_separation.or(config.min_item_separation.map(|s| s as f64)),
+        allow_partial: args.partial,
+    };
+    println!(
+        "[VERIFY] verifying {} ({} placed items, tolerance: {:e})",
+        output.instance.name, output.solution.layout.placed_items.len(), verify_config.tolerance
+    );
+
+    let violations = verify_solution(&output.instance, &instance, &output.solution, output.container.as_ref(), &verify_config)?;
+    for violation in &violations {
+        println!("[VERIFY] violation: {violation}");
+    }
+    match violations.is_empty() {
+        true => println!("[VERIFY] solution is feasible"),
+        false => println!("[VERIFY] solution is infeasible, {} violations", violations.len()),
+    }
+
+    if let Some(report_path) = &args.report {
+        let report = json!({
+            "feasible": violations.is_empty(),
+            "tolerance": verify_config.tolerance,
+            "violations": violations,
+        });
+        io::write_json(&report, Path::new(report_path), log::Level::Info)?;
+    }
+    Ok(violations.is_empty())
+}
@@ -1,38 +1,106 @@
 #[cfg(test)]
 mod integration_tests {
     use anyhow::Result;
-    use jagua_rs::io::import::Importer;
+    use itertools::Itertools;
+    use serde_json::json;
+    use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+    use jagua_rs::probs::spp::io::ext_repr::ExtSPInstance;
     use rand::SeedableRng;
     use rand_xoshiro::Xoshiro256PlusPlus;
-    use sparrow::config::DEFAULT_SPARROW_CONFIG;
-    use sparrow::consts::LBF_SAMPLE_CONFIG;
+    use jagua_rs::entities::Instance;
+    use sparrow::config::{BinPackingConfig, CompressionConfig, ContainerMode, DxfImportConfig, DxfUnits, ExplorationConfig, KnapsackConfig, ShrinkDecayStrategy, SparrowConfig, DEFAULT_SPARROW_CONFIG, DEFAULT_TUNING_CONFIG};
+    use sparrow::optimizer::handle::SparrowHandle;
+    use sparrow::optimizer::knapsack::{optimize_knapsack, ItemValue};
+    use sparrow::optimizer::multi_bin::optimize_bins;
+    use sparrow::optimizer::{optimize, resume, ResumeState};
+    use sparrow::util::checkpoint::{read_checkpoint, Checkpointer, ExtCheckpoint};
+    use sparrow::consts::{DEFAULT_VERIFY_TOLERANCE_RATIO, JSONL_PROGRESS_MIN_INTERVAL};
     use sparrow::optimizer::compress::compression_phase;
     use sparrow::optimizer::explore::exploration_phase;
     use sparrow::optimizer::lbf::LBFBuilder;
     use sparrow::optimizer::separator::Separator;
-    use sparrow::util::io;
+    use sparrow::util::{assertions, dxf, io, placement_table};
+    use sparrow::util::io::{ExtContainer, ExtItemValue, SvgMode};
+    use sparrow::util::svg_exporter::SvgExporter;
+    use sparrow::EPOCH;
+    use sparrow::error::SparrowError;
+    use sparrow::util::ctrlc_terminator::CtrlCTerminator;
     use sparrow::util::terminator::BasicTerminator;
-    use sparrow::util::terminator::Terminator;
+    use sparrow::util::terminator::{AllOf, AnyOf, CancellationTerminator, DeadlineTerminator, StallTerminator, StopCriteria, Terminator};
+    use sparrow::util::progress::Progress;
+    use sparrow::util::verify::{verify_solution, InvalidFixedPlacements, VerifyConfig, Violation};
+    use sparrow::util::jsonl_listener::JsonlProgressListener;
+    use sparrow::util::listener::{Phase, PhaseState, ReportType};
+    use sparrow::util::lower_bound::LowerBound;
+    use sparrow::util::run_recorder::RunRecorder;
+    use sparrow::util::live_server::LiveServer;
+    use sparrow::util::listener::SolutionListener;
+    use sparrow::util::inspect::inspect_instance;
     use std::path::Path;
-    use std::time::Duration;
+    use std::time::{Duration, Instant};
     use test_case::test_case;
     use sparrow::util::listener::DummySolListener;
 
     const EXPLORE_TIMEOUT: Duration = Duration::from_secs(10);
     const COMPRESS_TIMEOUT: Duration = Duration::from_secs(10);
+    /// Time limit for runs which are stopped by other means
+    const NO_TIME_LIMIT: Duration = Duration::from_secs(24 * 60 * 60);
     const INSTANCE_BASE_PATH: &str = "data/input";
     const RNG_SEED: Option<usize> = Some(0); // fix seed for reproducibility
 
-    #[test_case("swim.json"; "swim")]
-    #[test_case("shirts.json"; "shirts")]
-    #[test_case("trousers.json"; "trousers")]
-    fn simulate_optimization(path: &str) -> Result<()> {
-        let config = DEFAULT_SPARROW_CONFIG;
-        let input_file_path = format!("{INSTANCE_BASE_PATH}/{path}");
-        let (json_instance, _) = io::read_spp_input(Path::new(&input_file_path))?;
+    /// Reads an instance from [`INSTANCE_BASE_PATH`] and imports it with `config`
+    fn load_instance(path: &str, config: &SparrowConfig) -> Result<(ExtSPInstance, SPInstance)> {
+        let (ext_instance, _) = io::read_spp_input_with(Path::new(&format!("{INSTANCE_BASE_PATH}/{path}")), config)?;
+        let instance = io::import_instance(&ext_instance, config)?;
+        Ok((ext_instance, instance))
+    }
+
+    /// The exploration and compression configs of `config`, with the given time limits
+    fn phase_configs(config: &SparrowConfig, expl_time_limit: Duration, cmpr_time_limit: Duration) -> (ExplorationConfig, CompressionConfig) {
+        let mut expl_config = config.expl_cfg;
+        expl_config.time_limit = expl_time_limit;
+        let mut cmpr_config = config.cmpr_cfg;
+        cmpr_config.time_limit = cmpr_time_limit;
+        (expl_config, cmpr_config)
+    }
+
+    fn seeded_rng() -> Xoshiro256PlusPlus {
+        Xoshiro256PlusPlus::seed_from_u64(RNG_SEED.unwrap() as u64)
+    }
+
+    /// Exported transformations of two placements of the same item in a feasible layout of `instance`
+    fn feasible_transformations(ext_instance: &ExtSPInstance, instance: &SPInstance) -> Result<(serde_json::Value, serde_json::Value)> {
+        let builder = LBFBuilder::new(instance.clone(), seeded_rng(), DEFAULT_TUNING_CONFIG).construct()?;
+        let ext_sol = jagua_rs::probs::spp::io::export(instance, &builder.prob.save(), *EPOCH);
+        let placements = &ext_sol.layout.placed_items;
+        let first_item = ext_instance.items[0].base.id;
+        let (a, b) = placements.iter().filter(|pi| pi.item_id == first_item).take(2).collect_tuple()
+            .expect("the first item should be placed at least twice");
+        Ok((serde_json::to_value(&a.transformation)?, serde_json::to_value(&b.transformation)?))
+    }
+
+    /// `ext_instance` with one copy of its first item fixed at `a` and an extra copy of it fixed at `b`
+    fn with_fixed_copies(ext_instance: &ExtSPInstance, a: &serde_json::Value, b: &serde_json::Value) -> Result<ExtSPInstance> {
+        let mut json = serde_json::to_value(ext_instance)?;
+        let items = json["items"].as_array_mut().unwrap();
+        let mut copy = items[0].clone();
+        copy["id"] = json!(items.len());
+        copy["demand"] = json!(1);
+        copy["fixed_placement"] = b.clone();
+        items[0]["fixed_placement"] = a.clone();
+        items.push(copy);
+        Ok(serde_json::from_value(json)?)
+    }
 
-        let importer = Importer::new(config.cde_config, config.poly_simpl_tolerance, config.min_item_separation, config.narrow_concavity_cutoff_ratio);
-        let instance = jagua_rs::probs::spp::io::import_instance(&importer, &json_instance)?;
+    #[test_case("swim.json", ContainerMode::Square; "swim")]
+    #[test_case("shirts.json", ContainerMode::Square; "shirts")]
+    #[test_case("trousers.json", ContainerMode::Square; "trousers")]
+    #[test_case("swim.json", ContainerMode::Strip; "swim_strip")]
+    #[test_case("shirts.json", ContainerMode::FixedAspectRatio(2.0); "shirts_aspect_ratio")]
+    #[test_case("trousers.json", ContainerMode::FreeRectangle; "trousers_free_rectangle")]
+    fn simulate_optimization(path: &str, container_mode: ContainerMode) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
 
         println!("[TEST] loaded instance: {}", json_instance.name);
 
@@ -52,14 +120,1139 @@ mod integration_tests {
         let mut sol_listener = DummySolListener;
         terminator.new_timeout(EXPLORE_TIMEOUT);
 
-        let builder = LBFBuilder::new(instance.clone(), rng, LBF_SAMPLE_CONFIG).construct();
-        let mut separator = Separator::new(builder.instance, builder.prob, builder.rng, config.expl_cfg.separator_config);
+        let builder = LBFBuilder::new(instance.clone(), rng, DEFAULT_TUNING_CONFIG).construct()?;
+        let mut separator = Separator::new(builder.instance, builder.prob, builder.rng, config.expl_cfg.separator_config, DEFAULT_TUNING_CONFIG, container_mode);
 
-        let sols = exploration_phase(&instance, &mut separator, &mut sol_listener, &terminator, &config.expl_cfg);
+        let sols = exploration_phase(&instance, &mut separator, &mut sol_listener, &terminator, &config.expl_cfg)?;
         let final_explore_sol = sols.last().expect("no solutions found during exploration");
 
         terminator.new_timeout(COMPRESS_TIMEOUT);
-        compression_phase(&instance, &mut separator, final_explore_sol, &mut sol_listener, &terminator, &config.cmpr_cfg);
+        compression_phase(&instance, &mut separator, final_explore_sol, &mut sol_listener, &terminator, &config.cmpr_cfg)?;
+        Ok(())
+    }
+
+    #[test_case("shirts.json"; "shirts")]
+    fn simulate_bin_packing(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+
+        // square bins, smaller than what is required for the entire instance
+        let bin_config = BinPackingConfig {
+            bin_width: json_instance.strip_height,
+            max_migration_rounds: 3,
+        };
+        let (expl_config, cmpr_config) = phase_configs(&config, EXPLORE_TIMEOUT, COMPRESS_TIMEOUT);
+
+        let rng = seeded_rng();
+        let mut terminator = BasicTerminator::new();
+        let bins = optimize_bins(instance.clone(), rng, &mut DummySolListener, &mut terminator, &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, &bin_config)?;
+
+        let n_placed = bins.iter().map(|b| b.layout_snapshot.placed_items.len()).sum::<usize>();
+        assert_eq!(n_placed, instance.total_item_qty(), "all items should be placed across the bins");
+
+        // every bin on its own must be feasible and respect the bin width
+        let verify_config = VerifyConfig {
+            tolerance: json_instance.strip_height as f64 * DEFAULT_VERIFY_TOLERANCE_RATIO,
+            min_item_separation: None,
+            allow_partial: true,
+        };
+        for bin in &bins {
+            assert!(bin.strip_width() <= bin_config.bin_width);
+            let ext_bin = jagua_rs::probs::spp::io::export(&instance, bin, *EPOCH);
+            let violations = verify_solution(&json_instance, &instance, &ext_bin, None, &verify_config)?;
+            assert!(violations.is_empty(), "{violations:?}");
+        }
+        Ok(())
+    }
+
+    #[test_case("shirts.json"; "shirts")]
+    fn item_does_not_fit_bin(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (_, instance) = load_instance(path, &config)?;
+
+        // bins far too narrow for any item, the run should fail instead of panicking
+        let bin_config = BinPackingConfig {
+            bin_width: 1e-3,
+            max_migration_rounds: 3,
+        };
+        let rng = seeded_rng();
+        let result = optimize_bins(instance.clone(), rng, &mut DummySolListener, &mut BasicTerminator::new(), &config.expl_cfg, &config.cmpr_cfg, &DEFAULT_TUNING_CONFIG, &bin_config);
+
+        assert!(matches!(result, Err(SparrowError::ItemDoesNotFit { .. })), "expected ItemDoesNotFit");
+        Ok(())
+    }
+
+    #[test_case("shirts.json"; "shirts")]
+    fn simulate_knapsack(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+
+        // square container, too small to hold all items
+        let knapsack_config = KnapsackConfig {
+            container_width: json_instance.strip_height,
+            insertion_distribution_stddev: 0.1,
+        };
+        let item_values = ItemValue::resolve(&instance, &io::read_item_values(Path::new(&format!("{INSTANCE_BASE_PATH}/{path}")), &config)?);
+        let (expl_config, cmpr_config) = phase_configs(&config, EXPLORE_TIMEOUT, COMPRESS_TIMEOUT);
+
+        let rng = seeded_rng();
+        let mut terminator = BasicTerminator::new();
+        let solution = optimize_knapsack(instance.clone(), rng, &mut DummySolListener, &mut terminator, &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, &knapsack_config, &item_values)?;
+
+        let n_placed = solution.layout_snapshot.placed_items.len();
+        assert!(n_placed > 0 && n_placed < instance.total_item_qty(), "only part of the items should fit in the container");
+        assert_eq!(solution.strip_width(), knapsack_config.container_width, "the container should not be resized");
+        Ok(())
+    }
+
+    #[test_case("shirts.json"; "shirts")]
+    fn knapsack_item_too_large(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+
+        // the widest item does not fit the container at any rotation, the others still have to be placed
+        let item_width = LowerBound::new(&json_instance, &instance, ContainerMode::Strip)?.item_width;
+        let knapsack_config = KnapsackConfig {
+            container_width: 0.99 * item_width,
+            insertion_distribution_stddev: 0.1,
+        };
+        let item_values = ItemValue::resolve(&instance, &[]);
+        let (expl_config, cmpr_config) = phase_configs(&config, Duration::from_secs(2), Duration::from_secs(1));
+
+        let solution = optimize_knapsack(instance.clone(), seeded_rng(), &mut DummySolListener, &mut BasicTerminator::new(), &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, &knapsack_config, &item_values)?;
+        assert!(!solution.layout_snapshot.placed_items.is_empty());
+        Ok(())
+    }
+
+    #[test_case("shirts.json"; "shirts")]
+    fn knapsack_item_values(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (_, instance) = load_instance(path, &config)?;
+
+        // values are matched on the item id, regardless of their order, and missing entries get the defaults
+        let ext_values = vec![
+            ExtItemValue { id: 2, value: Some(5.0), priority: 1 },
+            ExtItemValue { id: 0, value: None, priority: 3 },
+        ];
+        let item_values = ItemValue::resolve(&instance, &ext_values);
+        assert_eq!(item_values.len(), instance.items.len());
+        assert_eq!((item_values[2].value, item_values[2].priority), (5.0, 1));
+        assert_eq!((item_values[0].value, item_values[0].priority), (instance.item(0).shape_cd.area, 3));
+        assert_eq!((item_values[1].value, item_values[1].priority), (instance.item(1).shape_cd.area, 0));
+        Ok(())
+    }
+
+    #[test_case("swim.json"; "swim")]
+    fn simulate_incremental_renesting(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+
+        // nest the original instance
+        let rng = seeded_rng();
+        let prev_builder = LBFBuilder::new(instance.clone(), rng.clone(), DEFAULT_TUNING_CONFIG).construct()?;
+        let prev_ext_sol = jagua_rs::probs::spp::io::export(&instance, &prev_builder.prob.save(), *EPOCH);
+
+        // the same set of items arrives a second time
+        let merged_json_instance = io::merge_instances(&json_instance, &json_instance);
+        let merged_instance = io::import_instance(&merged_json_instance, &config)?;
+        let prev_sol = jagua_rs::probs::spp::io::import_solution(&merged_instance, &prev_ext_sol);
+        let builder = LBFBuilder::new(merged_instance.clone(), rng.clone(), DEFAULT_TUNING_CONFIG)
+            .restore_placements(&prev_sol, true);
+        let init_sol = builder.prob.save();
+
+        let (expl_config, cmpr_config) = phase_configs(&config, EXPLORE_TIMEOUT, COMPRESS_TIMEOUT);
+
+        let mut terminator = BasicTerminator::new();
+        let solution = optimize(merged_instance.clone(), rng, &mut DummySolListener, &mut terminator, &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip, Some(&init_sol))?;
+
+        assert_eq!(solution.layout_snapshot.placed_items.len(), merged_instance.total_item_qty(), "all items should be placed");
+        assert!(assertions::locked_items_unchanged(&init_sol.layout_snapshot, &solution.layout_snapshot), "previous placements should not move");
+        Ok(())
+    }
+
+    #[test_case("swim.json"; "swim")]
+    fn simulate_partial_warm_start(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (_, instance) = load_instance(path, &config)?;
+
+        // a complete layout, of which the last placements are dropped
+        let builder = LBFBuilder::new(instance.clone(), seeded_rng(), DEFAULT_TUNING_CONFIG).construct()?;
+        let mut ext_sol = jagua_rs::probs::spp::io::export(&instance, &builder.prob.save(), *EPOCH);
+        let n_dropped = ext_sol.layout.placed_items.len() / 3;
+        let dropped = ext_sol.layout.placed_items.split_off(ext_sol.layout.placed_items.len() - n_dropped);
+        let init_sol = jagua_rs::probs::spp::io::import_solution(&instance, &ext_sol);
+
+        let (expl_config, cmpr_config) = phase_configs(&config, Duration::from_secs(2), Duration::from_secs(1));
+        let mut recorder = RunRecorder::new();
+        let solution = optimize(instance.clone(), seeded_rng(), &mut recorder, &mut BasicTerminator::new(), &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip, Some(&init_sol))?;
+        assert_eq!(solution.layout_snapshot.placed_items.len(), instance.total_item_qty(), "the missing demand should be placed");
+
+        let report = recorder.metadata(0, config).warm_start.expect("the warm start should be reported");
+        assert_eq!(report.carried_over.len(), ext_sol.layout.placed_items.len());
+        let mut dropped_ids = dropped.iter().map(|pi| pi.item_id as usize).collect::<Vec<_>>();
+        dropped_ids.sort();
+        assert_eq!(report.newly_placed, dropped_ids);
+
+        // a warm start from a complete solution has nothing to place
+        let full_sol = builder.prob.save();
+        let mut recorder = RunRecorder::new();
+        optimize(instance.clone(), seeded_rng(), &mut recorder, &mut BasicTerminator::new(), &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip, Some(&full_sol))?;
+        let report = recorder.metadata(0, config).warm_start.unwrap();
+        assert_eq!((report.carried_over.len(), report.newly_placed.len()), (instance.total_item_qty(), 0));
+        Ok(())
+    }
+
+    #[test_case("swim.json"; "swim")]
+    fn warm_start_fixed_items(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+        let (a, b) = feasible_transformations(&json_instance, &instance)?;
+        let fixed = with_fixed_copies(&json_instance, &a, &b)?;
+        let fixed_instance = io::import_instance(&fixed, &config)?;
+        let copy_id = fixed.items.len() - 1;
+
+        // an initial solution in which the extra fixed copy is not at its fixed placement
+        let builder = LBFBuilder::new(fixed_instance.clone(), seeded_rng(), DEFAULT_TUNING_CONFIG).construct()?;
+        let mut ext_sol = jagua_rs::probs::spp::io::export(&fixed_instance, &builder.prob.save(), *EPOCH);
+        let copy = ext_sol.layout.placed_items.iter_mut().find(|pi| pi.item_id as usize == copy_id).unwrap();
+        copy.transformation.translation.0 += json_instance.strip_height;
+        let init_sol = jagua_rs::probs::spp::io::import_solution(&fixed_instance, &ext_sol);
+
+        let (expl_config, cmpr_config) = phase_configs(&config, Duration::from_secs(2), Duration::from_secs(1));
+        let mut recorder = RunRecorder::new();
+        let solution = optimize(fixed_instance.clone(), seeded_rng(), &mut recorder, &mut BasicTerminator::new(), &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip, Some(&init_sol))?;
+
+        // the fixed copy is moved to its fixed placement, which counts as newly placed
+        let report = recorder.metadata(0, config).warm_start.expect("the warm start should be reported");
+        assert_eq!(report.newly_placed, [copy_id]);
+        assert!(!report.carried_over.contains(&copy_id));
+        let ext_solution = jagua_rs::probs::spp::io::export(&fixed_instance, &solution, *EPOCH);
+        let placed_copy = ext_solution.layout.placed_items.iter().find(|pi| pi.item_id as usize == copy_id).unwrap();
+        let (x, y) = placed_copy.transformation.translation;
+        assert!((x as f64 - b["translation"][0].as_f64().unwrap()).abs() < 1e-3 && (y as f64 - b["translation"][1].as_f64().unwrap()).abs() < 1e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn config_file_overrides() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let config_path = dir.path().join("config.toml");
+        std::fs::write(&config_path, r#"
+            container_mode = "strip"
+
+            [expl_cfg]
+            time_limit = 1.5
+            separator_config = { n_workers = 2 }
+
+            [cmpr_cfg]
+            shrink_decay = { failure_based = 0.8 }
+
+            [tuning]
+            cd_step_fail = 0.4
+        "#)?;
+        let config = io::read_config(&config_path)?;
+
+        assert_eq!(config.container_mode, ContainerMode::Strip);
+        assert_eq!(config.expl_cfg.time_limit, Duration::from_secs_f32(1.5));
+        assert_eq!(config.expl_cfg.separator_config.n_workers, 2);
+        assert!(matches!(config.cmpr_cfg.shrink_decay, ShrinkDecayStrategy::FailureBased(r) if r == 0.8));
+        assert_eq!(config.tuning.cd_step_fail, 0.4);
+        // untouched parameters keep their defaults
+        assert_eq!(config.expl_cfg.separator_config.strike_limit, DEFAULT_SPARROW_CONFIG.expl_cfg.separator_config.strike_limit);
+
+        // invalid values and unknown parameters are rejected
+        std::fs::write(&config_path, "[tuning]\ncd_step_fail = 1.5\n")?;
+        assert!(io::read_config(&config_path).is_err());
+        std::fs::write(&config_path, "[expl_cfg]\nshrink_stp = 0.01\n")?;
+        assert!(io::read_config(&config_path).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn two_stage_ctrlc() {
+        let mut terminator = CtrlCTerminator::detached();
+        terminator.new_timeout(EXPLORE_TIMEOUT);
+        assert!(!terminator.kill());
+
+        // first Ctrl-C only ends the current phase
+        terminator.interrupt();
+        assert!(terminator.kill() && !terminator.aborted());
+        terminator.new_timeout(COMPRESS_TIMEOUT);
+        assert!(!terminator.kill());
+
+        // second Ctrl-C ends all phases
+        terminator.interrupt();
+        assert!(terminator.kill() && terminator.aborted());
+        terminator.new_timeout(COMPRESS_TIMEOUT);
+        assert!(terminator.kill());
+    }
+
+    #[test]
+    fn ctrlc_reaches_concurrent_runs() {
+        // two concurrent runs sharing the Ctrl-C state, the first one moves on to its next phase before the second notices
+        let mut first = CtrlCTerminator::detached();
+        let mut second = first.clone();
+        first.new_timeout(EXPLORE_TIMEOUT);
+        second.new_timeout(EXPLORE_TIMEOUT);
+
+        first.interrupt();
+        first.new_timeout(COMPRESS_TIMEOUT);
+        assert!(!first.kill());
+        assert!(second.kill(), "the Ctrl-C should not be lost for the second run");
+        second.new_timeout(COMPRESS_TIMEOUT);
+        assert!(!second.kill());
+    }
+
+    #[test]
+    fn batch_summary() -> Result<()> {
+        // two inputs with the same file name and one which cannot be parsed
+        let input_dir = tempfile::tempdir()?;
+        for dir in ["a", "b"] {
+            std::fs::create_dir(input_dir.path().join(dir))?;
+            std::fs::copy(format!("{INSTANCE_BASE_PATH}/swim.json"), input_dir.path().join(dir).join("swim.json"))?;
+        }
+        std::fs::write(input_dir.path().join("b").join("broken.json"), "{ not json")?;
+        let output_dir = tempfile::tempdir()?;
+
+        let status = std::process::Command::new(env!("CARGO_BIN_EXE_sparrow"))
+            .args(["batch", &format!("{}/*/*.json", input_dir.path().display())])
+            .args(["-t", "2", "-s", "0", "--svg", "none", "--output-dir", &output_dir.path().display().to_string()])
+            .status()?;
+        assert!(status.success(), "a failing input should not fail the batch");
+
+        let summary: Vec<serde_json::Value> = serde_json::from_str(&std::fs::read_to_string(output_dir.path().join("batch_summary.json"))?)?;
+        let file_name = |e: &serde_json::Value| Path::new(e["input"].as_str().unwrap()).file_name().unwrap().to_string_lossy().to_string();
+        let success = summary.iter().map(|e| (file_name(e), e["success"].as_bool().unwrap())).collect_vec();
+        assert_eq!(success, [("swim.json".to_string(), true), ("broken.json".to_string(), false), ("swim.json".to_string(), true)]);
+        assert!(summary[1]["error"].as_str().is_some_and(|e| !e.is_empty()));
+        assert!(summary[0]["strip_width"].as_f64().is_some() && summary[1]["strip_width"].is_null());
+
+        let csv = std::fs::read_to_string(output_dir.path().join("batch_summary.csv"))?;
+        let rows = csv.lines().collect_vec();
+        assert_eq!(rows.len(), 4);
+        assert!(rows[2].contains("broken.json") && rows[2].contains(",false,"));
+
+        // inputs with the same name do not share their output directory, no SVG is written with --svg none
+        for dir in ["swim_0", "swim_2"] {
+            assert!(output_dir.path().join(dir).join("final_swim.json").exists());
+            assert!(!output_dir.path().join(dir).join("final_swim.svg").exists());
+        }
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[test_case("swim.json"; "swim")]
+    fn live_server(path: &str) -> Result<()> {
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::net::TcpStream;
+
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (_, instance) = load_instance(path, &config)?;
+        let solution = LBFBuilder::new(instance.clone(), seeded_rng(), DEFAULT_TUNING_CONFIG).construct()?.prob.save();
+
+        let mut server = LiveServer::start(0)?;
+        let port = server.port;
+        let connect = |request: &str| -> Result<TcpStream> {
+            let mut stream = TcpStream::connect(("127.0.0.1", port))?;
+            stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+            write!(stream, "GET {request} HTTP/1.1\r\nHost: localhost\r\n\r\n")?;
+            Ok(stream)
+        };
+
+        // A client which never sends its request does not block the others
+        let _idle = TcpStream::connect(("127.0.0.1", port))?;
+        let mut page = String::new();
+        connect("/")?.read_to_string(&mut page)?;
+        assert!(page.starts_with("HTTP/1.1 200 OK") && page.contains("<html"));
+
+        // Intermediate solutions are not rendered without clients
+        server.report(ReportType::ExplImproving, &solution, &instance)?;
+
+        let mut events = BufReader::new(connect("/events")?);
+        let mut line = String::new();
+        while events.read_line(&mut line)? > 2 {
+            line.clear();
+        }
+        assert_eq!(server.n_clients(), 1);
+
+        // The first event the client receives is the next solution, nothing was replayed
+        server.report(ReportType::Final, &solution, &instance)?;
+        let mut event = String::new();
+        while !event.ends_with("\n\n") {
+            events.read_line(&mut event)?;
+        }
+        assert!(event.starts_with("event: solution") && event.contains("\"report\":\"final\""), "{event}");
+        Ok(())
+    }
+
+    /// Simulates pressing Ctrl-C when the first feasible solution is reported, and again when the compression phase starts
+    struct CtrlCPresser {
+        signal: CtrlCTerminator,
+        pressed: bool,
+    }
+
+    impl SolutionListener for CtrlCPresser {
+        fn report(&mut self, report: ReportType, _solution: &SPSolution, _instance: &SPInstance) -> Result<(), SparrowError> {
+            if report == ReportType::ExplFeas && !self.pressed {
+                self.signal.interrupt();
+                self.pressed = true;
+            }
+            Ok(())
+        }
+
+        fn enter_phase(&mut self, phase: Phase) {
+            if phase == Phase::Compression {
+                self.signal.interrupt();
+            }
+        }
+    }
+
+    #[test_case("swim.json"; "swim")]
+    fn simulate_ctrlc_abort(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (_, instance) = load_instance(path, &config)?;
+
+        // The time limits are never reached, only the Ctrl-C's can stop the optimization
+        let (expl_config, cmpr_config) = phase_configs(&config, NO_TIME_LIMIT, NO_TIME_LIMIT);
+
+        let mut terminator = CtrlCTerminator::detached();
+        let mut presser = CtrlCPresser { signal: terminator.clone(), pressed: false };
+
+        let rng = seeded_rng();
+        let solution = optimize(instance.clone(), rng, &mut presser, &mut terminator, &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip, None)?;
+
+        assert!(terminator.aborted());
+        assert_eq!(solution.layout_snapshot.placed_items.len(), instance.total_item_qty(), "the best feasible solution should be returned");
+        Ok(())
+    }
+
+    #[test_case("swim.json"; "swim")]
+    fn simulate_checkpoint_resume(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+
+        let (expl_config, cmpr_config) = phase_configs(&config, Duration::from_secs(3), Duration::from_secs(2));
+
+        let dir = tempfile::tempdir()?;
+        let checkpoint_path = dir.path().join("checkpoint.json");
+        let mut checkpointer = Checkpointer::new(checkpoint_path.clone(), Duration::from_secs(1), json_instance.clone(), config, 0, Duration::ZERO);
+
+        let rng = seeded_rng();
+        optimize(instance.clone(), rng, &mut checkpointer, &mut BasicTerminator::new(), &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip, None)?;
+
+        let checkpoint = read_checkpoint(&checkpoint_path)?;
+        assert!(checkpoint.elapsed > 0.0);
+        let state = checkpoint.resume_state(&instance);
+        let solution = resume(instance.clone(), state, &mut DummySolListener, &mut BasicTerminator::new(), &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip)?;
+
+        assert_eq!(solution.layout_snapshot.placed_items.len(), instance.total_item_qty(), "the resumed run should place all items");
+        Ok(())
+    }
+
+    #[test_case("swim.json"; "swim")]
+    fn checkpoint_resume_fixed_items(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+        let (a, b) = feasible_transformations(&json_instance, &instance)?;
+        let fixed = with_fixed_copies(&json_instance, &a, &b)?;
+        let fixed_instance = io::import_instance(&fixed, &config)?;
+
+        let (expl_config, cmpr_config) = phase_configs(&config, Duration::from_secs(3), Duration::from_secs(2));
+        let dir = tempfile::tempdir()?;
+        let checkpoint_path = dir.path().join("checkpoint.json");
+        let mut checkpointer = Checkpointer::new(checkpoint_path.clone(), Duration::from_secs(1), fixed.clone(), config, 0, Duration::ZERO);
+        optimize(fixed_instance.clone(), seeded_rng(), &mut checkpointer, &mut BasicTerminator::new(), &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip, None)?;
+
+        // the fixed items are locked again in the restored solutions
+        let checkpoint = read_checkpoint(&checkpoint_path)?;
+        assert_eq!(checkpoint.locked_placements.len(), 2);
+        let state = checkpoint.resume_state(&fixed_instance);
+        assert_eq!(state.current_sol.layout_snapshot.placed_items.values().filter(|pi| pi.is_locked).count(), 2);
+        let solution = resume(fixed_instance.clone(), state, &mut DummySolListener, &mut BasicTerminator::new(), &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip)?;
+
+        // and have not moved
+        let ext_solution = jagua_rs::probs::spp::io::export(&fixed_instance, &solution, *EPOCH);
+        let placed_at = |item_id: usize, transformation: &serde_json::Value| {
+            let (x, y) = (transformation["translation"][0].as_f64().unwrap(), transformation["translation"][1].as_f64().unwrap());
+            ext_solution.layout.placed_items.iter().any(|pi| pi.item_id as usize == item_id
+                && (pi.transformation.translation.0 as f64 - x).abs() < 1e-3
+                && (pi.transformation.translation.1 as f64 - y).abs() < 1e-3)
+        };
+        assert!(placed_at(fixed.items[0].base.id as usize, &a));
+        assert!(placed_at(fixed.items.len() - 1, &b));
+        Ok(())
+    }
+
+    /// Records a checkpoint at every iteration of the exploration phase, and cancels the run after `n` of them
+    struct CheckpointRecorder {
+        ext_instance: ExtSPInstance,
+        config: SparrowConfig,
+        n: usize,
+        checkpoints: Vec<ExtCheckpoint>,
+        terminator: CancellationTerminator,
+    }
+
+    impl SolutionListener for CheckpointRecorder {
+        fn report(&mut self, _report: ReportType, _solution: &SPSolution, _instance: &SPInstance) -> Result<(), SparrowError> {
+            Ok(())
+        }
+
+        fn checkpoint_due(&self) -> bool {
+            self.checkpoints.len() < self.n
+        }
+
+        fn checkpoint(&mut self, state: &PhaseState) {
+            if state.phase == Phase::Exploration {
+                self.checkpoints.push(ExtCheckpoint::new(state, &self.ext_instance, self.config, 0, Duration::ZERO));
+            }
+            if self.checkpoints.len() == self.n {
+                self.terminator.cancel();
+            }
+        }
+    }
+
+    #[test_case("swim.json"; "swim")]
+    fn checkpoint_resume_determinism(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+        // long enough for no iteration to be cut short, the runs are cancelled after two checkpoints
+        let (expl_config, cmpr_config) = phase_configs(&config, Duration::from_secs(600), Duration::from_secs(600));
+
+        let run = |state: Option<ResumeState>| -> Result<Vec<ExtCheckpoint>> {
+            let terminator = CancellationTerminator::new();
+            let mut recorder = CheckpointRecorder { ext_instance: json_instance.clone(), config, n: 2, checkpoints: vec![], terminator: terminator.clone() };
+            match state {
+                None => optimize(instance.clone(), seeded_rng(), &mut recorder, &mut terminator.clone(), &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip, None)?,
+                Some(state) => resume(instance.clone(), state, &mut recorder, &mut terminator.clone(), &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip)?,
+            };
+            Ok(recorder.checkpoints)
+        };
+
+        // An uninterrupted run, and a run resumed from its first checkpoint, must be in the same state at every following checkpoint
+        let original = run(None)?;
+        let resumed = run(Some(original[0].resume_state(&instance)))?;
+        assert_eq!((original.len(), resumed.len()), (2, 2));
+
+        let state = |c: &ExtCheckpoint| json!([c.rng, c.worker_rngs, c.n_failed_attempts, c.current_height, c.current_solution.strip_width, c.infeasible_pool.len()]);
+        assert_eq!(state(&resumed[0]), state(&original[0]), "resuming should restore the checkpointed state");
+        assert_eq!(state(&resumed[1]), state(&original[1]), "the resumed run should continue like the original one");
+        assert!(!original[0].worker_rngs.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn composed_terminators() {
+        let (first, second) = (CancellationTerminator::new(), CancellationTerminator::new());
+        let any_of = AnyOf(vec![Box::new(first.clone()), Box::new(second.clone())]);
+        let all_of = AllOf(vec![Box::new(first.clone()), Box::new(second.clone())]);
+        assert!(!any_of.kill() && !all_of.kill());
+
+        first.cancel();
+        assert!(any_of.kill());
+        assert!(!all_of.kill(), "the second terminator is still running");
+        second.cancel();
+        assert!(all_of.kill());
+        assert!(!AnyOf(vec![]).kill() && !AllOf(vec![]).kill());
+
+        // new timeouts are forwarded to all terminators
+        let deadline = DeadlineTerminator::after(NO_TIME_LIMIT);
+        let mut any_of = AnyOf(vec![Box::new(BasicTerminator::new()), Box::new(deadline.clone())]);
+        let mut all_of = AllOf(vec![Box::new(BasicTerminator::new()), Box::new(deadline.clone())]);
+        any_of.new_timeout(EXPLORE_TIMEOUT);
+        all_of.new_timeout(EXPLORE_TIMEOUT);
+        assert!(any_of.timeout_at().is_some_and(|t| t < deadline.deadline), "the earliest timeout should be reported");
+        assert_eq!(all_of.timeout_at(), Some(deadline.deadline), "the latest timeout should be reported");
+    }
+
+    #[test]
+    fn stall_terminator() {
+        let stall = Duration::from_secs(60);
+        let mut terminator = StallTerminator::new(stall, Progress::new());
+        let start = Instant::now();
+        assert!(!terminator.stalled_at(start));
+        assert!(terminator.stalled_at(start + 2 * stall), "stalled for too long");
+
+        // a new phase gives the optimization time to improve again
+        terminator.new_timeout(EXPLORE_TIMEOUT);
+        let phase_start = Instant::now();
+        assert!(!terminator.stalled_at(phase_start + stall / 2));
+        assert!(terminator.stalled_at(phase_start + 2 * stall));
+    }
+
+    #[test_case("swim.json"; "swim")]
+    fn simulate_target_density(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (_, instance) = load_instance(path, &config)?;
+
+        // without time limits, only reaching the target density can stop the optimization
+        let (expl_config, cmpr_config) = phase_configs(&config, NO_TIME_LIMIT, NO_TIME_LIMIT);
+
+        let target_density = 0.4;
+        let stop_criteria = StopCriteria { target_density: Some(target_density), ..StopCriteria::default() };
+        let (mut terminator, progress) = stop_criteria.build(BasicTerminator::new());
+
+        let rng = seeded_rng();
+        let solution = optimize(instance.clone(), rng, &mut progress.clone(), &mut terminator, &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip, None)?;
+
+        assert!(solution.density(&instance) >= target_density);
+        assert!(progress.unwrap().best_density() >= target_density);
+        Ok(())
+    }
+
+    /// Signals the first feasible solution of the exploration phase
+    struct FirstFeasible(std::sync::mpsc::Sender<()>);
+
+    impl SolutionListener for FirstFeasible {
+        fn report(&mut self, report: ReportType, _solution: &SPSolution, _instance: &SPInstance) -> Result<(), SparrowError> {
+            if report == ReportType::ExplFeas {
+                let _ = self.0.send(());
+            }
+            Ok(())
+        }
+    }
+
+    #[test_case("swim.json", true; "swim_cancel_handle")]
+    #[test_case("swim.json", false; "swim_cancel_terminator")]
+    fn simulate_background_cancel(path: &str, cancel_handle: bool) -> Result<()> {
+        let mut config = DEFAULT_SPARROW_CONFIG;
+        let (_, instance) = load_instance(path, &config)?;
+
+        // without time limits, only cancelling can stop the optimization
+        config.expl_cfg.time_limit = NO_TIME_LIMIT;
+        config.cmpr_cfg.time_limit = NO_TIME_LIMIT;
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let terminator = CancellationTerminator::new();
+        let rng = seeded_rng();
+        let handle = SparrowHandle::spawn(instance.clone(), rng, config, None, FirstFeasible(tx), terminator.clone());
+
+        rx.recv().expect("a feasible solution should be reported");
+        let best_so_far = handle.best_so_far();
+        match cancel_handle {
+            true => handle.cancel(),
+            false => terminator.cancel(),
+        }
+        let solution = handle.join().expect("optimization thread panicked")?;
+
+        assert!(best_so_far.is_some(), "a feasible solution should have been reported before cancelling");
+        assert_eq!(solution.layout_snapshot.placed_items.len(), instance.total_item_qty());
+        Ok(())
+    }
+
+    #[test_case("swim.json"; "swim")]
+    fn jsonl_progress_stream(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (_, instance) = load_instance(path, &config)?;
+
+        let (expl_config, cmpr_config) = phase_configs(&config, Duration::from_secs(2), Duration::from_secs(1));
+
+        let dir = tempfile::tempdir()?;
+        let progress_path = dir.path().join("progress.jsonl");
+        let mut listener = JsonlProgressListener::open(progress_path.to_str().unwrap(), true)?;
+
+        let rng = seeded_rng();
+        let mut terminator = BasicTerminator::new();
+        optimize(instance.clone(), rng, &mut listener, &mut terminator, &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip, None)?;
+
+        let events = std::fs::read_to_string(&progress_path)?.lines()
+            .map(serde_json::from_str::<io::ExtProgressEvent>)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let last = events.last().expect("at least the final solution should be reported");
+        assert_eq!(last.report, ReportType::Final);
+        assert_eq!(last.n_colliding_items, 0);
+        assert!(last.placements.is_some());
+        assert!(events.windows(2).all(|w| w[0].time <= w[1].time));
+        assert!(events.iter().all(|e| e.input.is_none()));
+
+        // Intermediate solutions are throttled
+        let intermediate = events.iter()
+            .filter(|e| matches!(e.report, ReportType::ExplInfeas | ReportType::ExplImproving))
+            .collect_vec();
+        let min_interval = JSONL_PROGRESS_MIN_INTERVAL.as_secs_f64() - 1e-3;
+        assert!(intermediate.windows(2).all(|w| w[1].time - w[0].time >= min_interval));
+
+        // Runs sharing a stream (batch mode) label their events with their input
+        let progress_path = dir.path().join("progress_shared.jsonl");
+        let listener = JsonlProgressListener::open(progress_path.to_str().unwrap(), false)?;
+        let solution = LBFBuilder::new(instance.clone(), seeded_rng(), DEFAULT_TUNING_CONFIG).construct()?.prob.save();
+        for input in ["a.json", "b.json"] {
+            listener.for_input(input).report(ReportType::Final, &solution, &instance)?;
+        }
+        let inputs = std::fs::read_to_string(&progress_path)?.lines()
+            .map(|l| serde_json::from_str::<io::ExtProgressEvent>(l).map(|e| e.input))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(inputs, [Some("a.json".to_string()), Some("b.json".to_string())]);
+        Ok(())
+    }
+
+    #[test_case("dxf_demo.json"; "dxf_demo")]
+    fn dxf_import(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+        assert_eq!(instance.total_item_qty(), 8);
+
+        let (expl_config, cmpr_config) = phase_configs(&config, Duration::from_secs(2), Duration::from_secs(1));
+
+        let rng = seeded_rng();
+        let solution = optimize(instance.clone(), rng, &mut DummySolListener, &mut BasicTerminator::new(), &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip, None)?;
+        assert_eq!(solution.layout_snapshot.placed_items.len(), instance.total_item_qty());
+
+        // Exported layout should contain the container and every placed item (and its holes)
+        let dxf = dxf::layout_to_dxf(&json_instance, &instance, &solution, config.dxf_import.units)?;
+        let contours = dxf::parse_dxf_contours(&dxf, &config.dxf_import)?;
+        assert!(contours.len() > solution.layout_snapshot.placed_items.len());
+
+        // The R12 header has no units variable, the units are only noted in a comment
+        let dxf_mm = dxf::layout_to_dxf(&json_instance, &instance, &solution, Some(DxfUnits::Millimeters))?;
+        assert!(dxf_mm.contains("AC1009") && !dxf_mm.contains("$INSUNITS"));
+        assert_eq!(dxf::parse_dxf_contours(&dxf_mm, &config.dxf_import)?, contours);
+        Ok(())
+    }
+
+    /// ASCII DXF file with the given header variables and entities
+    fn dxf_file(header: &[(i32, &str)], entities: &[(i32, &str)]) -> String {
+        [(0, "SECTION"), (2, "HEADER")].iter()
+            .chain(header)
+            .chain(&[(0, "ENDSEC"), (0, "SECTION"), (2, "ENTITIES")])
+            .chain(entities)
+            .chain(&[(0, "ENDSEC"), (0, "EOF")])
+            .map(|(code, value)| format!("{code}\n{value}\n"))
+            .collect()
+    }
+
+    #[test_case("1", 1.0; "wcs")]
+    #[test_case("-1", -1.0; "mirrored_ocs")]
+    fn dxf_bulge(extrusion_z: &str, x_sign: f64) -> Result<()> {
+        // 10 x 10 square with a semicircle bulging out of its right side
+        let dxf = dxf_file(&[], &[
+            (0, "LWPOLYLINE"), (90, "4"), (70, "1"), (210, "0"), (220, "0"), (230, extrusion_z),
+            (10, "0"), (20, "0"),
+            (10, "10"), (20, "0"), (42, "1"),
+            (10, "10"), (20, "10"),
+            (10, "0"), (20, "10"),
+        ]);
+        let config = DEFAULT_SPARROW_CONFIG.dxf_import;
+        let contours = dxf::parse_dxf_contours(&dxf, &config)?;
+        assert_eq!(contours.len(), 1);
+        // An extrusion direction of (0, 0, -1) mirrors the x-axis
+        let contour = contours[0].iter().map(|&(x, y)| (x * x_sign, y)).collect_vec();
+        let n = contour.len();
+        assert_eq!(contour[..2], [(0.0, 0.0), (10.0, 0.0)]);
+        assert_eq!(contour[n - 2..], [(10.0, 10.0), (0.0, 10.0)]);
+
+        // All points in between lie counterclockwise on the arc, which passes through (15, 5)
+        let arc = &contour[2..n - 2];
+        assert!(arc.len() > 1);
+        assert!(arc.iter().all(|&p| (dxf::dist(p, (10.0, 5.0)) - 5.0).abs() < 1e-9 && p.0 > 10.0), "{arc:?}");
+        assert!(arc.iter().tuple_windows().all(|(a, b)| b.1 > a.1), "{arc:?}");
+        let max_x = arc.iter().map(|p| p.0).fold(f64::MIN, f64::max);
+        assert!(15.0 - max_x <= config.flatten_tolerance as f64 + 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn dxf_bulge_degenerate_segment() -> Result<()> {
+        // 10 x 10 square with a repeated vertex, the zero-length segment between them has a bulge
+        let dxf = dxf_file(&[], &[
+            (0, "LWPOLYLINE"), (90, "5"), (70, "1"),
+            (10, "0"), (20, "0"),
+            (10, "10"), (20, "0"), (42, "1"),
+            (10, "10"), (20, "0"),
+            (10, "10"), (20, "10"),
+            (10, "0"), (20, "10"),
+        ]);
+        let contours = dxf::parse_dxf_contours(&dxf, &DEFAULT_SPARROW_CONFIG.dxf_import)?;
+        assert_eq!(contours.len(), 1);
+        assert!(contours[0].iter().all(|p| p.0.is_finite() && p.1.is_finite()), "{:?}", contours[0]);
+        assert!((dxf::signed_area(&contours[0]).abs() - 100.0).abs() < 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn dxf_entity_outside_xy_plane() {
+        let dxf = dxf_file(&[], &[(0, "CIRCLE"), (10, "0"), (20, "0"), (40, "1"), (210, "1"), (220, "0"), (230, "0")]);
+        assert!(dxf::parse_dxf_contours(&dxf, &DEFAULT_SPARROW_CONFIG.dxf_import).is_err());
+    }
+
+    #[test]
+    fn dxf_closed_spline() -> Result<()> {
+        // Closed linear B-spline along the sides of a 10 x 10 square, its first control point is repeated at the end
+        let dxf = dxf_file(&[], &[
+            (0, "SPLINE"), (70, "1"), (71, "1"), (72, "7"), (73, "5"),
+            (40, "0"), (40, "0"), (40, "1"), (40, "2"), (40, "3"), (40, "4"), (40, "4"),
+            (10, "0"), (20, "0"),
+            (10, "10"), (20, "0"),
+            (10, "10"), (20, "10"),
+            (10, "0"), (20, "10"),
+            (10, "0"), (20, "0"),
+        ]);
+        let contours = dxf::parse_dxf_contours(&dxf, &DEFAULT_SPARROW_CONFIG.dxf_import)?;
+        assert_eq!(contours.len(), 1);
+        let contour = &contours[0];
+        // Four samples per knot span, without repeating the first point
+        assert_eq!(contour.len(), 20, "{contour:?}");
+        for (i, corner) in [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)].into_iter().enumerate() {
+            assert!(dxf::dist(contour[5 * i], corner) < 1e-9, "{contour:?}");
+        }
+        assert!((dxf::signed_area(contour) - 100.0).abs() < 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    fn dxf_units() -> Result<()> {
+        // 1 x 2 inch rectangle
+        let dxf = dxf_file(&[(9, "$INSUNITS"), (70, "1")], &[
+            (0, "LWPOLYLINE"), (90, "4"), (70, "1"),
+            (10, "0"), (20, "0"),
+            (10, "1"), (20, "0"),
+            (10, "1"), (20, "2"),
+            (10, "0"), (20, "2"),
+        ]);
+        let config = DxfImportConfig { units: Some(DxfUnits::Millimeters), ..DEFAULT_SPARROW_CONFIG.dxf_import };
+        assert_eq!(dxf::parse_dxf_contours(&dxf, &config)?, vec![vec![(0.0, 0.0), (25.4, 0.0), (25.4, 50.8), (0.0, 50.8)]]);
+        // Without configured units, the coordinates are left untouched
+        assert_eq!(dxf::parse_dxf_contours(&dxf, &DEFAULT_SPARROW_CONFIG.dxf_import)?, vec![vec![(0.0, 0.0), (1.0, 0.0), (1.0, 2.0), (0.0, 2.0)]]);
+        Ok(())
+    }
+
+    #[test_case("swim.json"; "swim")]
+    fn placement_table(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+
+        let (expl_config, cmpr_config) = phase_configs(&config, Duration::from_secs(2), Duration::from_secs(1));
+
+        let rng = seeded_rng();
+        let solution = optimize(instance.clone(), rng, &mut DummySolListener, &mut BasicTerminator::new(), &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip, None)?;
+
+        let csv = placement_table::placements_to_csv(&json_instance, &instance, &solution)?;
+        let rows = csv.lines().map(|l| l.split(',').collect::<Vec<_>>()).collect::<Vec<_>>();
+        let n_columns = placement_table::PLACEMENT_TABLE_HEADER.split(',').count();
+        assert!(rows.iter().all(|r| r.len() == n_columns));
+        assert_eq!(rows.iter().filter(|r| r[0] == "item").count(), solution.layout_snapshot.placed_items.len());
+
+        // The first row is the first copy of the item with the lowest id, at the lowest x: it must match the JSON output
+        let ext_solution = jagua_rs::probs::spp::io::export(&instance, &solution, *EPOCH);
+        let ext_pi = ext_solution.layout.placed_items.iter()
+            .min_by(|a, b| a.item_id.cmp(&b.item_id).then(a.transformation.translation.0.total_cmp(&b.transformation.translation.0)))
+            .unwrap();
+        let row = &rows[1];
+        let column = |name: &str| row[placement_table::PLACEMENT_TABLE_HEADER.split(',').position(|c| c == name).unwrap()].parse::<f32>().unwrap();
+        assert_eq!(row[1], ext_pi.item_id.to_string());
+        assert_eq!((column("x"), column("y")), ext_pi.transformation.translation);
+        assert!((column("rotation") - ext_pi.transformation.rotation.to_degrees()).abs() < 1e-3);
+        let tol = 1e-3 * solution.strip.fixed_height;
+        assert!(column("bbox_x_min") >= -tol && column("bbox_x_max") <= solution.strip_width() + tol);
+        assert!(column("bbox_y_min") >= -tol && column("bbox_y_max") <= solution.strip.fixed_height + tol);
+        let ext_item = json_instance.items.iter().find(|i| i.base.id == ext_pi.item_id).unwrap();
+        let contours = dxf::ext_shape_contours(&serde_json::to_value(&ext_item.base.shape)?)?;
+        assert!((column("area") as f64 - dxf::signed_area(&contours[0]).abs()).abs() <= 1e-3 * column("area") as f64);
+        let width = rows.iter().find(|r| r[0] == "strip_width").unwrap()[n_columns - 1].parse::<f32>()?;
+        assert_eq!(width, solution.strip_width());
+        Ok(())
+    }
+
+    #[test_case("swim.json"; "swim")]
+    fn verify_exact(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+
+        let (expl_config, cmpr_config) = phase_configs(&config, Duration::from_secs(2), Duration::from_secs(1));
+
+        let rng = seeded_rng();
+        let solution = optimize(instance.clone(), rng, &mut DummySolListener, &mut BasicTerminator::new(), &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip, None)?;
+        let ext_solution = jagua_rs::probs::spp::io::export(&instance, &solution, *EPOCH);
+
+        let verify_config = VerifyConfig {
+            tolerance: json_instance.strip_height as f64 * DEFAULT_VERIFY_TOLERANCE_RATIO,
+            min_item_separation: None,
+            allow_partial: false,
+        };
+        let violations = verify_solution(&json_instance, &instance, &ext_solution, None, &verify_config)?;
+        assert!(violations.is_empty(), "{violations:?}");
+
+        // Stacking two copies of the same item must be detected
+        let mut overlapping = ext_solution.clone();
+        let placements = &mut overlapping.layout.placed_items;
+        let j = (1..placements.len()).find(|&j| placements[j].item_id == placements[0].item_id).unwrap();
+        placements[j].transformation = placements[0].transformation.clone();
+        let violations = verify_solution(&json_instance, &instance, &overlapping, None, &verify_config)?;
+        assert!(violations.iter().any(|v| matches!(v, Violation::Overlap { placements, depth, .. } if *placements == (0, j) && *depth > verify_config.tolerance)));
+
+        // Partially overlapping items must be detected as well
+        let mut penetrating = ext_solution.clone();
+        let placements = &mut penetrating.layout.placed_items;
+        placements[j].transformation = placements[0].transformation.clone();
+        placements[j].transformation.translation.0 += 0.1 * instance.item(placements[0].item_id as usize).shape_cd.diameter;
+        let violations = verify_solution(&json_instance, &instance, &penetrating, None, &verify_config)?;
+        assert!(violations.iter().any(|v| matches!(v, Violation::Overlap { placements, .. } if *placements == (0, j))), "{violations:?}");
+
+        // Moving an item below the container must be detected
+        let mut outside = ext_solution.clone();
+        outside.layout.placed_items[0].transformation.translation.1 -= json_instance.strip_height;
+        let violations = verify_solution(&json_instance, &instance, &outside, None, &verify_config)?;
+        assert!(violations.iter().any(|v| matches!(v, Violation::OutsideContainer { placement: 0, distance, .. } if *distance > verify_config.tolerance)));
+        Ok(())
+    }
+
+    #[test_case("shirts.json", ContainerMode::Square; "shirts_square")]
+    #[test_case("shirts.json", ContainerMode::FixedAspectRatio(2.0); "shirts_aspect_ratio")]
+    #[test_case("shirts.json", ContainerMode::FreeRectangle; "shirts_free_rectangle")]
+    fn verify_container_mode(path: &str, container_mode: ContainerMode) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+
+        let (expl_config, cmpr_config) = phase_configs(&config, Duration::from_secs(2), Duration::from_secs(1));
+        let solution = optimize(instance.clone(), seeded_rng(), &mut DummySolListener, &mut BasicTerminator::new(), &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, container_mode, None)?;
+        let ext_solution = jagua_rs::probs::spp::io::export(&instance, &solution, *EPOCH);
+
+        // The container of the output follows the mode, not the strip height of the instance
+        let container = ExtContainer::new(&solution, container_mode);
+
+        let verify_config = VerifyConfig {
+            tolerance: container.height as f64 * DEFAULT_VERIFY_TOLERANCE_RATIO,
+            min_item_separation: None,
+            allow_partial: false,
+        };
+        let violations = verify_solution(&json_instance, &instance, &ext_solution, Some(&container), &verify_config)?;
+        assert!(violations.is_empty(), "{violations:?}");
+
+        // Moving an item above the container must be detected
+        let mut outside = ext_solution.clone();
+        outside.layout.placed_items[0].transformation.translation.1 += container.height;
+        let violations = verify_solution(&json_instance, &instance, &outside, Some(&container), &verify_config)?;
+        assert!(violations.iter().any(|v| matches!(v, Violation::OutsideContainer { placement: 0, .. })), "{violations:?}");
+        Ok(())
+    }
+
+    #[test_case("swim.json", ContainerMode::Strip; "swim_strip")]
+    #[test_case("shirts.json", ContainerMode::Square; "shirts_square")]
+    #[test_case("shirts.json", ContainerMode::FixedAspectRatio(2.0); "shirts_fixed_aspect_ratio")]
+    fn lower_bound(path: &str, container_mode: ContainerMode) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+
+        let lower_bound = LowerBound::new(&json_instance, &instance, container_mode)?;
+        assert!(lower_bound.bound > 0.0 && lower_bound.bound >= lower_bound.item_width);
+
+        let (expl_config, cmpr_config) = phase_configs(&config, Duration::from_secs(2), Duration::from_secs(1));
+
+        let rng = seeded_rng();
+        let solution = optimize(instance.clone(), rng, &mut DummySolListener, &mut BasicTerminator::new(), &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, container_mode, None)?;
+        let gap = lower_bound.gap(&solution);
+        assert!((0.0..100.0).contains(&gap), "gap: {gap}");
+        Ok(())
+    }
+
+    #[test_case("shirts.json"; "shirts")]
+    fn lower_bound_fixed_aspect_ratio(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+
+        let square = LowerBound::new(&json_instance, &instance, ContainerMode::Square)?;
+        let ratio_one = LowerBound::new(&json_instance, &instance, ContainerMode::FixedAspectRatio(1.0))?;
+        assert!((square.bound - ratio_one.bound).abs() < 1e-3 * square.bound, "{} != {}", square.bound, ratio_one.bound);
+
+        // in a very wide container, the height of the tallest item bounds the width more than the area of the items
+        let ratio = 1000.0;
+        let wide = LowerBound::new(&json_instance, &instance, ContainerMode::FixedAspectRatio(ratio))?;
+        assert!(wide.bound > (wide.item_area * ratio).sqrt());
+        assert_eq!(wide.bound, wide.item_width);
+        Ok(())
+    }
+
+    #[test_case("swim.json", ContainerMode::Strip; "swim_strip")]
+    #[test_case("svg_demo.svg", ContainerMode::Square; "svg_demo_square")]
+    fn inspect(path: &str, container_mode: ContainerMode) -> Result<()> {
+        let mut config = DEFAULT_SPARROW_CONFIG;
+        config.container_mode = container_mode;
+        let (json_instance, instance) = load_instance(path, &config)?;
+
+        let report = inspect_instance(&json_instance, &instance, &config)?;
+        assert_eq!(report.items.len(), json_instance.items.len());
+        assert_eq!(report.total_demand, instance.total_item_qty());
+        assert!(report.items.iter().all(|i| i.fits && i.n_poles > 0 && i.convex_hull_ratio <= 1.0 + 1e-3));
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+
+        // A container too narrow for any item must be reported
+        config.knapsack = Some(KnapsackConfig { container_width: 1e-3, insertion_distribution_stddev: 0.1 });
+        let report = inspect_instance(&json_instance, &instance, &config)?;
+        assert!(report.items.iter().all(|i| !i.fits));
+        assert_eq!(report.warnings.len(), report.items.len());
+        Ok(())
+    }
+
+    #[test_case("swim.json"; "swim")]
+    fn fixed_placement_validation(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+        let (a, b) = feasible_transformations(&json_instance, &instance)?;
+
+        // Two copies of an item fixed where a feasible layout placed them are accepted, and kept where they are
+        let valid = with_fixed_copies(&json_instance, &a, &b)?;
+        let valid_instance = io::import_instance(&valid, &config)?;
+        let builder = LBFBuilder::new(valid_instance.clone(), seeded_rng(), DEFAULT_TUNING_CONFIG).construct()?;
+        let ext_sol = jagua_rs::probs::spp::io::export(&valid_instance, &builder.prob.save(), *EPOCH);
+        let tolerance = json_instance.strip_height as f64 * DEFAULT_VERIFY_TOLERANCE_RATIO;
+        let verify_config = VerifyConfig { tolerance, min_item_separation: None, allow_partial: false };
+        let violations = verify_solution(&valid, &valid_instance, &ext_sol, None, &verify_config)?;
+        assert!(violations.is_empty(), "{violations:?}");
+
+        let rejected = |ext_instance: &ExtSPInstance| -> Vec<Violation> {
+            let error = io::import_instance(ext_instance, &config).expect_err("invalid fixed placements should be rejected");
+            error.downcast_ref::<InvalidFixedPlacements>().expect("the violations should be part of the error").violations.clone()
+        };
+
+        // Both copies at the same place
+        let violations = rejected(&with_fixed_copies(&json_instance, &a, &a)?);
+        assert!(violations.iter().any(|v| matches!(v, Violation::Overlap { depth, .. } if *depth > tolerance)), "{violations:?}");
+
+        // A copy below the container
+        let mut outside = b.clone();
+        outside["translation"][1] = json!(b["translation"][1].as_f64().unwrap() - json_instance.strip_height as f64);
+        let violations = rejected(&with_fixed_copies(&json_instance, &a, &outside)?);
+        assert!(violations.iter().any(|v| matches!(v, Violation::OutsideContainer { placement: 1, .. })), "{violations:?}");
+
+        // A copy at a rotation which is not allowed
+        let mut rotated = b.clone();
+        rotated["rotation"] = json!(b["rotation"].as_f64().unwrap() + 0.1);
+        let violations = rejected(&with_fixed_copies(&json_instance, &a, &rotated)?);
+        assert!(violations.iter().any(|v| matches!(v, Violation::DisallowedRotation { .. })), "{violations:?}");
+        Ok(())
+    }
+
+    #[test_case("swim.json", ContainerMode::Strip, false; "swim_strip")]
+    #[test_case("swim.json", ContainerMode::FreeRectangle, false; "swim_free_rectangle")]
+    #[test_case("swim.json", ContainerMode::Square, true; "swim_square")]
+    #[test_case("swim.json", ContainerMode::FixedAspectRatio(0.5), true; "swim_aspect_ratio")]
+    fn fixed_placement_container_mode(path: &str, container_mode: ContainerMode, accepted: bool) -> Result<()> {
+        let mut config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+        let (a, b) = feasible_transformations(&json_instance, &instance)?;
+
+        // A copy above the strip height only fits a container whose height follows its width
+        let mut above = b.clone();
+        above["translation"][1] = json!(b["translation"][1].as_f64().unwrap() + json_instance.strip_height as f64);
+        config.container_mode = container_mode;
+        let result = io::import_instance(&with_fixed_copies(&json_instance, &a, &above)?, &config);
+        match accepted {
+            true => assert!(result.is_ok(), "{:?}", result.err()),
+            false => {
+                let violations = &result.expect_err("the copy lies above the container").downcast::<InvalidFixedPlacements>()?.violations;
+                assert!(violations.iter().any(|v| matches!(v, Violation::OutsideContainer { placement: 1, .. })), "{violations:?}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test_case("swim.json"; "swim")]
+    fn inspect_fixed_placements(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+        let (a, b) = feasible_transformations(&json_instance, &instance)?;
+
+        let valid = with_fixed_copies(&json_instance, &a, &b)?;
+        let report = inspect_instance(&valid, &io::import_instance_unchecked(&valid, &config)?, &config)?;
+        assert_eq!(report.items.iter().filter(|i| i.fixed_placement.is_some()).count(), 2);
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+
+        // Unsatisfiable fixed placements are reported instead of rejected
+        let overlapping = with_fixed_copies(&json_instance, &a, &a)?;
+        let report = inspect_instance(&overlapping, &io::import_instance_unchecked(&overlapping, &config)?, &config)?;
+        assert!(report.warnings.iter().any(|w| w.starts_with("fixed items:") && w.contains("overlap")), "{:?}", report.warnings);
+        Ok(())
+    }
+
+    #[test_case("M0 0 L10 0 L10 10 Z L5 5 L0 10 Z"; "absolute")]
+    #[test_case("M0 0 l10 0 l0 10 z l5 5 l-5 5 z"; "relative")]
+    fn svg_path_after_close(d: &str) -> Result<()> {
+        // A subpath following a close command without a move starts at the start of the closed subpath
+        let contours = sparrow::util::svg_import::path_contours(d, 0.01)?;
+        assert_eq!(contours, vec![
+            vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)],
+            vec![(0.0, 0.0), (5.0, 5.0), (0.0, 10.0)],
+        ]);
+        Ok(())
+    }
+
+    #[test_case("svg_demo.svg"; "svg_demo")]
+    fn svg_import(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (json_instance, instance) = load_instance(path, &config)?;
+        assert_eq!(json_instance.items.len(), 5);
+        assert_eq!(instance.total_item_qty(), 13);
+
+        let (expl_config, cmpr_config) = phase_configs(&config, Duration::from_secs(2), Duration::from_secs(1));
+
+        let rng = seeded_rng();
+        let solution = optimize(instance.clone(), rng, &mut DummySolListener, &mut BasicTerminator::new(), &expl_config, &cmpr_config, &DEFAULT_TUNING_CONFIG, ContainerMode::Strip, None)?;
+        assert_eq!(solution.layout_snapshot.placed_items.len(), instance.total_item_qty());
+        Ok(())
+    }
+
+    #[test_case("swim.json"; "swim")]
+    fn svg_intermediate_files(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (_, instance) = load_instance(path, &config)?;
+        let solution = LBFBuilder::new(instance.clone(), seeded_rng(), DEFAULT_TUNING_CONFIG).construct()?.prob.save();
+
+        let dir = tempfile::tempdir()?;
+        let intermediate_dir = dir.path().join("sols");
+        std::fs::create_dir(&intermediate_dir)?;
+        let final_path = dir.path().join("final.svg");
+        let mut exporter = SvgExporter::new(Some(final_path.display().to_string()), Some(intermediate_dir.display().to_string()), None);
+        exporter.intermediate_filter = vec![ReportType::ExplFeas, ReportType::Final];
+        exporter.max_intermediate_files = Some(2);
+
+        let reports = [ReportType::ExplInfeas, ReportType::ExplFeas, ReportType::ExplImproving, ReportType::ExplFeas, ReportType::CmprFeas, ReportType::Final];
+        for report in reports {
+            exporter.report(report, &solution, &instance)?;
+        }
+
+        // only the two most recent reports of the filtered types are kept
+        let file_names = std::fs::read_dir(&intermediate_dir)?
+            .map(|entry| entry.map(|e| e.file_name().to_string_lossy().into_owned()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        assert_eq!(file_names.len(), 2);
+        assert!(file_names.iter().any(|f| f.starts_with("1_") && f.ends_with("_expl_f.svg")), "{file_names:?}");
+        assert!(file_names.iter().any(|f| f.starts_with("2_") && f.ends_with("_final.svg")), "{file_names:?}");
+        assert!(final_path.exists());
+        Ok(())
+    }
+
+    #[test_case("swim.json"; "swim")]
+    fn svg_snapshot_write_failure(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let (_, instance) = load_instance(path, &config)?;
+        let solution = LBFBuilder::new(instance.clone(), seeded_rng(), DEFAULT_TUNING_CONFIG).construct()?.prob.save();
+
+        // the snapshot "directories" are regular files, so nothing can be written inside them
+        let dir = tempfile::tempdir()?;
+        let blocked = dir.path().join("blocked");
+        std::fs::write(&blocked, "")?;
+        let final_path = dir.path().join("final.svg");
+        let mut exporter = SvgExporter::new(
+            Some(final_path.display().to_string()),
+            Some(blocked.display().to_string()),
+            Some(blocked.join("live.svg").display().to_string()),
+        );
+
+        // failed snapshots do not abort the optimization, a failed final export does
+        exporter.report(ReportType::ExplFeas, &solution, &instance)?;
+        exporter.report(ReportType::Final, &solution, &instance)?;
+        assert!(final_path.exists());
+
+        exporter.final_path = Some(blocked.join("final.svg").display().to_string());
+        assert!(matches!(exporter.report(ReportType::Final, &solution, &instance), Err(SparrowError::Listener(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn svg_modes() {
+        let modes = [SvgMode::Final, SvgMode::Live];
+        assert!(SvgMode::enabled(&modes, SvgMode::Final));
+        assert!(SvgMode::enabled(&modes, SvgMode::Live));
+        assert!(!SvgMode::enabled(&modes, SvgMode::Intermediate), "modes are independent");
+        assert!(!SvgMode::enabled(&[SvgMode::Live, SvgMode::None], SvgMode::Live));
+    }
+}
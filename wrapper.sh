@@ -15,8 +15,7 @@ echo "Processing Task ${BATCH_TASK_INDEX}"
 echo "Params: Seed=${SEED_VAL}, Epochs=${EXPLORE_VAL}, Candidates=${COMPRESS_VAL}"
 
 # Run the binary with dynamic flags
-/app/sparrow -i "${INPUT_FILE}" -s "${SEED_VAL}" -e "${EXPLORE_VAL}" -c "${COMPRESS_VAL}"
-# Will fail if multiple files exist because cp expects 2 arguments (source, dest)
-cp /app/output/*.json "$OUTPUT_FILE" 2>/dev/null || echo "Error: Copy failed (0 or >1 files found)"
+/app/sparrow -i "${INPUT_FILE}" -s "${SEED_VAL}" -e "${EXPLORE_VAL}" -c "${COMPRESS_VAL}" \
+    --output-json "${OUTPUT_FILE}"
 
 echo "Done. Result saved to ${OUTPUT_FILE}"